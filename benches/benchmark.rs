@@ -3,7 +3,8 @@ extern crate criterion;
 
 use std::net::{Ipv4Addr, Ipv6Addr};
 use ip_network::{Ipv4Network, Ipv6Network, IpNetwork};
-use criterion::Criterion;
+use ip_network::lpm::LpmTable;
+use criterion::{black_box, Criterion};
 use std::str::FromStr;
 
 fn parse(c: &mut Criterion) {
@@ -84,5 +85,85 @@ fn collapse_addresses(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, parse, contains, collapse_addresses);
+/// Returns next value of xorshift generator and updates `state`.
+fn random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+fn longest_match(c: &mut Criterion) {
+    let mut state = 0x1234_5678;
+    let ipv4_networks: Vec<(Ipv4Network, usize)> = (0..10_000)
+        .map(|i| {
+            let address = Ipv4Addr::from(random(&mut state) as u32);
+            let netmask = 8 + (random(&mut state) % 25) as u8;
+            (Ipv4Network::new_truncate(address, netmask).unwrap(), i)
+        })
+        .collect();
+    let ipv6_networks: Vec<(Ipv6Network, usize)> = (0..10_000)
+        .map(|i| {
+            let address = u128::from(random(&mut state)) << 64 | 0x2000 << 112;
+            let netmask = 16 + (random(&mut state) % 49) as u8;
+            (
+                Ipv6Network::new_truncate(Ipv6Addr::from(address), netmask).unwrap(),
+                i,
+            )
+        })
+        .collect();
+    let ipv4_addresses: Vec<Ipv4Addr> = (0..1024)
+        .map(|_| Ipv4Addr::from(random(&mut state) as u32))
+        .collect();
+    let ipv6_addresses: Vec<Ipv6Addr> = (0..1024)
+        .map(|_| Ipv6Addr::from(u128::from(random(&mut state)) << 64 | 0x2000 << 112))
+        .collect();
+
+    let table: LpmTable<usize> = ipv4_networks
+        .iter()
+        .map(|(network, value)| (IpNetwork::from(*network), *value))
+        .chain(
+            ipv6_networks
+                .iter()
+                .map(|(network, value)| (IpNetwork::from(*network), *value)),
+        )
+        .collect();
+
+    c.bench_function("lpm ipv4 1024 lookups", |b| {
+        b.iter(|| {
+            for address in &ipv4_addresses {
+                black_box(table.longest_match_ipv4(*address));
+            }
+        })
+    });
+    c.bench_function("lpm ipv4 1024 batch lookups", |b| {
+        let mut results = vec![None; ipv4_addresses.len()];
+        b.iter(|| table.longest_match_ipv4_batch(&ipv4_addresses, &mut results))
+    });
+    c.bench_function("lpm ipv6 1024 lookups", |b| {
+        b.iter(|| {
+            for address in &ipv6_addresses {
+                black_box(table.longest_match_ipv6(*address));
+            }
+        })
+    });
+    c.bench_function("linear contains ipv4 1 lookup", |b| {
+        b.iter(|| {
+            ipv4_networks
+                .iter()
+                .filter(|(network, _)| network.contains(ipv4_addresses[0]))
+                .max_by_key(|(network, _)| network.netmask())
+        })
+    });
+    c.bench_function("linear contains ipv6 1 lookup", |b| {
+        b.iter(|| {
+            ipv6_networks
+                .iter()
+                .filter(|(network, _)| network.contains(ipv6_addresses[0]))
+                .max_by_key(|(network, _)| network.netmask())
+        })
+    });
+}
+
+criterion_group!(benches, parse, contains, collapse_addresses, longest_match);
 criterion_main!(benches);
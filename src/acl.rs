@@ -0,0 +1,374 @@
+//! Analysis of ordered access lists, where every rule permits or denies addresses in network.
+//!
+//! Rules are evaluated in order, the first rule containing address decides and addresses, that
+//! don't match any rule, are denied. [`analyze`] finds rules, that can never match, rules, that
+//! can be removed without changing the result, and computes all permitted addresses.
+//!
+//! # Examples
+//!
+//! ```
+//! use ip_network::IpNetwork;
+//! use ip_network::acl::analyze;
+//! use ip_network::prefix_list::Action;
+//!
+//! let rules = [
+//!     (Action::Deny, "10.0.0.0/8".parse::<IpNetwork>()?),
+//!     (Action::Permit, "10.1.0.0/16".parse()?),
+//!     (Action::Permit, "192.0.2.0/25".parse()?),
+//!     (Action::Permit, "192.0.2.128/25".parse()?),
+//! ];
+//! let analysis = analyze(&rules);
+//! assert_eq!(1, analysis.shadowed[0].rule);
+//! assert_eq!(vec![0], analysis.shadowed[0].by);
+//! assert_eq!(vec!["192.0.2.0/24".parse::<IpNetwork>()?], analysis.permitted);
+//! # Ok::<(), ip_network::IpNetworkParseError>(())
+//! ```
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use crate::prefix_list::Action;
+use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+/// Rule, that can never match, because all its addresses are matched by earlier rules.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Shadowed {
+    /// Index of shadowed rule.
+    pub rule: usize,
+    /// Indexes of earlier rules overlapping shadowed rule, that are not shadowed themselves.
+    pub by: Vec<usize>,
+}
+
+/// Rule, that can be removed without changing the result, because all addresses matched by this
+/// rule would get the same action from later rules or from implicit deny.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Redundant {
+    /// Index of redundant rule.
+    pub rule: usize,
+    /// Indexes of later rules, that would match addresses of redundant rule. When empty or when
+    /// some addresses are not matched by these rules, implicit deny applies.
+    pub by: Vec<usize>,
+}
+
+/// Result of access list analysis.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AclAnalysis {
+    /// Rules fully shadowed by earlier rules.
+    pub shadowed: Vec<Shadowed>,
+    /// Rules redundant with later rules, shadowed rules are not included.
+    pub redundant: Vec<Redundant>,
+    /// All permitted addresses as the smallest list of networks, IPv4 networks first.
+    pub permitted: Vec<IpNetwork>,
+}
+
+/// Analyzes ordered list of rules. Rules are identified by index in `rules`.
+pub fn analyze(rules: &[(Action, IpNetwork)]) -> AclAnalysis {
+    let mut shadowed = vec![];
+    let mut redundant = vec![];
+    let mut permitted = AddressSet::default();
+    // Addresses matched by rules processed so far.
+    let mut matched = AddressSet::default();
+
+    for (index, &(action, network)) in rules.iter().enumerate() {
+        // Addresses, for which this rule decides.
+        let mut effective = AddressSet::from(network);
+        effective.subtract_set(&matched);
+        if effective.is_empty() {
+            // Shadowed rules never match, so only rules, that can match, shadow this one.
+            let by = overlapping(&rules[..index], network)
+                .filter(|&other| {
+                    shadowed
+                        .binary_search_by_key(&other, |s: &Shadowed| s.rule)
+                        .is_err()
+                })
+                .collect();
+            shadowed.push(Shadowed { rule: index, by });
+            continue;
+        }
+        matched.union(network);
+        if action == Action::Permit {
+            permitted.union_set(&effective);
+        }
+
+        // Addresses falling through to later rules, when this rule is removed.
+        let mut remaining = effective;
+        let mut by = vec![];
+        let mut is_redundant = true;
+        for (later, &(later_action, later_network)) in rules.iter().enumerate().skip(index + 1) {
+            if remaining.is_empty() {
+                break;
+            }
+            if remaining.overlaps(later_network) {
+                if later_action != action {
+                    is_redundant = false;
+                    break;
+                }
+                by.push(later);
+                remaining.subtract(network_range(later_network), later_network.is_ipv6());
+            }
+        }
+        if is_redundant && (remaining.is_empty() || action == Action::Deny) {
+            redundant.push(Redundant { rule: index, by });
+        }
+    }
+
+    AclAnalysis {
+        shadowed,
+        redundant,
+        permitted: permitted.to_networks(),
+    }
+}
+
+fn overlapping(
+    rules: &[(Action, IpNetwork)],
+    network: IpNetwork,
+) -> impl Iterator<Item = usize> + '_ {
+    rules
+        .iter()
+        .enumerate()
+        .filter(move |(_, (_, other))| {
+            other.contains(network.network_address()) || network.contains(other.network_address())
+        })
+        .map(|(index, _)| index)
+}
+
+/// Returns first and last address of network as integers.
+fn network_range(network: IpNetwork) -> (u128, u128) {
+    match network {
+        IpNetwork::V4(network) => (
+            u32::from(network.network_address()).into(),
+            u32::from(network.broadcast_address()).into(),
+        ),
+        IpNetwork::V6(network) => (
+            network.network_address().into(),
+            network.last_address().into(),
+        ),
+    }
+}
+
+/// Set of addresses stored as sorted disjoint ranges of addresses for every family.
+#[derive(Clone, Debug, Default)]
+struct AddressSet {
+    ipv4: Vec<(u128, u128)>,
+    ipv6: Vec<(u128, u128)>,
+}
+
+impl From<IpNetwork> for AddressSet {
+    fn from(network: IpNetwork) -> Self {
+        let mut set = Self::default();
+        set.union(network);
+        set
+    }
+}
+
+impl AddressSet {
+    fn ranges_mut(&mut self, ipv6: bool) -> &mut Vec<(u128, u128)> {
+        if ipv6 {
+            &mut self.ipv6
+        } else {
+            &mut self.ipv4
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.ipv4.is_empty() && self.ipv6.is_empty()
+    }
+
+    fn overlaps(&self, network: IpNetwork) -> bool {
+        let (first, last) = network_range(network);
+        let ranges = if network.is_ipv6() {
+            &self.ipv6
+        } else {
+            &self.ipv4
+        };
+        ranges
+            .iter()
+            .any(|&(start, end)| start <= last && first <= end)
+    }
+
+    fn union(&mut self, network: IpNetwork) {
+        self.insert(network_range(network), network.is_ipv6());
+    }
+
+    fn union_set(&mut self, other: &AddressSet) {
+        for range in &other.ipv4 {
+            self.insert(*range, false);
+        }
+        for range in &other.ipv6 {
+            self.insert(*range, true);
+        }
+    }
+
+    fn insert(&mut self, (first, last): (u128, u128), ipv6: bool) {
+        let ranges = self.ranges_mut(ipv6);
+        // Merge with overlapping and adjacent ranges.
+        let (mut first, mut last) = (first, last);
+        ranges.retain(|&(start, end)| {
+            let touches = start <= last.saturating_add(1) && first <= end.saturating_add(1);
+            if touches {
+                first = first.min(start);
+                last = last.max(end);
+            }
+            !touches
+        });
+        let position = ranges.partition_point(|&(start, _)| start < first);
+        ranges.insert(position, (first, last));
+    }
+
+    fn subtract(&mut self, (first, last): (u128, u128), ipv6: bool) {
+        let ranges = self.ranges_mut(ipv6);
+        let mut output = Vec::with_capacity(ranges.len() + 1);
+        for &(start, end) in ranges.iter() {
+            if end < first || start > last {
+                output.push((start, end));
+                continue;
+            }
+            if start < first {
+                output.push((start, first - 1));
+            }
+            if end > last {
+                output.push((last + 1, end));
+            }
+        }
+        *ranges = output;
+    }
+
+    fn subtract_set(&mut self, other: &AddressSet) {
+        for range in &other.ipv4 {
+            self.subtract(*range, false);
+        }
+        for range in &other.ipv6 {
+            self.subtract(*range, true);
+        }
+    }
+
+    fn to_networks(&self) -> Vec<IpNetwork> {
+        let mut networks = vec![];
+        for &(first, last) in &self.ipv4 {
+            let first = Ipv4Addr::from(first as u32);
+            let last = Ipv4Addr::from(last as u32);
+            let summarized = Ipv4Network::summarize_address_range(first, last);
+            networks.extend(summarized.into_iter().map(IpNetwork::V4));
+        }
+        for &(first, last) in &self.ipv6 {
+            let summarized =
+                Ipv6Network::summarize_address_range(Ipv6Addr::from(first), Ipv6Addr::from(last));
+            networks.extend(summarized.into_iter().map(IpNetwork::V6));
+        }
+        networks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use super::{analyze, Redundant, Shadowed};
+    use crate::prefix_list::Action::{Deny, Permit};
+    use crate::IpNetwork;
+
+    #[test]
+    fn shadowed() {
+        let rules = [
+            (Permit, IpNetwork::from_str("10.0.0.0/9").unwrap()),
+            (Deny, IpNetwork::from_str("10.128.0.0/9").unwrap()),
+            (Deny, IpNetwork::from_str("10.1.0.0/16").unwrap()),
+            (Permit, IpNetwork::from_str("10.0.0.0/8").unwrap()),
+            (Permit, IpNetwork::from_str("2001:db8::/32").unwrap()),
+            (Deny, IpNetwork::from_str("2001:db8:1::/48").unwrap()),
+        ];
+        let analysis = analyze(&rules);
+        assert_eq!(
+            vec![
+                Shadowed {
+                    rule: 2,
+                    by: vec![0]
+                },
+                // Rule 2 is shadowed, so it is not listed.
+                Shadowed {
+                    rule: 3,
+                    by: vec![0, 1]
+                },
+                Shadowed {
+                    rule: 5,
+                    by: vec![4]
+                },
+            ],
+            analysis.shadowed
+        );
+        assert_eq!(
+            vec![
+                IpNetwork::from_str("10.0.0.0/9").unwrap(),
+                IpNetwork::from_str("2001:db8::/32").unwrap()
+            ],
+            analysis.permitted
+        );
+    }
+
+    #[test]
+    fn redundant() {
+        let rules = [
+            (Permit, IpNetwork::from_str("192.0.2.0/25").unwrap()),
+            (Deny, IpNetwork::from_str("198.51.100.0/24").unwrap()),
+            (Permit, IpNetwork::from_str("192.0.2.0/24").unwrap()),
+            (Permit, IpNetwork::from_str("203.0.113.0/25").unwrap()),
+            (Deny, IpNetwork::from_str("203.0.113.0/24").unwrap()),
+            (Permit, IpNetwork::from_str("203.0.113.0/24").unwrap()),
+        ];
+        let analysis = analyze(&rules);
+        // Rule 4 is not redundant, without it rule 5 would permit the rest of network.
+        assert!(analysis.shadowed.iter().any(|s| s.rule == 5));
+        assert_eq!(
+            vec![
+                Redundant {
+                    rule: 0,
+                    by: vec![2]
+                },
+                // Implicit deny.
+                Redundant {
+                    rule: 1,
+                    by: vec![]
+                },
+            ],
+            analysis.redundant
+        );
+        assert_eq!(
+            vec![
+                IpNetwork::from_str("192.0.2.0/24").unwrap(),
+                IpNetwork::from_str("203.0.113.0/25").unwrap()
+            ],
+            analysis.permitted
+        );
+    }
+
+    #[test]
+    fn not_redundant_with_partial_permit() {
+        // Later permit covers only part of addresses, the rest would be denied.
+        let rules = [
+            (Permit, IpNetwork::from_str("10.0.0.0/8").unwrap()),
+            (Permit, IpNetwork::from_str("10.0.0.0/9").unwrap()),
+        ];
+        let analysis = analyze(&rules);
+        assert!(analysis.redundant.is_empty());
+        assert_eq!(1, analysis.shadowed[0].rule);
+    }
+
+    #[test]
+    fn permitted_collapsed() {
+        let rules = [
+            (Deny, IpNetwork::from_str("0.0.0.0/32").unwrap()),
+            (Permit, IpNetwork::from_str("0.0.0.0/0").unwrap()),
+            (Deny, IpNetwork::from_str("::/1").unwrap()),
+            (Permit, IpNetwork::from_str("::/0").unwrap()),
+        ];
+        let analysis = analyze(&rules);
+        let permitted: Vec<String> = analysis.permitted.iter().map(|n| n.to_string()).collect();
+        assert_eq!(33, permitted.len());
+        assert_eq!("0.0.0.1/32", permitted[0]);
+        assert_eq!("128.0.0.0/1", permitted[31]);
+        assert_eq!("8000::/1", permitted[32]);
+
+        let analysis = analyze(&[(Permit, IpNetwork::from_str("::/0").unwrap())]);
+        assert_eq!(
+            vec![IpNetwork::from_str("::/0").unwrap()],
+            analysis.permitted
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use arbitrary::{size_hint, Arbitrary, Result, Unstructured};
+use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+impl<'a> Arbitrary<'a> for Ipv4Network {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let network_address = Ipv4Addr::from(u32::arbitrary(u)?);
+        let netmask = u.int_in_range(0..=Self::LENGTH)?;
+        Ok(Self::new_truncate(network_address, netmask).unwrap())
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and(u32::size_hint(depth), u8::size_hint(depth))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Ipv6Network {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let network_address = Ipv6Addr::from(u128::arbitrary(u)?);
+        let netmask = u.int_in_range(0..=Self::LENGTH)?;
+        Ok(Self::new_truncate(network_address, netmask).unwrap())
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and(u128::size_hint(depth), u8::size_hint(depth))
+    }
+}
+
+impl<'a> Arbitrary<'a> for IpNetwork {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        if u.arbitrary()? {
+            Ok(IpNetwork::V4(u.arbitrary()?))
+        } else {
+            Ok(IpNetwork::V6(u.arbitrary()?))
+        }
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and(
+            bool::size_hint(depth),
+            size_hint::or(Ipv4Network::size_hint(depth), Ipv6Network::size_hint(depth)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+    use arbitrary::{Arbitrary, Unstructured};
+    use crate::{IpNetwork, Ipv4Network};
+
+    #[test]
+    fn ipv4_network() {
+        let mut u = Unstructured::new(&[192, 168, 1, 1, 24]);
+        let network = Ipv4Network::arbitrary(&mut u).unwrap();
+        assert_eq!(Ipv4Addr::new(1, 1, 168, 0), network.network_address());
+        assert_eq!(24, network.netmask());
+    }
+
+    #[test]
+    fn valid_networks() {
+        let bytes: Vec<u8> = (0..=255).cycle().step_by(7).take(4096).collect();
+        let mut u = Unstructured::new(&bytes);
+        while !u.is_empty() {
+            let network = IpNetwork::arbitrary(&mut u).unwrap();
+            assert_eq!(
+                Ok(network),
+                IpNetwork::new(network.network_address(), network.netmask())
+            );
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Order-preserving binary encoding of networks, used for storing networks in databases without
+//! native network types.
+//!
+//! Network is encoded as family byte (`4` or `6`), network address octets in network byte order
+//! and netmask byte. Comparing encoded values byte by byte gives the same order as `Ord` for
+//! networks of the same family, IPv4 networks sort before IPv6 networks.
+//!
+//! All networks contained in network `N` are encoded in continuous range from `N` itself to
+//! the last address of `N` with maximal netmask, so containment can be checked by range query.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+use crate::postgres_common::CidrDecodeError;
+
+const IPV4_FAMILY: u8 = 4;
+const IPV6_FAMILY: u8 = 6;
+
+pub const IPV4_LENGTH: usize = 6;
+pub const IPV6_LENGTH: usize = 18;
+
+#[inline]
+pub fn to_binary_ipv4_network(network: &Ipv4Network) -> [u8; IPV4_LENGTH] {
+    encode_ipv4(network.network_address(), network.netmask())
+}
+
+#[inline]
+pub fn to_binary_ipv6_network(network: &Ipv6Network) -> [u8; IPV6_LENGTH] {
+    encode_ipv6(network.network_address(), network.netmask())
+}
+
+pub fn to_binary_ip_network(network: &IpNetwork) -> Vec<u8> {
+    match *network {
+        IpNetwork::V4(ref network) => to_binary_ipv4_network(network).to_vec(),
+        IpNetwork::V6(ref network) => to_binary_ipv6_network(network).to_vec(),
+    }
+}
+
+fn encode_ipv4(address: Ipv4Addr, netmask: u8) -> [u8; IPV4_LENGTH] {
+    let mut output = [IPV4_FAMILY, 0, 0, 0, 0, netmask];
+    output[1..5].copy_from_slice(&address.octets());
+    output
+}
+
+fn encode_ipv6(address: Ipv6Addr, netmask: u8) -> [u8; IPV6_LENGTH] {
+    let mut output = [0; IPV6_LENGTH];
+    output[0] = IPV6_FAMILY;
+    output[1..17].copy_from_slice(&address.octets());
+    output[17] = netmask;
+    output
+}
+
+fn check_length(raw: &[u8], expected: usize) -> Result<(), CidrDecodeError> {
+    if raw.len() != expected {
+        return Err(CidrDecodeError::BadLength {
+            expected,
+            actual: raw.len(),
+        });
+    }
+    Ok(())
+}
+
+pub fn from_binary_ipv4_network(raw: &[u8]) -> Result<Ipv4Network, CidrDecodeError> {
+    if let Some(&family) = raw.first() {
+        if family != IPV4_FAMILY {
+            return Err(CidrDecodeError::WrongFamily(family));
+        }
+    }
+    check_length(raw, IPV4_LENGTH)?;
+
+    let address = Ipv4Addr::new(raw[1], raw[2], raw[3], raw[4]);
+    Ok(Ipv4Network::new(address, raw[5])?)
+}
+
+pub fn from_binary_ipv6_network(raw: &[u8]) -> Result<Ipv6Network, CidrDecodeError> {
+    if let Some(&family) = raw.first() {
+        if family != IPV6_FAMILY {
+            return Err(CidrDecodeError::WrongFamily(family));
+        }
+    }
+    check_length(raw, IPV6_LENGTH)?;
+
+    let mut octets = [0; 16];
+    octets.copy_from_slice(&raw[1..17]);
+    Ok(Ipv6Network::new(Ipv6Addr::from(octets), raw[17])?)
+}
+
+pub fn from_binary_ip_network(raw: &[u8]) -> Result<IpNetwork, CidrDecodeError> {
+    match raw.first() {
+        Some(&IPV6_FAMILY) => Ok(IpNetwork::V6(from_binary_ipv6_network(raw)?)),
+        _ => Ok(IpNetwork::V4(from_binary_ipv4_network(raw)?)),
+    }
+}
+
+/// Returns first and last encoded value of networks contained in given network (including itself).
+pub fn contained_range(network: &IpNetwork) -> (Vec<u8>, Vec<u8>) {
+    match *network {
+        IpNetwork::V4(ref network) => (
+            to_binary_ipv4_network(network).to_vec(),
+            encode_ipv4(network.broadcast_address(), Ipv4Network::LENGTH).to_vec(),
+        ),
+        IpNetwork::V6(ref network) => (
+            to_binary_ipv6_network(network).to_vec(),
+            encode_ipv6(network.last_address(), Ipv6Network::LENGTH).to_vec(),
+        ),
+    }
+}
+
+/// Returns encoded supernets of given network, from the longest to the shortest netmask.
+pub fn supernets(network: &IpNetwork) -> Vec<Vec<u8>> {
+    match *network {
+        IpNetwork::V4(network) => {
+            let mut output = Vec::with_capacity(network.netmask() as usize);
+            let mut current = network.supernet();
+            while let Some(supernet) = current {
+                output.push(to_binary_ipv4_network(&supernet).to_vec());
+                current = supernet.supernet();
+            }
+            output
+        }
+        IpNetwork::V6(network) => {
+            let mut output = Vec::with_capacity(network.netmask() as usize);
+            let mut current = network.supernet();
+            while let Some(supernet) = current {
+                output.push(to_binary_ipv6_network(&supernet).to_vec());
+                current = supernet.supernet();
+            }
+            output
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn networks() -> Vec<IpNetwork> {
+        [
+            "0.0.0.0/0",
+            "10.0.0.0/8",
+            "10.0.0.0/16",
+            "10.0.0.0/24",
+            "10.0.1.0/24",
+            "10.1.0.0/16",
+            "10.255.255.255/32",
+            "11.0.0.0/8",
+            "192.168.0.0/16",
+            "::/0",
+            "2001:db8::/32",
+            "2001:db8::/64",
+            "2001:db8:0:1::/64",
+            "2001:db9::/32",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn roundtrip() {
+        for network in networks() {
+            let encoded = to_binary_ip_network(&network);
+            assert_eq!(network, from_binary_ip_network(&encoded).unwrap());
+        }
+    }
+
+    #[test]
+    fn order_is_preserved() {
+        let networks = networks();
+        let mut encoded: Vec<_> = networks.iter().map(to_binary_ip_network).collect();
+        encoded.sort();
+        let decoded: Vec<_> = encoded
+            .iter()
+            .map(|raw| from_binary_ip_network(raw).unwrap())
+            .collect();
+        assert_eq!(networks, decoded);
+    }
+
+    #[test]
+    fn containment_by_range() {
+        let networks = networks();
+        for network in &networks {
+            let (first, last) = contained_range(network);
+            for other in &networks {
+                let encoded = to_binary_ip_network(other);
+                let in_range = first <= encoded && encoded <= last;
+                let contained = match (network, other) {
+                    (IpNetwork::V4(a), IpNetwork::V4(b)) => {
+                        a.netmask() <= b.netmask() && a.contains(b.network_address())
+                    }
+                    (IpNetwork::V6(a), IpNetwork::V6(b)) => {
+                        a.netmask() <= b.netmask() && a.contains(b.network_address())
+                    }
+                    _ => false,
+                };
+                assert_eq!(contained, in_range, "{} in {}", other, network);
+            }
+        }
+    }
+
+    #[test]
+    fn supernets_of_network() {
+        let network: IpNetwork = "10.0.0.0/8".parse().unwrap();
+        let supernets = supernets(&network);
+        assert_eq!(8, supernets.len());
+        assert_eq!(vec![4, 10, 0, 0, 0, 7], supernets[0]);
+        assert_eq!(vec![4, 8, 0, 0, 0, 6], supernets[1]);
+        assert_eq!(vec![4, 0, 0, 0, 0, 0], supernets[7]);
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert_eq!(
+            Err(CidrDecodeError::BadLength {
+                expected: 6,
+                actual: 0
+            }),
+            from_binary_ip_network(&[])
+        );
+        assert_eq!(
+            Err(CidrDecodeError::WrongFamily(5)),
+            from_binary_ip_network(&[5, 0, 0, 0, 0, 0])
+        );
+        assert_eq!(
+            Err(CidrDecodeError::WrongFamily(6)),
+            from_binary_ipv4_network(&to_binary_ip_network(&"::/0".parse().unwrap()))
+        );
+        assert_eq!(
+            Err(CidrDecodeError::InvalidPrefix(33)),
+            from_binary_ipv4_network(&[4, 0, 0, 0, 0, 33])
+        );
+        assert_eq!(
+            Err(CidrDecodeError::HostBitsSet),
+            from_binary_ipv4_network(&[4, 10, 0, 0, 1, 8])
+        );
+    }
+}
@@ -0,0 +1,486 @@
+//! Parsing of text files with list of networks, for example blocklists.
+//!
+//! Every line contains one network in CIDR notation or single IP address, that is converted to
+//! network with netmask 32 or 128. IPv4 and IPv6 networks can be mixed. Text after `#` or `;` is
+//! comment, empty lines and lines with only comment are ignored. Text after network separated by
+//! whitespace is trailing field, that can be captured with [`ParseOptions::capture_trailing`].
+//!
+//! # Examples
+//!
+//! ```
+//! use ip_network::bulk::{ErrorPolicy, ParseOptions};
+//!
+//! let input = "\
+//! ; Blocklist
+//! 10.0.0.0/8 internal  ; added by admin
+//! 2001:db8::/32
+//! 192.0.2.1
+//! invalid
+//! ";
+//!
+//! let list = ParseOptions::new()
+//!     .error_policy(ErrorPolicy::Skip)
+//!     .capture_trailing(true)
+//!     .parse(input.as_bytes())?;
+//! assert_eq!(3, list.entries.len());
+//! assert_eq!(2, list.entries[0].line);
+//! assert_eq!(Some("internal"), list.entries[0].trailing.as_deref());
+//! assert_eq!("192.0.2.1/32", list.entries[2].network.to_string());
+//! assert_eq!(5, list.errors[0].line);
+//! # Ok::<(), ip_network::bulk::BulkParseError>(())
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::net::IpAddr;
+use std::str::{self, Utf8Error};
+use crate::{IpNetwork, IpNetworkParseError};
+
+/// What to do when line contains invalid network.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrorPolicy {
+    /// Stop parsing and return error.
+    Fail,
+    /// Skip invalid line, including line that is not valid UTF-8, and record error to
+    /// [`ParsedList::errors`].
+    Skip,
+}
+
+/// Options for parsing list of networks.
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    error_policy: ErrorPolicy,
+    capture_trailing: bool,
+    collapse: bool,
+}
+
+impl ParseOptions {
+    /// Creates default options, that fail on first invalid line, ignore trailing fields and
+    /// don't collapse networks.
+    pub fn new() -> Self {
+        Self {
+            error_policy: ErrorPolicy::Fail,
+            capture_trailing: false,
+            collapse: false,
+        }
+    }
+
+    /// Sets what to do with invalid lines.
+    pub fn error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// When enabled, text after network is stored to [`Entry::trailing`].
+    pub fn capture_trailing(mut self, capture_trailing: bool) -> Self {
+        self.capture_trailing = capture_trailing;
+        self
+    }
+
+    /// When enabled, parsed networks are collapsed to the smallest list of networks covering the
+    /// same addresses, see [`IpNetwork::collapse_addresses`]. Every collapsed entry has line
+    /// number of the first line, that it covers, and trailing field only when it wasn't merged
+    /// with other networks.
+    pub fn collapse(mut self, collapse: bool) -> Self {
+        self.collapse = collapse;
+        self
+    }
+
+    /// Parses list of networks from `reader`.
+    pub fn parse<R: BufRead>(&self, mut reader: R) -> Result<ParsedList, BulkParseError> {
+        let mut list = ParsedList {
+            entries: vec![],
+            errors: vec![],
+        };
+        let mut buffer = vec![];
+        for line_number in 1.. {
+            buffer.clear();
+            let read =
+                reader
+                    .read_until(b'\n', &mut buffer)
+                    .map_err(|error| BulkParseError::Io {
+                        line: line_number,
+                        error,
+                    })?;
+            if read == 0 {
+                break;
+            }
+            let line = strip_line_ending(&buffer);
+            let result = str::from_utf8(line)
+                .map_err(LineErrorKind::InvalidUtf8)
+                .and_then(|line| parse_line(line).map_err(LineErrorKind::Network));
+            match result {
+                Ok(None) => {}
+                Ok(Some((network, trailing))) => list.entries.push(Entry {
+                    line: line_number,
+                    network,
+                    trailing: trailing
+                        .filter(|_| self.capture_trailing)
+                        .map(str::to_owned),
+                }),
+                Err(error) => {
+                    let error = LineError {
+                        line: line_number,
+                        error,
+                    };
+                    match self.error_policy {
+                        ErrorPolicy::Fail => return Err(BulkParseError::Line(error)),
+                        ErrorPolicy::Skip => list.errors.push(error),
+                    }
+                }
+            }
+        }
+        if self.collapse {
+            list.entries = collapse(list.entries);
+        }
+        Ok(list)
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses list of networks from `reader` with default options, so parsing fails on the first
+/// invalid line.
+pub fn parse_prefix_list<R: BufRead>(reader: R) -> Result<ParsedList, BulkParseError> {
+    ParseOptions::new().parse(reader)
+}
+
+/// Removes `\n` or `\r\n` from the end of line.
+fn strip_line_ending(line: &[u8]) -> &[u8] {
+    match line {
+        [line @ .., b'\r', b'\n'] | [line @ .., b'\n'] => line,
+        line => line,
+    }
+}
+
+/// Returns network and trailing field from line or `None` if line doesn't contain network.
+fn parse_line(line: &str) -> Result<Option<(IpNetwork, Option<&str>)>, IpNetworkParseError> {
+    let line = match line.find(['#', ';']) {
+        Some(position) => &line[..position],
+        None => line,
+    };
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    let (network, trailing) = match line.find(char::is_whitespace) {
+        Some(position) => (&line[..position], Some(line[position..].trim_start())),
+        None => (line, None),
+    };
+    let network = if network.contains('/') {
+        network.parse()?
+    } else {
+        match network.parse::<IpAddr>() {
+            Ok(address) => IpNetwork::from(address),
+            Err(_) => {
+                return Err(IpNetworkParseError::AddrParseError {
+                    span: 0..network.len(),
+                    family: None,
+                })
+            }
+        }
+    };
+    Ok(Some((network, trailing)))
+}
+
+/// Replaces entries with collapsed networks.
+fn collapse(mut entries: Vec<Entry>) -> Vec<Entry> {
+    let networks: Vec<IpNetwork> = entries.iter().map(|entry| entry.network).collect();
+    let collapsed = IpNetwork::collapse_addresses(&networks);
+
+    // Both lists are sorted, so all entries covered by collapsed network follow each other.
+    entries.sort_by(|a, b| a.network.cmp(&b.network).then(a.line.cmp(&b.line)));
+    let mut entries = entries.into_iter().peekable();
+    collapsed
+        .into_iter()
+        .map(|network| {
+            let mut covered = vec![];
+            while let Some(entry) = entries.next_if(|entry| {
+                entry.network.netmask() >= network.netmask()
+                    && network.contains(entry.network.network_address())
+            }) {
+                covered.push(entry);
+            }
+            let line = covered.iter().map(|entry| entry.line).min().unwrap_or(0);
+            let trailing = match covered.as_mut_slice() {
+                [entry] if entry.network == network => entry.trailing.take(),
+                _ => None,
+            };
+            Entry {
+                line,
+                network,
+                trailing,
+            }
+        })
+        .collect()
+}
+
+/// Result of parsing list of networks.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParsedList {
+    /// Parsed networks in the same order as in input or sorted when collapsed.
+    pub entries: Vec<Entry>,
+    /// Skipped invalid lines, when errors are skipped.
+    pub errors: Vec<LineError>,
+}
+
+impl ParsedList {
+    /// Returns parsed networks without line numbers and trailing fields.
+    pub fn networks(&self) -> Vec<IpNetwork> {
+        self.entries.iter().map(|entry| entry.network).collect()
+    }
+}
+
+/// Network parsed from one line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    /// Line number starting from 1.
+    pub line: usize,
+    /// Parsed network.
+    pub network: IpNetwork,
+    /// Text after network, if it is captured.
+    pub trailing: Option<String>,
+}
+
+/// Error in one line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineError {
+    /// Line number starting from 1.
+    pub line: usize,
+    /// Reason why line is invalid.
+    pub error: LineErrorKind,
+}
+
+impl Error for LineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "line {}: {}", self.line, self.error)
+    }
+}
+
+/// Reason why line is invalid.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum LineErrorKind {
+    /// Error when parsing network, span is relative to network in line.
+    Network(IpNetworkParseError),
+    /// Line is not valid UTF-8.
+    InvalidUtf8(Utf8Error),
+}
+
+impl Error for LineErrorKind {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            LineErrorKind::Network(ref error) => Some(error),
+            LineErrorKind::InvalidUtf8(ref error) => Some(error),
+        }
+    }
+}
+
+impl fmt::Display for LineErrorKind {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LineErrorKind::Network(ref error) => write!(fmt, "{}", error),
+            LineErrorKind::InvalidUtf8(ref error) => write!(fmt, "invalid UTF-8: {}", error),
+        }
+    }
+}
+
+/// Errors when parsing list of networks.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BulkParseError {
+    /// Reading from reader failed.
+    Io {
+        /// Line number starting from 1.
+        line: usize,
+        /// Error returned by reader.
+        error: io::Error,
+    },
+    /// Line contains invalid network or is not valid UTF-8 and errors are not skipped.
+    Line(LineError),
+}
+
+impl Error for BulkParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            BulkParseError::Io { ref error, .. } => Some(error),
+            BulkParseError::Line(ref error) => Some(error),
+        }
+    }
+}
+
+impl fmt::Display for BulkParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BulkParseError::Io { line, ref error } => {
+                write!(fmt, "reading line {} failed: {}", line, error)
+            }
+            BulkParseError::Line(ref error) => write!(fmt, "{}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use super::{parse_prefix_list, BulkParseError, Entry, ErrorPolicy, LineError, LineErrorKind};
+    use super::ParseOptions;
+    use crate::{IpNetwork, IpNetworkError, IpNetworkParseError};
+
+    #[test]
+    fn comments_and_blank_lines() {
+        let input =
+            "# comment\n\n  ; other comment\n10.0.0.0/8 # inline\r\n\t2001:db8::/32;x\n::1\n";
+        let list = parse_prefix_list(input.as_bytes()).unwrap();
+        assert_eq!(
+            vec![
+                IpNetwork::from_str("10.0.0.0/8").unwrap(),
+                IpNetwork::from_str("2001:db8::/32").unwrap(),
+                IpNetwork::from_str("::1/128").unwrap()
+            ],
+            list.networks()
+        );
+        assert_eq!(
+            vec![4, 5, 6],
+            list.entries.iter().map(|e| e.line).collect::<Vec<_>>()
+        );
+        assert!(list.errors.is_empty());
+    }
+
+    #[test]
+    fn trailing() {
+        let input = "10.0.0.0/8   spam  source ; comment\n192.0.2.0/24\n";
+        let list = ParseOptions::new()
+            .capture_trailing(true)
+            .parse(input.as_bytes())
+            .unwrap();
+        assert_eq!(Some("spam  source"), list.entries[0].trailing.as_deref());
+        assert_eq!(None, list.entries[1].trailing);
+
+        let list = parse_prefix_list(input.as_bytes()).unwrap();
+        assert_eq!(None, list.entries[0].trailing);
+    }
+
+    #[test]
+    fn fail() {
+        let input = "10.0.0.0/8\n10.0.0.1/8\n";
+        match parse_prefix_list(input.as_bytes()) {
+            Err(BulkParseError::Line(error)) => {
+                assert_eq!(2, error.line);
+                assert_eq!(
+                    LineErrorKind::Network(IpNetworkParseError::IpNetworkError {
+                        error: IpNetworkError::HostBitsSet {
+                            truncated: IpNetwork::from_str("10.0.0.0/8").unwrap()
+                        },
+                        span: 0..8,
+                    }),
+                    error.error
+                );
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn skip() {
+        let input = "invalid\n10.0.0.0/8\n10.0.0.0/33\n\u{0}\n";
+        let list = ParseOptions::new()
+            .error_policy(ErrorPolicy::Skip)
+            .parse(input.as_bytes())
+            .unwrap();
+        assert_eq!(
+            vec![IpNetwork::from_str("10.0.0.0/8").unwrap()],
+            list.networks()
+        );
+        assert_eq!(
+            vec![1, 3, 4],
+            list.errors.iter().map(|e| e.line).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "line 1: invalid IP address syntax at position 0",
+            list.errors[0].to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let input: &[u8] = b"10.0.0.0/8\n\xff\r\n192.0.2.0/24";
+        let list = ParseOptions::new()
+            .error_policy(ErrorPolicy::Skip)
+            .parse(input)
+            .unwrap();
+        assert_eq!(
+            vec![
+                IpNetwork::from_str("10.0.0.0/8").unwrap(),
+                IpNetwork::from_str("192.0.2.0/24").unwrap()
+            ],
+            list.networks()
+        );
+        assert_eq!(2, list.errors[0].line);
+        assert!(matches!(
+            list.errors[0].error,
+            LineErrorKind::InvalidUtf8(_)
+        ));
+        assert_eq!(
+            "line 2: invalid UTF-8: invalid utf-8 sequence of 1 bytes from index 0",
+            list.errors[0].to_string()
+        );
+
+        let error = parse_prefix_list(input).unwrap_err();
+        assert!(matches!(
+            error,
+            BulkParseError::Line(LineError {
+                line: 2,
+                error: LineErrorKind::InvalidUtf8(_),
+            })
+        ));
+    }
+
+    #[test]
+    fn collapse() {
+        let input = "\
+10.0.1.0/24 b
+2001:db8::/32 c
+10.0.0.0/24 a
+192.0.2.0/24 d
+10.0.0.0/25 e
+";
+        let list = ParseOptions::new()
+            .capture_trailing(true)
+            .collapse(true)
+            .parse(input.as_bytes())
+            .unwrap();
+        assert_eq!(
+            vec![
+                Entry {
+                    line: 1,
+                    network: IpNetwork::from_str("10.0.0.0/23").unwrap(),
+                    trailing: None,
+                },
+                Entry {
+                    line: 4,
+                    network: IpNetwork::from_str("192.0.2.0/24").unwrap(),
+                    trailing: Some("d".to_owned()),
+                },
+                Entry {
+                    line: 2,
+                    network: IpNetwork::from_str("2001:db8::/32").unwrap(),
+                    trailing: Some("c".to_owned()),
+                },
+            ],
+            list.entries
+        );
+    }
+}
@@ -0,0 +1,591 @@
+//! Encoding of networks and interfaces to CBOR according to [RFC 9164].
+//!
+//! IPv4 values are tagged with tag 52 and IPv6 values with tag 54. Networks are encoded in prefix
+//! form `[prefix, address]`, where trailing zero bytes of address are omitted, and interfaces in
+//! interface form `[address, prefix]` with full address.
+//!
+//! # Examples
+//!
+//! ```
+//! use ip_network::Ipv4Network;
+//! use ip_network::cbor::Cbor;
+//!
+//! let network: Ipv4Network = "192.0.2.0/24".parse()?;
+//! let encoded = network.to_cbor();
+//! assert_eq!(encoded, [0xd8, 0x34, 0x82, 0x18, 0x18, 0x43, 0xc0, 0x00, 0x02]);
+//! assert_eq!(Ipv4Network::from_cbor(&encoded)?, network);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [RFC 9164]: https://www.rfc-editor.org/rfc/rfc9164.html
+
+use std::error::Error;
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use crate::{IpInterface, IpNetwork, IpNetworkError, Ipv4Interface, Ipv4Network, Ipv6Interface};
+use crate::Ipv6Network;
+
+/// CBOR tag for IPv4 address, prefix or interface.
+pub const IPV4_TAG: u64 = 52;
+/// CBOR tag for IPv6 address, prefix or interface.
+pub const IPV6_TAG: u64 = 54;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_TAG: u8 = 6;
+
+/// Errors when decoding CBOR representation of networks or interfaces.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum CborDecodeError {
+    /// Input ended before the whole item was decoded.
+    UnexpectedEnd,
+    /// Input contains data after decoded item.
+    TrailingData,
+    /// Item is not tagged with IPv4 or IPv6 tag or the tag is not the expected one.
+    WrongTag(u64),
+    /// Item has unexpected CBOR type or structure, for example address instead of prefix.
+    UnexpectedItem,
+    /// Address byte string has invalid length.
+    BadAddressLength(usize),
+    /// Prefix is bigger than possible for address family.
+    InvalidPrefix(u64),
+    /// Address in prefix form has trailing zero bytes, that must be omitted.
+    NonCanonical,
+    /// Host bits are set in network address.
+    HostBitsSet,
+}
+
+impl Error for CborDecodeError {}
+
+impl fmt::Display for CborDecodeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CborDecodeError::UnexpectedEnd => write!(fmt, "CBOR input ended unexpectedly"),
+            CborDecodeError::TrailingData => write!(fmt, "CBOR input has trailing data"),
+            CborDecodeError::WrongTag(tag) => write!(fmt, "CBOR item has unexpected tag '{}'", tag),
+            CborDecodeError::UnexpectedItem => write!(fmt, "CBOR item has unexpected structure"),
+            CborDecodeError::BadAddressLength(length) => {
+                write!(fmt, "CBOR address has bad length '{}'", length)
+            }
+            CborDecodeError::InvalidPrefix(prefix) => {
+                write!(fmt, "CBOR item has invalid prefix '{}'", prefix)
+            }
+            CborDecodeError::NonCanonical => {
+                write!(fmt, "CBOR prefix address has trailing zero bytes")
+            }
+            CborDecodeError::HostBitsSet => write!(fmt, "CBOR prefix has host bits set"),
+        }
+    }
+}
+
+impl From<IpNetworkError> for CborDecodeError {
+    fn from(error: IpNetworkError) -> Self {
+        match error {
+            IpNetworkError::NetmaskError { netmask, .. } => {
+                CborDecodeError::InvalidPrefix(netmask.into())
+            }
+            IpNetworkError::HostBitsSet { .. } => CborDecodeError::HostBitsSet,
+        }
+    }
+}
+
+/// Encoding to and decoding from tagged CBOR item defined by RFC 9164.
+pub trait Cbor: Sized {
+    /// Encodes value to tagged CBOR item.
+    fn to_cbor(&self) -> Vec<u8>;
+
+    /// Decodes value from tagged CBOR item. Input must contain exactly one item.
+    fn from_cbor(raw: &[u8]) -> Result<Self, CborDecodeError>;
+}
+
+/// Content of tagged item.
+enum Item<'a> {
+    /// Bare address, that is not accepted by any type.
+    Address,
+    Prefix(u64, &'a [u8]),
+    Interface(&'a [u8], u64),
+}
+
+fn write_head(output: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    if value < 24 {
+        output.push(major | value as u8);
+    } else if value <= u64::from(u8::MAX) {
+        output.extend_from_slice(&[major | 24, value as u8]);
+    } else if value <= u64::from(u16::MAX) {
+        output.push(major | 25);
+        output.extend_from_slice(&(value as u16).to_be_bytes());
+    } else if value <= u64::from(u32::MAX) {
+        output.push(major | 26);
+        output.extend_from_slice(&(value as u32).to_be_bytes());
+    } else {
+        output.push(major | 27);
+        output.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn write_bytes(output: &mut Vec<u8>, bytes: &[u8]) {
+    write_head(output, MAJOR_BYTES, bytes.len() as u64);
+    output.extend_from_slice(bytes);
+}
+
+fn encode_prefix(tag: u64, prefix: u8, octets: &[u8]) -> Vec<u8> {
+    let length = octets
+        .iter()
+        .rposition(|&byte| byte != 0)
+        .map_or(0, |i| i + 1);
+    let mut output = Vec::with_capacity(octets.len() + 6);
+    write_head(&mut output, MAJOR_TAG, tag);
+    write_head(&mut output, MAJOR_ARRAY, 2);
+    write_head(&mut output, MAJOR_UNSIGNED, prefix.into());
+    write_bytes(&mut output, &octets[..length]);
+    output
+}
+
+fn encode_interface(tag: u64, octets: &[u8], prefix: u8) -> Vec<u8> {
+    let mut output = Vec::with_capacity(octets.len() + 6);
+    write_head(&mut output, MAJOR_TAG, tag);
+    write_head(&mut output, MAJOR_ARRAY, 2);
+    write_bytes(&mut output, octets);
+    write_head(&mut output, MAJOR_UNSIGNED, prefix.into());
+    output
+}
+
+struct Reader<'a> {
+    raw: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], CborDecodeError> {
+        if self.raw.len() < length {
+            return Err(CborDecodeError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.raw.split_at(length);
+        self.raw = rest;
+        Ok(taken)
+    }
+
+    fn peek_major(&self) -> Result<u8, CborDecodeError> {
+        self.raw
+            .first()
+            .map(|byte| byte >> 5)
+            .ok_or(CborDecodeError::UnexpectedEnd)
+    }
+
+    fn head(&mut self, expected_major: u8) -> Result<u64, CborDecodeError> {
+        let initial = self.take(1)?[0];
+        if initial >> 5 != expected_major {
+            return Err(CborDecodeError::UnexpectedItem);
+        }
+        let length = match initial & 0x1f {
+            value @ 0..=23 => return Ok(value.into()),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            // Reserved values and indefinite length items are not valid here.
+            _ => return Err(CborDecodeError::UnexpectedItem),
+        };
+        let mut bytes = [0; 8];
+        bytes[8 - length..].copy_from_slice(self.take(length)?);
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], CborDecodeError> {
+        let length = self.head(MAJOR_BYTES)?;
+        if length > self.raw.len() as u64 {
+            return Err(CborDecodeError::UnexpectedEnd);
+        }
+        self.take(length as usize)
+    }
+}
+
+fn decode(raw: &[u8]) -> Result<(u64, Item<'_>), CborDecodeError> {
+    let mut reader = Reader { raw };
+    let tag = reader.head(MAJOR_TAG)?;
+    if tag != IPV4_TAG && tag != IPV6_TAG {
+        return Err(CborDecodeError::WrongTag(tag));
+    }
+
+    let item = match reader.peek_major()? {
+        MAJOR_BYTES => {
+            reader.bytes()?;
+            Item::Address
+        }
+        MAJOR_ARRAY => {
+            if reader.head(MAJOR_ARRAY)? != 2 {
+                return Err(CborDecodeError::UnexpectedItem);
+            }
+            match reader.peek_major()? {
+                MAJOR_UNSIGNED => {
+                    let prefix = reader.head(MAJOR_UNSIGNED)?;
+                    Item::Prefix(prefix, reader.bytes()?)
+                }
+                _ => {
+                    let address = reader.bytes()?;
+                    Item::Interface(address, reader.head(MAJOR_UNSIGNED)?)
+                }
+            }
+        }
+        _ => return Err(CborDecodeError::UnexpectedItem),
+    };
+
+    if !reader.raw.is_empty() {
+        return Err(CborDecodeError::TrailingData);
+    }
+    Ok((tag, item))
+}
+
+/// Checks prefix form and copies address bytes to `octets`, returns prefix.
+fn decode_prefix(prefix: u64, address: &[u8], octets: &mut [u8]) -> Result<u8, CborDecodeError> {
+    let max_prefix = octets.len() as u64 * 8;
+    if prefix > max_prefix {
+        return Err(CborDecodeError::InvalidPrefix(prefix));
+    }
+    if address.last() == Some(&0) {
+        return Err(CborDecodeError::NonCanonical);
+    }
+    if address.len() > octets.len() {
+        return Err(CborDecodeError::BadAddressLength(address.len()));
+    }
+    // Non-zero bytes after prefix.
+    if address.len() as u64 > (prefix + 7) / 8 {
+        return Err(CborDecodeError::HostBitsSet);
+    }
+    octets[..address.len()].copy_from_slice(address);
+    Ok(prefix as u8)
+}
+
+/// Checks interface form and copies address bytes to `octets`, returns prefix.
+fn decode_interface(address: &[u8], prefix: u64, octets: &mut [u8]) -> Result<u8, CborDecodeError> {
+    if address.len() != octets.len() {
+        return Err(CborDecodeError::BadAddressLength(address.len()));
+    }
+    if prefix > octets.len() as u64 * 8 {
+        return Err(CborDecodeError::InvalidPrefix(prefix));
+    }
+    octets.copy_from_slice(address);
+    Ok(prefix as u8)
+}
+
+fn decode_ipv4_network(item: Item<'_>) -> Result<Ipv4Network, CborDecodeError> {
+    match item {
+        Item::Prefix(prefix, address) => {
+            let mut octets = [0; 4];
+            let prefix = decode_prefix(prefix, address, &mut octets)?;
+            Ok(Ipv4Network::new(Ipv4Addr::from(octets), prefix)?)
+        }
+        _ => Err(CborDecodeError::UnexpectedItem),
+    }
+}
+
+fn decode_ipv6_network(item: Item<'_>) -> Result<Ipv6Network, CborDecodeError> {
+    match item {
+        Item::Prefix(prefix, address) => {
+            let mut octets = [0; 16];
+            let prefix = decode_prefix(prefix, address, &mut octets)?;
+            Ok(Ipv6Network::new(Ipv6Addr::from(octets), prefix)?)
+        }
+        _ => Err(CborDecodeError::UnexpectedItem),
+    }
+}
+
+fn decode_ipv4_interface(item: Item<'_>) -> Result<Ipv4Interface, CborDecodeError> {
+    match item {
+        Item::Interface(address, prefix) => {
+            let mut octets = [0; 4];
+            let prefix = decode_interface(address, prefix, &mut octets)?;
+            Ok(Ipv4Interface::new(Ipv4Addr::from(octets), prefix)?)
+        }
+        _ => Err(CborDecodeError::UnexpectedItem),
+    }
+}
+
+fn decode_ipv6_interface(item: Item<'_>) -> Result<Ipv6Interface, CborDecodeError> {
+    match item {
+        Item::Interface(address, prefix) => {
+            let mut octets = [0; 16];
+            let prefix = decode_interface(address, prefix, &mut octets)?;
+            Ok(Ipv6Interface::new(Ipv6Addr::from(octets), prefix)?)
+        }
+        _ => Err(CborDecodeError::UnexpectedItem),
+    }
+}
+
+impl Cbor for Ipv4Network {
+    fn to_cbor(&self) -> Vec<u8> {
+        encode_prefix(IPV4_TAG, self.netmask(), &self.network_address().octets())
+    }
+
+    fn from_cbor(raw: &[u8]) -> Result<Self, CborDecodeError> {
+        match decode(raw)? {
+            (IPV4_TAG, item) => decode_ipv4_network(item),
+            (tag, _) => Err(CborDecodeError::WrongTag(tag)),
+        }
+    }
+}
+
+impl Cbor for Ipv6Network {
+    fn to_cbor(&self) -> Vec<u8> {
+        encode_prefix(IPV6_TAG, self.netmask(), &self.network_address().octets())
+    }
+
+    fn from_cbor(raw: &[u8]) -> Result<Self, CborDecodeError> {
+        match decode(raw)? {
+            (IPV6_TAG, item) => decode_ipv6_network(item),
+            (tag, _) => Err(CborDecodeError::WrongTag(tag)),
+        }
+    }
+}
+
+impl Cbor for IpNetwork {
+    fn to_cbor(&self) -> Vec<u8> {
+        match self {
+            IpNetwork::V4(network) => network.to_cbor(),
+            IpNetwork::V6(network) => network.to_cbor(),
+        }
+    }
+
+    fn from_cbor(raw: &[u8]) -> Result<Self, CborDecodeError> {
+        match decode(raw)? {
+            (IPV4_TAG, item) => decode_ipv4_network(item).map(IpNetwork::V4),
+            (_, item) => decode_ipv6_network(item).map(IpNetwork::V6),
+        }
+    }
+}
+
+impl Cbor for Ipv4Interface {
+    fn to_cbor(&self) -> Vec<u8> {
+        encode_interface(IPV4_TAG, &self.address().octets(), self.netmask())
+    }
+
+    fn from_cbor(raw: &[u8]) -> Result<Self, CborDecodeError> {
+        match decode(raw)? {
+            (IPV4_TAG, item) => decode_ipv4_interface(item),
+            (tag, _) => Err(CborDecodeError::WrongTag(tag)),
+        }
+    }
+}
+
+impl Cbor for Ipv6Interface {
+    fn to_cbor(&self) -> Vec<u8> {
+        encode_interface(IPV6_TAG, &self.address().octets(), self.netmask())
+    }
+
+    fn from_cbor(raw: &[u8]) -> Result<Self, CborDecodeError> {
+        match decode(raw)? {
+            (IPV6_TAG, item) => decode_ipv6_interface(item),
+            (tag, _) => Err(CborDecodeError::WrongTag(tag)),
+        }
+    }
+}
+
+impl Cbor for IpInterface {
+    fn to_cbor(&self) -> Vec<u8> {
+        match self {
+            IpInterface::V4(interface) => interface.to_cbor(),
+            IpInterface::V6(interface) => interface.to_cbor(),
+        }
+    }
+
+    fn from_cbor(raw: &[u8]) -> Result<Self, CborDecodeError> {
+        match decode(raw)? {
+            (IPV4_TAG, item) => decode_ipv4_interface(item).map(IpInterface::V4),
+            (_, item) => decode_ipv6_interface(item).map(IpInterface::V6),
+        }
+    }
+}
+
+/// Serde adapter for [ciborium](https://crates.io/crates/ciborium), that serializes values in
+/// RFC 9164 form instead of the default compact form.
+///
+/// # Examples
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use ip_network::Ipv6Network;
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Route {
+///     #[serde(with = "ip_network::cbor::ciborium_support")]
+///     destination: Ipv6Network,
+/// }
+///
+/// let route = Route { destination: "2001:db8::/32".parse()? };
+/// let mut encoded = vec![];
+/// ciborium::into_writer(&route, &mut encoded)?;
+/// assert_eq!(route, ciborium::from_reader(&encoded[..])?);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "ciborium")]
+pub mod ciborium_support {
+    use ciborium::Value;
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use super::Cbor;
+
+    /// Serializes value as tagged CBOR item.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Cbor,
+        S: Serializer,
+    {
+        let value: Value = ciborium::from_reader(&value.to_cbor()[..]).map_err(S::Error::custom)?;
+        value.serialize(serializer)
+    }
+
+    /// Deserializes value from tagged CBOR item.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Cbor,
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        let mut raw = vec![];
+        ciborium::into_writer(&value, &mut raw).map_err(D::Error::custom)?;
+        T::from_cbor(&raw).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use super::{Cbor, CborDecodeError};
+    use crate::{IpInterface, IpNetwork, Ipv4Interface, Ipv4Network, Ipv6Network};
+
+    #[test]
+    fn rfc_examples() {
+        // Examples from RFC 9164, section 3.
+        assert_eq!(
+            vec![0xd8, 0x36, 0x82, 0x18, 0x30, 0x46, 0x20, 0x01, 0x0d, 0xb8, 0x12, 0x34],
+            IpNetwork::from_str("2001:db8:1234::/48").unwrap().to_cbor()
+        );
+        assert_eq!(
+            vec![0xd8, 0x34, 0x82, 0x18, 0x18, 0x43, 0xc0, 0x00, 0x02],
+            IpNetwork::from_str("192.0.2.0/24").unwrap().to_cbor()
+        );
+        let interface: IpInterface = "192.0.2.1/24".parse().unwrap();
+        assert_eq!(
+            vec![0xd8, 0x34, 0x82, 0x44, 0xc0, 0x00, 0x02, 0x01, 0x18, 0x18],
+            interface.to_cbor()
+        );
+        assert_eq!(
+            interface,
+            IpInterface::from_cbor(&interface.to_cbor()).unwrap()
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        for s in &[
+            "0.0.0.0/0",
+            "10.0.0.0/8",
+            "255.255.255.255/32",
+            "::/0",
+            "2001:db8::/32",
+            "::1/128",
+        ] {
+            let network = IpNetwork::from_str(s).unwrap();
+            assert_eq!(network, IpNetwork::from_cbor(&network.to_cbor()).unwrap());
+        }
+        let ipv4_network: Ipv4Network = "0.0.0.0/0".parse().unwrap();
+        assert_eq!(vec![0xd8, 0x34, 0x82, 0x00, 0x40], ipv4_network.to_cbor());
+        let ipv6_network: Ipv6Network = "::1/128".parse().unwrap();
+        assert_eq!(
+            ipv6_network,
+            Ipv6Network::from_cbor(&ipv6_network.to_cbor()).unwrap()
+        );
+    }
+
+    #[test]
+    fn decode_errors() {
+        let decode = |raw: &[u8]| IpNetwork::from_cbor(raw);
+        assert_eq!(Err(CborDecodeError::UnexpectedEnd), decode(&[]));
+        assert_eq!(
+            Err(CborDecodeError::UnexpectedEnd),
+            decode(&[0xd8, 0x34, 0x82, 0x18, 0x18, 0x43, 0xc0])
+        );
+        assert_eq!(
+            Err(CborDecodeError::TrailingData),
+            decode(&[0xd8, 0x34, 0x82, 0x00, 0x40, 0x00])
+        );
+        assert_eq!(
+            Err(CborDecodeError::WrongTag(53)),
+            decode(&[0xd8, 0x35, 0x82, 0x00, 0x40])
+        );
+        assert_eq!(
+            Err(CborDecodeError::UnexpectedItem),
+            decode(&[0x82, 0x00, 0x40])
+        );
+        // Address form.
+        assert_eq!(
+            Err(CborDecodeError::UnexpectedItem),
+            decode(&[0xd8, 0x34, 0x44, 0xc0, 0x00, 0x02, 0x01])
+        );
+        assert_eq!(
+            Err(CborDecodeError::InvalidPrefix(33)),
+            decode(&[0xd8, 0x34, 0x82, 0x18, 0x21, 0x40])
+        );
+        // Trailing zero byte.
+        assert_eq!(
+            Err(CborDecodeError::NonCanonical),
+            decode(&[0xd8, 0x34, 0x82, 0x18, 0x18, 0x43, 0xc0, 0x02, 0x00])
+        );
+        // Byte after prefix.
+        assert_eq!(
+            Err(CborDecodeError::HostBitsSet),
+            decode(&[0xd8, 0x34, 0x82, 0x08, 0x42, 0x0a, 0x01])
+        );
+        // Bit after prefix.
+        assert_eq!(
+            Err(CborDecodeError::HostBitsSet),
+            decode(&[0xd8, 0x34, 0x82, 0x07, 0x41, 0x0b])
+        );
+        assert_eq!(
+            Err(CborDecodeError::BadAddressLength(5)),
+            decode(&[0xd8, 0x34, 0x82, 0x18, 0x20, 0x45, 1, 1, 1, 1, 1])
+        );
+        assert_eq!(
+            Err(CborDecodeError::WrongTag(54)),
+            Ipv4Network::from_cbor(&IpNetwork::from_str("::/0").unwrap().to_cbor())
+        );
+        assert_eq!(
+            Err(CborDecodeError::UnexpectedItem),
+            Ipv4Interface::from_cbor(&IpNetwork::from_str("10.0.0.0/8").unwrap().to_cbor())
+        );
+        assert_eq!(
+            Err(CborDecodeError::BadAddressLength(3)),
+            Ipv4Interface::from_cbor(&[0xd8, 0x34, 0x82, 0x43, 0xc0, 0x00, 0x02, 0x18, 0x18])
+        );
+    }
+
+    #[cfg(feature = "ciborium")]
+    #[test]
+    fn ciborium() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Networks(
+            #[serde(with = "super::ciborium_support")] IpNetwork,
+            #[serde(with = "super::ciborium_support")] Ipv4Interface,
+        );
+
+        let networks = Networks(
+            IpNetwork::from_str("2001:db8:1234::/48").unwrap(),
+            "192.0.2.1/24".parse().unwrap(),
+        );
+        let mut encoded = vec![];
+        ciborium::into_writer(&networks, &mut encoded).unwrap();
+        let mut expected = vec![0x82];
+        expected.extend_from_slice(&networks.0.to_cbor());
+        expected.extend_from_slice(&networks.1.to_cbor());
+        assert_eq!(expected, encoded);
+        assert_eq!(networks, ciborium::from_reader(&encoded[..]).unwrap());
+
+        let result: Result<Networks, _> = ciborium::from_reader(&[0x82, 0x00, 0x00][..]);
+        assert!(result.is_err());
+    }
+}
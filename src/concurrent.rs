@@ -0,0 +1,282 @@
+//! Prefix map for concurrent lookups, that can be updated while readers are running.
+//!
+//! [`ConcurrentPrefixMap`] is a cheaply cloneable handle for readers and [`PrefixMapWriter`] is
+//! the only handle, that can change the map. Readers look up immutable snapshot compiled to
+//! [`LpmTable`], that is replaced atomically by writer, so readers never take a lock and never
+//! wait for writer.
+//!
+//! # Consistency
+//!
+//! * Changes made by writer are not visible to readers until [`PrefixMapWriter::publish`] is
+//!   called. All changes made before publishing become visible at once, readers never see only
+//!   part of batch.
+//! * Every lookup is performed on one snapshot. Lookups started after `publish` returns see the new
+//!   snapshot, lookups running during `publish` can still see the previous one.
+//! * Consecutive lookups from one thread never see older snapshot than the previous lookup. Use
+//!   [`ConcurrentPrefixMap::snapshot`] when multiple lookups must see the same state.
+//!
+//! Publishing compiles the whole table again, so it is suitable for batches of updates, not for
+//! publishing every single change. Old snapshot is freed when the last reader stops using it.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::net::Ipv4Addr;
+//! use std::thread;
+//! use ip_network::IpNetwork;
+//! use ip_network::concurrent::ConcurrentPrefixMap;
+//!
+//! let (map, mut writer) = ConcurrentPrefixMap::new();
+//! writer.insert("10.0.0.0/8".parse::<IpNetwork>()?, "private");
+//! assert_eq!(None, map.longest_match(Ipv4Addr::new(10, 0, 0, 1)));
+//! writer.publish();
+//!
+//! let reader = map.clone();
+//! thread::spawn(move || {
+//!     let (network, value) = reader.longest_match(Ipv4Addr::new(10, 0, 0, 1)).unwrap();
+//!     assert_eq!("private", value);
+//! })
+//! .join()
+//! .unwrap();
+//! # Ok::<(), ip_network::IpNetworkParseError>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use crate::lpm::{LpmTable, LpmTableBuilder};
+use crate::IpNetwork;
+
+/// Handle for lookups in concurrently updated prefix map. Cloned handles share the same map.
+pub struct ConcurrentPrefixMap<V> {
+    current: Arc<ArcSwap<LpmTable<V>>>,
+}
+
+impl<V> ConcurrentPrefixMap<V> {
+    /// Creates empty map and returns handle for readers and the only writer.
+    pub fn new() -> (Self, PrefixMapWriter<V>) {
+        let current = Arc::new(ArcSwap::from_pointee(LpmTableBuilder::new().build()));
+        let writer = PrefixMapWriter {
+            networks: BTreeMap::new(),
+            current: current.clone(),
+        };
+        (Self { current }, writer)
+    }
+
+    /// Returns the most specific network containing given address together with its value from
+    /// the last published snapshot.
+    pub fn longest_match<A: Into<IpAddr>>(&self, address: A) -> Option<(IpNetwork, V)>
+    where
+        V: Clone,
+    {
+        self.current
+            .load()
+            .longest_match(address)
+            .map(|(network, value)| (network, value.clone()))
+    }
+
+    /// Returns the last published snapshot, that is not affected by later updates.
+    pub fn snapshot(&self) -> Arc<LpmTable<V>> {
+        self.current.load_full()
+    }
+}
+
+impl<V> Clone for ConcurrentPrefixMap<V> {
+    fn clone(&self) -> Self {
+        Self {
+            current: self.current.clone(),
+        }
+    }
+}
+
+/// Update of prefix map.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Update<V> {
+    /// Inserts network with value or replaces value of existing network.
+    Insert(IpNetwork, V),
+    /// Removes network.
+    Remove(IpNetwork),
+}
+
+/// The only handle, that can update [`ConcurrentPrefixMap`].
+pub struct PrefixMapWriter<V> {
+    networks: BTreeMap<IpNetwork, V>,
+    current: Arc<ArcSwap<LpmTable<V>>>,
+}
+
+impl<V: Clone> PrefixMapWriter<V> {
+    /// Inserts network with value, returns previous value. Change is visible to readers after
+    /// publishing.
+    pub fn insert<N: Into<IpNetwork>>(&mut self, network: N, value: V) -> Option<V> {
+        self.networks.insert(network.into(), value)
+    }
+
+    /// Removes network, returns its value. Change is visible to readers after publishing.
+    pub fn remove<N: Into<IpNetwork>>(&mut self, network: N) -> Option<V> {
+        self.networks.remove(&network.into())
+    }
+
+    /// Returns value of network including not yet published changes.
+    pub fn get<N: Into<IpNetwork>>(&self, network: N) -> Option<&V> {
+        self.networks.get(&network.into())
+    }
+
+    /// Returns number of networks including not yet published changes.
+    pub fn len(&self) -> usize {
+        self.networks.len()
+    }
+
+    /// Returns `true` if map contains no networks including not yet published changes.
+    pub fn is_empty(&self) -> bool {
+        self.networks.is_empty()
+    }
+
+    /// Makes all changes visible to readers at once.
+    pub fn publish(&mut self) {
+        let table = self
+            .networks
+            .iter()
+            .map(|(network, value)| (*network, value.clone()))
+            .collect();
+        self.current.store(Arc::new(table));
+    }
+
+    /// Applies batch of updates and publishes them.
+    pub fn apply<I: IntoIterator<Item = Update<V>>>(&mut self, updates: I) {
+        for update in updates {
+            match update {
+                Update::Insert(network, value) => {
+                    self.insert(network, value);
+                }
+                Update::Remove(network) => {
+                    self.remove(network);
+                }
+            }
+        }
+        self.publish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::str::FromStr;
+    use super::{ConcurrentPrefixMap, Update};
+    use crate::{IpNetwork, Ipv4Network};
+
+    #[test]
+    fn publish() {
+        let (map, mut writer) = ConcurrentPrefixMap::new();
+        assert_eq!(
+            None,
+            writer.insert(IpNetwork::from_str("10.0.0.0/8").unwrap(), 1)
+        );
+        assert_eq!(
+            Some(1),
+            writer.insert(IpNetwork::from_str("10.0.0.0/8").unwrap(), 2)
+        );
+        writer.insert(IpNetwork::from_str("2001:db8::/32").unwrap(), 3);
+        assert_eq!(2, writer.len());
+        assert_eq!(None, map.longest_match(Ipv4Addr::new(10, 0, 0, 1)));
+
+        writer.publish();
+        let snapshot = map.snapshot();
+        assert_eq!(
+            Some((IpNetwork::from_str("10.0.0.0/8").unwrap(), 2)),
+            map.longest_match(Ipv4Addr::new(10, 0, 0, 1))
+        );
+
+        writer.apply(vec![
+            Update::Remove(IpNetwork::from_str("10.0.0.0/8").unwrap()),
+            Update::Insert(IpNetwork::from_str("10.1.0.0/16").unwrap(), 4),
+        ]);
+        assert_eq!(None, map.longest_match(Ipv4Addr::new(10, 0, 0, 1)));
+        assert_eq!(
+            Some((IpNetwork::from_str("10.1.0.0/16").unwrap(), 4)),
+            map.longest_match(Ipv4Addr::new(10, 1, 0, 1))
+        );
+        assert_eq!(
+            Some((IpNetwork::from_str("2001:db8::/32").unwrap(), 3)),
+            map.longest_match(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1))
+        );
+
+        // Old snapshot is not changed.
+        assert_eq!(
+            Some(&2),
+            snapshot
+                .longest_match(Ipv4Addr::new(10, 0, 0, 1))
+                .map(|(_, v)| v)
+        );
+    }
+
+    #[test]
+    fn stress() {
+        const NETWORKS: u32 = 64;
+        const GENERATIONS: u32 = 100;
+
+        let networks: Vec<Ipv4Network> = (0..NETWORKS)
+            .map(|i| Ipv4Network::new(Ipv4Addr::from(0x0a00_0000 | i << 8), 24).unwrap())
+            .collect();
+
+        let (map, mut writer) = ConcurrentPrefixMap::new();
+        let done = Arc::new(AtomicBool::new(false));
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let map = map.clone();
+                let networks = networks.clone();
+                let done = done.clone();
+                thread::spawn(move || {
+                    let mut last_generation = 0;
+                    let mut snapshots = 0;
+                    while !done.load(Ordering::Acquire) {
+                        // All networks in one snapshot have the same generation.
+                        let snapshot = map.snapshot();
+                        let generations: Vec<Option<u32>> = networks
+                            .iter()
+                            .map(|n| {
+                                snapshot
+                                    .longest_match_ipv4(n.network_address())
+                                    .map(|(_, v)| *v)
+                            })
+                            .collect();
+                        let generation = generations[0].unwrap_or(0);
+                        assert!(generations.iter().all(|g| g.unwrap_or(0) == generation));
+                        assert!(generation >= last_generation);
+                        last_generation = generation;
+
+                        // Lookups without snapshot never go back in time.
+                        let (_, value) = map
+                            .longest_match(networks[0].network_address())
+                            .unwrap_or((IpNetwork::V4(networks[0]), 0));
+                        assert!(value >= last_generation);
+                        last_generation = value;
+                        snapshots += 1;
+                    }
+                    snapshots
+                })
+            })
+            .collect();
+
+        for generation in 1..=GENERATIONS {
+            writer.apply(
+                networks
+                    .iter()
+                    .map(|n| Update::Insert((*n).into(), generation)),
+            );
+        }
+        done.store(true, Ordering::Release);
+
+        for reader in readers {
+            assert!(reader.join().unwrap() > 0);
+        }
+        assert_eq!(
+            Some((IpNetwork::V4(networks[5]), GENERATIONS)),
+            map.longest_match(networks[5].network_address())
+        );
+    }
+}
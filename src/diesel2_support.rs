@@ -0,0 +1,803 @@
+use std::io::Write;
+use diesel2 as diesel;
+use diesel::backend::Backend;
+use diesel::deserialize::{self, FromSql, Queryable};
+use diesel::dsl::{And, Between, EqAny, Gt, LtEq};
+use diesel::expression::{AsExpression, Expression};
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::{BigInt, Binary, Cidr, Inet, SingleValue, Text};
+use diesel::{BoolExpressionMethods, ExpressionMethods};
+use crate::{IpInterface, IpNetwork, Ipv4Interface, Ipv4Network, Ipv6Interface, Ipv6Network};
+use crate::binary_common;
+use crate::diesel_common::{binary_extension_methods, pq_extension_methods, text_and_binary_impl};
+use crate::postgres_common::{self, SqlType, Truncated};
+
+impl FromSql<Cidr, Pg> for Ipv4Network {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        Ok(postgres_common::from_sql_ipv4_network(
+            bytes,
+            SqlType::Cidr,
+            false,
+        )?)
+    }
+}
+
+impl FromSql<Cidr, Pg> for Ipv6Network {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        Ok(postgres_common::from_sql_ipv6_network(
+            bytes,
+            SqlType::Cidr,
+            false,
+        )?)
+    }
+}
+
+impl FromSql<Cidr, Pg> for IpNetwork {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        Ok(postgres_common::from_sql_ip_network(
+            bytes,
+            SqlType::Cidr,
+            false,
+        )?)
+    }
+}
+
+impl FromSql<Inet, Pg> for Ipv4Network {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        Ok(postgres_common::from_sql_ipv4_network(
+            bytes,
+            SqlType::Inet,
+            false,
+        )?)
+    }
+}
+
+impl FromSql<Inet, Pg> for Ipv6Network {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        Ok(postgres_common::from_sql_ipv6_network(
+            bytes,
+            SqlType::Inet,
+            false,
+        )?)
+    }
+}
+
+impl FromSql<Inet, Pg> for IpNetwork {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        Ok(postgres_common::from_sql_ip_network(
+            bytes,
+            SqlType::Inet,
+            false,
+        )?)
+    }
+}
+
+impl FromSql<Inet, Pg> for Truncated<Ipv4Network> {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        let network = postgres_common::from_sql_ipv4_network(bytes, SqlType::Inet, true)?;
+        Ok(Truncated(network))
+    }
+}
+
+impl FromSql<Inet, Pg> for Truncated<Ipv6Network> {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        let network = postgres_common::from_sql_ipv6_network(bytes, SqlType::Inet, true)?;
+        Ok(Truncated(network))
+    }
+}
+
+impl FromSql<Inet, Pg> for Truncated<IpNetwork> {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        let network = postgres_common::from_sql_ip_network(bytes, SqlType::Inet, true)?;
+        Ok(Truncated(network))
+    }
+}
+
+impl<N, ST, DB> Queryable<ST, DB> for Truncated<N>
+where
+    DB: Backend,
+    ST: SingleValue,
+    Truncated<N>: FromSql<ST, DB>,
+{
+    type Row = Self;
+
+    fn build(row: Self::Row) -> deserialize::Result<Self> {
+        Ok(row)
+    }
+}
+
+impl FromSql<Inet, Pg> for Ipv4Interface {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        Ok(postgres_common::from_sql_ipv4_interface(bytes)?)
+    }
+}
+
+impl FromSql<Inet, Pg> for Ipv6Interface {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        Ok(postgres_common::from_sql_ipv6_interface(bytes)?)
+    }
+}
+
+impl FromSql<Inet, Pg> for IpInterface {
+    fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+        let bytes = value.as_bytes();
+        Ok(postgres_common::from_sql_ip_interface(bytes)?)
+    }
+}
+
+impl ToSql<Cidr, Pg> for Ipv4Network {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let data = postgres_common::to_sql_ipv4_network(self, SqlType::Cidr);
+        out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
+    }
+}
+
+impl ToSql<Cidr, Pg> for Ipv6Network {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let data = postgres_common::to_sql_ipv6_network(self, SqlType::Cidr);
+        out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
+    }
+}
+
+impl ToSql<Cidr, Pg> for IpNetwork {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match self {
+            IpNetwork::V4(network) => ToSql::<Cidr, Pg>::to_sql(network, out),
+            IpNetwork::V6(network) => ToSql::<Cidr, Pg>::to_sql(network, out),
+        }
+    }
+}
+
+impl ToSql<Inet, Pg> for Ipv4Network {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let data = postgres_common::to_sql_ipv4_network(self, SqlType::Inet);
+        out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
+    }
+}
+
+impl ToSql<Inet, Pg> for Ipv6Network {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let data = postgres_common::to_sql_ipv6_network(self, SqlType::Inet);
+        out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
+    }
+}
+
+impl ToSql<Inet, Pg> for IpNetwork {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match self {
+            IpNetwork::V4(network) => ToSql::<Inet, Pg>::to_sql(network, out),
+            IpNetwork::V6(network) => ToSql::<Inet, Pg>::to_sql(network, out),
+        }
+    }
+}
+
+impl ToSql<Inet, Pg> for Ipv4Interface {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let data = postgres_common::to_sql_ipv4_interface(self);
+        out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
+    }
+}
+
+impl ToSql<Inet, Pg> for Ipv6Interface {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let data = postgres_common::to_sql_ipv6_interface(self);
+        out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
+    }
+}
+
+impl ToSql<Inet, Pg> for IpInterface {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match self {
+            IpInterface::V4(interface) => ToSql::<Inet, Pg>::to_sql(interface, out),
+            IpInterface::V6(interface) => ToSql::<Inet, Pg>::to_sql(interface, out),
+        }
+    }
+}
+
+/// Implements `ToSql` for backends that collect binds as raw bytes (PostgreSQL and MySQL).
+macro_rules! raw_bytes_to_sql_impl {
+    ($t:ty, $to_binary:path, $db:ty) => {
+        impl ToSql<Text, $db> for $t {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, $db>) -> serialize::Result {
+                out.write_all(self.to_string().as_bytes())?;
+                Ok(IsNull::No)
+            }
+        }
+
+        impl ToSql<Binary, $db> for $t {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, $db>) -> serialize::Result {
+                out.write_all(&$to_binary(self)[..])?;
+                Ok(IsNull::No)
+            }
+        }
+    };
+}
+
+/// Implements `ToSql` for text and binary encoding for PostgreSQL, MySQL and SQLite.
+macro_rules! text_and_binary_to_sql_impl {
+    ($t:ty, $to_binary:path) => {
+        raw_bytes_to_sql_impl!($t, $to_binary, Pg);
+        #[cfg(feature = "diesel2-mysql")]
+        raw_bytes_to_sql_impl!($t, $to_binary, diesel::mysql::Mysql);
+
+        #[cfg(feature = "diesel2-sqlite")]
+        impl ToSql<Text, diesel::sqlite::Sqlite> for $t {
+            fn to_sql<'b>(
+                &'b self,
+                out: &mut Output<'b, '_, diesel::sqlite::Sqlite>,
+            ) -> serialize::Result {
+                out.set_value(self.to_string());
+                Ok(IsNull::No)
+            }
+        }
+
+        #[cfg(feature = "diesel2-sqlite")]
+        impl ToSql<Binary, diesel::sqlite::Sqlite> for $t {
+            fn to_sql<'b>(
+                &'b self,
+                out: &mut Output<'b, '_, diesel::sqlite::Sqlite>,
+            ) -> serialize::Result {
+                out.set_value($to_binary(self).to_vec());
+                Ok(IsNull::No)
+            }
+        }
+    };
+}
+
+text_and_binary_impl!(DB::RawValue<'_>, text_and_binary_to_sql_impl);
+
+#[allow(dead_code)]
+mod foreign_derives {
+    use diesel2 as diesel;
+    use diesel::deserialize::FromSqlRow;
+    use diesel::expression::AsExpression;
+    use diesel::sql_types::{Binary, Cidr, Inet, Text};
+    use crate::{IpInterface, IpNetwork, Ipv4Interface, Ipv4Network, Ipv6Interface, Ipv6Network};
+
+    #[derive(FromSqlRow, AsExpression)]
+    #[diesel(foreign_derive)]
+    #[diesel(sql_type = Cidr)]
+    #[diesel(sql_type = Inet)]
+    #[diesel(sql_type = Text)]
+    #[diesel(sql_type = Binary)]
+    struct IpNetworkProxy(IpNetwork);
+
+    #[derive(FromSqlRow, AsExpression)]
+    #[diesel(foreign_derive)]
+    #[diesel(sql_type = Cidr)]
+    #[diesel(sql_type = Inet)]
+    #[diesel(sql_type = Text)]
+    #[diesel(sql_type = Binary)]
+    struct Ipv4NetworkProxy(Ipv4Network);
+
+    #[derive(FromSqlRow, AsExpression)]
+    #[diesel(foreign_derive)]
+    #[diesel(sql_type = Cidr)]
+    #[diesel(sql_type = Inet)]
+    #[diesel(sql_type = Text)]
+    #[diesel(sql_type = Binary)]
+    struct Ipv6NetworkProxy(Ipv6Network);
+
+    #[derive(FromSqlRow, AsExpression)]
+    #[diesel(foreign_derive)]
+    #[diesel(sql_type = Inet)]
+    struct IpInterfaceProxy(IpInterface);
+
+    #[derive(FromSqlRow, AsExpression)]
+    #[diesel(foreign_derive)]
+    #[diesel(sql_type = Inet)]
+    struct Ipv4InterfaceProxy(Ipv4Interface);
+
+    #[derive(FromSqlRow, AsExpression)]
+    #[diesel(foreign_derive)]
+    #[diesel(sql_type = Inet)]
+    struct Ipv6InterfaceProxy(Ipv6Interface);
+}
+
+diesel::infix_operator!(IsContainedBy, " << ", backend: Pg);
+diesel::infix_operator!(IsContainedByOrEquals, " <<= ", backend: Pg);
+diesel::infix_operator!(Contains, " >> ", backend: Pg);
+diesel::infix_operator!(ContainsOrEquals, " >>= ", backend: Pg);
+diesel::infix_operator!(ContainsOrIsContainedBy, " && ", backend: Pg);
+diesel::prefix_operator!(BitwiseNot, "~", Inet, backend: Pg);
+diesel::infix_operator!(BitwiseAnd, " & ", Inet, backend: Pg);
+diesel::infix_operator!(BitwiseOr, " | ", Inet, backend: Pg);
+diesel::infix_operator!(AddOffset, " + ", Inet, backend: Pg);
+diesel::infix_operator!(SubtractOffset, " - ", Inet, backend: Pg);
+diesel::infix_operator!(Difference, " - ", BigInt, backend: Pg);
+
+pq_extension_methods!(
+    /// Support for PostgreSQL Network Address Operators for Diesel
+    ///
+    /// See [PostgreSQL documentation for details](https://www.postgresql.org/docs/current/static/functions-net.html).
+    ///
+    /// Diesel 2 prelude contains `PgNetExpressionMethods` trait with `contains` and `is_contained_by`
+    /// methods too, so when both traits are in scope, call these methods as
+    /// `PqCidrExtensionMethods::contains(expr, other)`.
+    PqCidrExtensionMethods,
+    Cidr
+);
+
+pq_extension_methods!(
+    /// Support for PostgreSQL Network Address Operators for Diesel on INET expressions
+    ///
+    /// See [PostgreSQL documentation for details](https://www.postgresql.org/docs/current/static/functions-net.html).
+    ///
+    /// Diesel 2 prelude contains `PgNetExpressionMethods` trait with `contains` and `is_contained_by`
+    /// methods too, so when both traits are in scope, call these methods as
+    /// `PqInetExtensionMethods::contains(expr, other)`.
+    PqInetExtensionMethods,
+    Inet
+);
+
+binary_extension_methods!();
+
+/// SQL types for IP networks, that are accepted by network functions.
+pub trait IpNetworkSqlType: SingleValue {}
+
+impl IpNetworkSqlType for Cidr {}
+impl IpNetworkSqlType for Inet {}
+
+/// Defines SQL function for PostgreSQL backend.
+///
+/// Works like `define_sql_function!` from Diesel, that cannot be used when this crate is compiled
+/// together with Diesel 1, because generated code refers to crate named `diesel`.
+macro_rules! pg_function {
+    ($(#[$attr:meta])* fn $name:ident($($args:tt)+) -> $ret:ty;) => {
+        pg_function! {
+            $(#[$attr])*
+            fn $name<>($($args)+) -> $ret;
+        }
+    };
+
+    (
+        $(#[$attr:meta])*
+        fn $name:ident<$($sql_ty:ident: $bound:path),*>($($arg:ident: $arg_ty:ty),+) -> $ret:ty;
+    ) => {
+        #[derive(Debug, Clone, Copy)]
+        #[doc(hidden)]
+        pub struct $name<$($sql_ty,)* $($arg),+> {
+            $($arg: $arg,)+
+            sql_types: PhantomData<($($sql_ty,)*)>,
+        }
+
+        $(#[$attr])*
+        pub fn $name<$($sql_ty: $bound,)* $($arg),+>(
+            $($arg: $arg),+
+        ) -> $name<$($sql_ty,)* $(<$arg as AsExpression<$arg_ty>>::Expression),+>
+        where
+            $($arg: AsExpression<$arg_ty>,)+
+        {
+            $name {
+                $($arg: $arg.as_expression(),)+
+                sql_types: PhantomData,
+            }
+        }
+
+        impl<$($sql_ty: $bound,)* $($arg),+> Expression for $name<$($sql_ty,)* $($arg),+>
+        where
+            $($arg: Expression,)+
+        {
+            type SqlType = $ret;
+        }
+
+        impl<$($sql_ty,)* $($arg),+> QueryFragment<Pg> for $name<$($sql_ty,)* $($arg),+>
+        where
+            $($arg: QueryFragment<Pg>,)+
+        {
+            #[allow(unused_assignments)]
+            fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+                out.push_sql(concat!(stringify!($name), "("));
+                let mut first = true;
+                $(
+                    if !first {
+                        out.push_sql(", ");
+                    }
+                    self.$arg.walk_ast(out.reborrow())?;
+                    first = false;
+                )+
+                out.push_sql(")");
+                Ok(())
+            }
+        }
+
+        impl<$($sql_ty,)* $($arg),+> QueryId for $name<$($sql_ty,)* $($arg),+>
+        where
+            $($sql_ty: 'static,)*
+            $($arg: QueryId,)+
+        {
+            type QueryId = $name<$($sql_ty,)* $(<$arg as QueryId>::QueryId),+>;
+
+            const HAS_STATIC_QUERY_ID: bool = $(<$arg as QueryId>::HAS_STATIC_QUERY_ID &&)+ true;
+        }
+
+        impl<$($sql_ty,)* $($arg,)+ GB> ValidGrouping<GB> for $name<$($sql_ty,)* $($arg),+>
+        where
+            ($($arg,)+): ValidGrouping<GB>,
+        {
+            type IsAggregate = <($($arg,)+) as ValidGrouping<GB>>::IsAggregate;
+        }
+
+        impl<$($sql_ty,)* $($arg,)+ QS> AppearsOnTable<QS> for $name<$($sql_ty,)* $($arg),+>
+        where
+            ($($arg,)+): AppearsOnTable<QS>,
+            Self: Expression,
+        {
+        }
+
+        impl<$($sql_ty,)* $($arg,)+ QS> SelectableExpression<QS> for $name<$($sql_ty,)* $($arg),+>
+        where
+            ($($arg,)+): SelectableExpression<QS>,
+            Self: AppearsOnTable<QS>,
+        {
+        }
+    };
+}
+
+/// CIDR and INET functions.
+pub mod functions {
+    // Type parameters are named after function arguments, like in Diesel.
+    #![allow(non_camel_case_types)]
+
+    use std::marker::PhantomData;
+    use diesel2::expression::{
+        AppearsOnTable, AsExpression, Expression, SelectableExpression, ValidGrouping,
+    };
+    use diesel2::pg::Pg;
+    use diesel2::query_builder::{AstPass, QueryFragment, QueryId};
+    use diesel2::sql_types::{Bool, Cidr, Inet, Integer, Text};
+    use diesel2::QueryResult;
+    use super::IpNetworkSqlType;
+
+    pg_function! {
+        /// Extract family of address; 4 for IPv4, 6 for IPv6.
+        fn family(x: Cidr) -> Integer;
+    }
+    pg_function! {
+        /// Extract netmask length.
+        fn masklen(x: Cidr) -> Integer;
+    }
+    pg_function! {
+        /// Abbreviated display format as text.
+        fn abbrev<ST: IpNetworkSqlType>(x: ST) -> Text;
+    }
+    pg_function! {
+        /// Broadcast address for network.
+        fn broadcast<ST: IpNetworkSqlType>(x: ST) -> Inet;
+    }
+    pg_function! {
+        /// Extract IP address as text.
+        fn host<ST: IpNetworkSqlType>(x: ST) -> Text;
+    }
+    pg_function! {
+        /// Construct host mask for network.
+        fn hostmask<ST: IpNetworkSqlType>(x: ST) -> Inet;
+    }
+    pg_function! {
+        /// Construct netmask for network.
+        fn netmask<ST: IpNetworkSqlType>(x: ST) -> Inet;
+    }
+    pg_function! {
+        /// Extract network part of address.
+        fn network<ST: IpNetworkSqlType>(x: ST) -> Cidr;
+    }
+    pg_function! {
+        /// Set netmask length, returns value of the same type.
+        fn set_masklen<ST: IpNetworkSqlType>(x: ST, len: Integer) -> ST;
+    }
+    pg_function! {
+        /// Extract IP address and netmask length as text.
+        fn text<ST: IpNetworkSqlType>(x: ST) -> Text;
+    }
+    pg_function! {
+        /// Are the addresses from the same family?
+        fn inet_same_family<L: IpNetworkSqlType, R: IpNetworkSqlType>(x: L, y: R) -> Bool;
+    }
+    pg_function! {
+        /// The smallest network which includes both of the given networks.
+        fn inet_merge<L: IpNetworkSqlType, R: IpNetworkSqlType>(x: L, y: R) -> Cidr;
+    }
+}
+
+pub mod helper_types {
+    use diesel2::expression::AsExpression;
+    use diesel2::sql_types::{Cidr, Integer};
+
+    type Expression<ST, Expr> = <Expr as AsExpression<ST>>::Expression;
+
+    pub type Family<Expr> = super::functions::family<Expression<Cidr, Expr>>;
+    pub type Masklen<Expr> = super::functions::masklen<Expression<Cidr, Expr>>;
+    pub type Abbrev<ST, Expr> = super::functions::abbrev<ST, Expression<ST, Expr>>;
+    pub type Broadcast<ST, Expr> = super::functions::broadcast<ST, Expression<ST, Expr>>;
+    pub type Host<ST, Expr> = super::functions::host<ST, Expression<ST, Expr>>;
+    pub type Hostmask<ST, Expr> = super::functions::hostmask<ST, Expression<ST, Expr>>;
+    pub type Netmask<ST, Expr> = super::functions::netmask<ST, Expression<ST, Expr>>;
+    pub type Network<ST, Expr> = super::functions::network<ST, Expression<ST, Expr>>;
+    pub type SetMasklen<ST, Expr, Len> =
+        super::functions::set_masklen<ST, Expression<ST, Expr>, Expression<Integer, Len>>;
+    pub type Text<ST, Expr> = super::functions::text<ST, Expression<ST, Expr>>;
+    pub type InetSameFamily<L, R, X, Y> =
+        super::functions::inet_same_family<L, R, Expression<L, X>, Expression<R, Y>>;
+    pub type InetMerge<L, R, X, Y> =
+        super::functions::inet_merge<L, R, Expression<L, X>, Expression<R, Y>>;
+}
+
+pub mod dsl {
+    pub use super::functions::*;
+    pub use super::helper_types::*;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+    use diesel2 as diesel;
+    use diesel::deserialize::Queryable;
+    use diesel::pg::Pg;
+    use diesel::prelude::*;
+    use diesel::debug_query;
+    use diesel::sql_types::{Binary, Cidr, Inet};
+    use super::{BinaryNetworkExtensionMethods, PqCidrExtensionMethods, PqInetExtensionMethods};
+    use super::{IpInterface, IpNetwork, Ipv4Network, Ipv6Network};
+    use super::dsl::*;
+    use crate::Truncated;
+
+    // `table!` macro from Diesel 2 requires crate named `diesel`, so tests use bound values
+    // instead of columns.
+    fn ip_network() -> IpNetwork {
+        IpNetwork::new(Ipv4Addr::new(192, 168, 0, 0), 16).unwrap()
+    }
+
+    #[test]
+    fn as_expression() {
+        let ipv4_network: Ipv4Network = "10.0.0.0/8".parse().unwrap();
+        let ipv6_network: Ipv6Network = "2001:db8::/32".parse().unwrap();
+        let ip_interface: IpInterface = "192.168.1.10/24".parse().unwrap();
+        let query = diesel::select((
+            ip_network().into_sql::<Cidr>(),
+            ipv4_network.into_sql::<Inet>(),
+            ipv6_network.into_sql::<Cidr>(),
+            ip_interface.into_sql::<Inet>(),
+        ));
+        assert_eq!(
+            "SELECT $1, $2, $3, $4 -- binds: [V4(Ipv4Network { network_address: 192.168.0.0, netmask: 16 }), \
+            Ipv4Network { network_address: 10.0.0.0, netmask: 8 }, Ipv6Network { network_address: 2001:db8::, netmask: 32 }, \
+            V4(Ipv4Interface { address: 192.168.1.10, netmask: 24 })]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+    }
+
+    #[test]
+    fn from_sql_row() {
+        fn assert_queryable<T: Queryable<ST, Pg>, ST>() {}
+        assert_queryable::<IpNetwork, Cidr>();
+        assert_queryable::<Ipv4Network, Inet>();
+        assert_queryable::<Ipv6Network, Cidr>();
+        assert_queryable::<IpInterface, Inet>();
+        assert_queryable::<Truncated<IpNetwork>, Inet>();
+    }
+
+    #[test]
+    fn operators() {
+        let ip = IpNetwork::new(Ipv4Addr::new(127, 0, 0, 1), 32).unwrap();
+        let query = diesel::select(PqCidrExtensionMethods::is_contained_by(
+            ip_network().into_sql::<Cidr>(),
+            &ip,
+        ));
+        assert_eq!(
+            "SELECT $1 << $2 -- binds: [V4(Ipv4Network { network_address: 192.168.0.0, netmask: 16 }), \
+            V4(Ipv4Network { network_address: 127.0.0.1, netmask: 32 })]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+        let query = diesel::select(
+            ip_network()
+                .into_sql::<Cidr>()
+                .is_contained_by_or_equals(&ip),
+        );
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("SELECT $1 <<= $2"));
+        let query = diesel::select(PqCidrExtensionMethods::contains(
+            ip_network().into_sql::<Cidr>(),
+            &ip,
+        ));
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("SELECT $1 >> $2"));
+        let query = diesel::select(ip_network().into_sql::<Cidr>().contains_or_equals(&ip));
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("SELECT $1 >>= $2"));
+        let query = diesel::select(
+            ip_network()
+                .into_sql::<Cidr>()
+                .contains_or_is_contained_by(&ip),
+        );
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("SELECT $1 && $2"));
+    }
+
+    #[test]
+    fn function_family() {
+        let query = diesel::select(family(ip_network()));
+        assert_eq!(
+            "SELECT family($1) -- binds: [V4(Ipv4Network { network_address: 192.168.0.0, netmask: 16 })]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+    }
+
+    #[test]
+    fn function_masklen() {
+        let query = diesel::select(masklen(ip_network()));
+        assert_eq!(
+            "SELECT masklen($1) -- binds: [V4(Ipv4Network { network_address: 192.168.0.0, netmask: 16 })]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+    }
+
+    #[test]
+    fn inet_operators() {
+        let ip: IpInterface = "127.0.0.1/8".parse().unwrap();
+        let query = diesel::select(ip.into_sql::<Inet>().bitwise_not());
+        assert_eq!(
+            "SELECT ~$1 -- binds: [V4(Ipv4Interface { address: 127.0.0.1, netmask: 8 })]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+        let query = diesel::select(ip.into_sql::<Inet>().bitwise_and(ip));
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("SELECT $1 & $2"));
+        let query = diesel::select(ip.into_sql::<Inet>().bitwise_or(ip));
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("SELECT $1 | $2"));
+        let query = diesel::select(ip.into_sql::<Inet>().add_offset(10));
+        assert_eq!(
+            "SELECT $1 + $2 -- binds: [V4(Ipv4Interface { address: 127.0.0.1, netmask: 8 }), 10]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+        let query = diesel::select(ip.into_sql::<Inet>().subtract_offset(10));
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("SELECT $1 - $2"));
+        let query = diesel::select(ip.into_sql::<Inet>().difference(ip));
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("SELECT $1 - $2"));
+    }
+
+    #[test]
+    fn operators_return_types() {
+        use diesel::expression::Expression;
+        use diesel::sql_types::BigInt;
+        use std::marker::PhantomData;
+
+        fn sql_type<E: Expression>(_: E) -> PhantomData<E::SqlType> {
+            PhantomData
+        }
+        let cidr = ip_network().into_sql::<Cidr>();
+        let inet = ip_network().into_sql::<Inet>();
+        let _: PhantomData<Inet> = sql_type(cidr.bitwise_not());
+        let _: PhantomData<Inet> = sql_type(cidr.bitwise_and(ip_network()));
+        let _: PhantomData<Inet> = sql_type(cidr.add_offset(1));
+        let _: PhantomData<BigInt> = sql_type(cidr.difference(ip_network()));
+        let _: PhantomData<Cidr> = sql_type(network(inet));
+        let _: PhantomData<Cidr> = sql_type(set_masklen(cidr, 8));
+        let _: PhantomData<Inet> = sql_type(set_masklen(inet, 8));
+        let _: PhantomData<Cidr> = sql_type(inet_merge(inet, cidr));
+    }
+
+    #[test]
+    fn functions() {
+        let cidr = ip_network().into_sql::<Cidr>();
+        let inet = ip_network().into_sql::<Inet>();
+        let query = diesel::select((
+            abbrev(cidr),
+            broadcast(inet),
+            host(inet),
+            hostmask(cidr),
+            netmask(inet),
+            network(inet),
+        ));
+        assert!(debug_query::<Pg, _>(&query).to_string().starts_with(
+            "SELECT abbrev($1), broadcast($2), host($3), hostmask($4), netmask($5), network($6) -- binds: "
+        ));
+
+        let query = diesel::select((
+            set_masklen(inet, 16),
+            text(inet),
+            inet_same_family(inet, cidr),
+            inet_merge(inet, cidr),
+        ));
+        assert!(debug_query::<Pg, _>(&query).to_string().starts_with(
+            "SELECT set_masklen($1, $2), text($3), inet_same_family($4, $5), inet_merge($6, $7) -- binds: "
+        ));
+    }
+
+    #[test]
+    fn binary_containment() {
+        let ip: Ipv4Network = "10.0.0.0/8".parse().unwrap();
+
+        let query = diesel::select(
+            ip_network()
+                .into_sql::<Binary>()
+                .is_contained_by_or_equals(ip),
+        );
+        assert_eq!(
+            "SELECT ($1 BETWEEN $2 AND $3) -- binds: [V4(Ipv4Network { network_address: 192.168.0.0, netmask: 16 }), \
+            [4, 10, 0, 0, 0, 8], [4, 10, 255, 255, 255, 32]]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+
+        let query = diesel::select(ip_network().into_sql::<Binary>().is_contained_by(ip));
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("SELECT (($1 > $2) AND ($3 <= $4)) -- binds"));
+
+        let query = diesel::select(ip_network().into_sql::<Binary>().contains(ip));
+        assert!(debug_query::<Pg, _>(&query).to_string().contains(
+            "[[4, 10, 0, 0, 0, 7], [4, 8, 0, 0, 0, 6], [4, 8, 0, 0, 0, 5], [4, 0, 0, 0, 0, 4], \
+            [4, 0, 0, 0, 0, 3], [4, 0, 0, 0, 0, 2], [4, 0, 0, 0, 0, 1], [4, 0, 0, 0, 0, 0]]"
+        ));
+
+        let query = diesel::select(ip_network().into_sql::<Binary>().contains_or_equals(ip));
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .contains("[[4, 10, 0, 0, 0, 8], [4, 10, 0, 0, 0, 7]"));
+    }
+
+    #[cfg(feature = "diesel2-sqlite")]
+    #[test]
+    fn sqlite() {
+        use diesel::sql_types::Text;
+
+        let mut connection = SqliteConnection::establish(":memory:").unwrap();
+        let ipv4_network: Ipv4Network = "10.0.0.0/8".parse().unwrap();
+        let ipv6_network: Ipv6Network = "2001:db8::/32".parse().unwrap();
+
+        let (text, binary): (IpNetwork, Ipv6Network) = diesel::select((
+            ipv4_network.into_sql::<Text>(),
+            ipv6_network.into_sql::<Binary>(),
+        ))
+        .get_result(&mut connection)
+        .unwrap();
+        assert_eq!(IpNetwork::V4(ipv4_network), text);
+        assert_eq!(ipv6_network, binary);
+
+        let text: String = diesel::select(ipv4_network.into_sql::<Text>().concat(""))
+            .get_result(&mut connection)
+            .unwrap();
+        assert_eq!("10.0.0.0/8", text);
+
+        let subnet: Ipv4Network = "10.1.0.0/16".parse().unwrap();
+        let contained: (bool, bool, bool, bool) = diesel::select((
+            subnet.into_sql::<Binary>().is_contained_by(ipv4_network),
+            ipv4_network
+                .into_sql::<Binary>()
+                .is_contained_by(ipv4_network),
+            ipv4_network.into_sql::<Binary>().contains(subnet),
+            ipv4_network
+                .into_sql::<Binary>()
+                .contains_or_equals(ipv4_network),
+        ))
+        .get_result(&mut connection)
+        .unwrap();
+        assert_eq!((true, false, true, true), contained);
+
+        let result = diesel::select(vec![4u8, 10].into_sql::<Binary>())
+            .get_result::<Ipv4Network>(&mut connection);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! Code shared by Diesel and Diesel 2 support.
+
+/// Defines extension trait with PostgreSQL network address operators for expressions of given
+/// SQL type. Operator types are resolved in the module, where the macro is used.
+macro_rules! pq_extension_methods {
+    ($(#[$attr:meta])* $name:ident, $sql_type:ident) => {
+        $(#[$attr])*
+        #[allow(clippy::wrong_self_convention)]
+        pub trait $name: Expression<SqlType = $sql_type> + Sized {
+            /// Creates a SQL `<<` expression.
+            fn is_contained_by<T>(self, other: T) -> IsContainedBy<Self, T::Expression>
+            where
+                T: AsExpression<Self::SqlType>,
+            {
+                IsContainedBy::new(self, other.as_expression())
+            }
+
+            /// Creates a SQL `<<=` expression.
+            fn is_contained_by_or_equals<T>(self, other: T) -> IsContainedByOrEquals<Self, T::Expression>
+            where
+                T: AsExpression<Self::SqlType>,
+            {
+                IsContainedByOrEquals::new(self, other.as_expression())
+            }
+
+            /// Creates a SQL `>>` expression.
+            fn contains<T>(self, other: T) -> Contains<Self, T::Expression>
+            where
+                T: AsExpression<Self::SqlType>,
+            {
+                Contains::new(self, other.as_expression())
+            }
+
+            /// Creates a SQL `>>=` expression.
+            fn contains_or_equals<T>(self, other: T) -> ContainsOrEquals<Self, T::Expression>
+            where
+                T: AsExpression<Self::SqlType>,
+            {
+                ContainsOrEquals::new(self, other.as_expression())
+            }
+
+            /// Creates a SQL `&&` expression.
+            fn contains_or_is_contained_by<T>(
+                self,
+                other: T,
+            ) -> ContainsOrIsContainedBy<Self, T::Expression>
+            where
+                T: AsExpression<Self::SqlType>,
+            {
+                ContainsOrIsContainedBy::new(self, other.as_expression())
+            }
+
+            /// Creates a SQL `~` expression, bitwise NOT. Returns `Inet`.
+            fn bitwise_not(self) -> BitwiseNot<Self> {
+                BitwiseNot::new(self)
+            }
+
+            /// Creates a SQL `&` expression, bitwise AND. Returns `Inet`.
+            fn bitwise_and<T>(self, other: T) -> BitwiseAnd<Self, T::Expression>
+            where
+                T: AsExpression<Self::SqlType>,
+            {
+                BitwiseAnd::new(self, other.as_expression())
+            }
+
+            /// Creates a SQL `|` expression, bitwise OR. Returns `Inet`.
+            fn bitwise_or<T>(self, other: T) -> BitwiseOr<Self, T::Expression>
+            where
+                T: AsExpression<Self::SqlType>,
+            {
+                BitwiseOr::new(self, other.as_expression())
+            }
+
+            /// Creates a SQL `+` expression that adds offset to address. Returns `Inet`.
+            fn add_offset<T>(self, offset: T) -> AddOffset<Self, T::Expression>
+            where
+                T: AsExpression<BigInt>,
+            {
+                AddOffset::new(self, offset.as_expression())
+            }
+
+            /// Creates a SQL `-` expression that subtracts offset from address. Returns `Inet`.
+            fn subtract_offset<T>(self, offset: T) -> SubtractOffset<Self, T::Expression>
+            where
+                T: AsExpression<BigInt>,
+            {
+                SubtractOffset::new(self, offset.as_expression())
+            }
+
+            /// Creates a SQL `-` expression that computes difference between two addresses. Returns `BigInt`.
+            fn difference<T>(self, other: T) -> Difference<Self, T::Expression>
+            where
+                T: AsExpression<Self::SqlType>,
+            {
+                Difference::new(self, other.as_expression())
+            }
+        }
+
+        impl<T> $name for T where T: Expression<SqlType = $sql_type> {}
+    };
+}
+
+pub(crate) use pq_extension_methods;
+
+/// Defines extension trait with containment queries for networks in binary encoding. Types are
+/// resolved in the module, where the macro is used.
+macro_rules! binary_extension_methods {
+    () => {
+        /// Network containment queries for networks stored in order-preserving binary encoding
+        /// as `Binary` SQL type, that is useful for databases without network types like SQLite
+        /// or MySQL.
+        ///
+        /// Network is encoded as family byte (`4` or `6`), network address octets and netmask
+        /// byte, so queries for networks contained in given network can use index on the column.
+        pub trait BinaryNetworkExtensionMethods: Expression<SqlType = Binary> + Sized {
+            /// Creates a SQL expression that is true if stored network is contained by `network`
+            /// and is not equal to it.
+            #[allow(clippy::wrong_self_convention)]
+            fn is_contained_by<N>(self, network: N) -> And<Gt<Self, Vec<u8>>, LtEq<Self, Vec<u8>>>
+            where
+                N: Into<IpNetwork>,
+                Self: Clone,
+            {
+                let (first, last) = binary_common::contained_range(&network.into());
+                self.clone().gt(first).and(self.le(last))
+            }
+
+            /// Creates a SQL expression that is true if stored network is contained by `network`
+            /// or is equal to it.
+            #[allow(clippy::wrong_self_convention)]
+            fn is_contained_by_or_equals<N>(self, network: N) -> Between<Self, Vec<u8>, Vec<u8>>
+            where
+                N: Into<IpNetwork>,
+            {
+                let (first, last) = binary_common::contained_range(&network.into());
+                self.between(first, last)
+            }
+
+            /// Creates a SQL expression that is true if stored network contains `network` and is
+            /// not equal to it.
+            fn contains<N>(self, network: N) -> EqAny<Self, Vec<Vec<u8>>>
+            where
+                N: Into<IpNetwork>,
+            {
+                self.eq_any(binary_common::supernets(&network.into()))
+            }
+
+            /// Creates a SQL expression that is true if stored network contains `network` or is
+            /// equal to it.
+            fn contains_or_equals<N>(self, network: N) -> EqAny<Self, Vec<Vec<u8>>>
+            where
+                N: Into<IpNetwork>,
+            {
+                let network = network.into();
+                let mut networks = binary_common::supernets(&network);
+                networks.insert(0, binary_common::to_binary_ip_network(&network));
+                self.eq_any(networks)
+            }
+        }
+
+        impl<T> BinaryNetworkExtensionMethods for T where T: Expression<SqlType = Binary> {}
+    };
+}
+
+pub(crate) use binary_extension_methods;
+
+/// Implements text and binary encoding of networks for backends without network types, like
+/// SQLite or MySQL. `FromSql` is implemented for every backend `DB` with `$raw_value` as raw value
+/// type, `ToSql` is implemented by `$to_sql` macro called with network type and binary encoding
+/// function.
+macro_rules! text_and_binary_impl {
+    ($raw_value:ty, $to_sql:ident) => {
+        text_and_binary_impl!(
+            @network Ipv4Network,
+            binary_common::to_binary_ipv4_network,
+            binary_common::from_binary_ipv4_network,
+            $raw_value,
+            $to_sql
+        );
+        text_and_binary_impl!(
+            @network Ipv6Network,
+            binary_common::to_binary_ipv6_network,
+            binary_common::from_binary_ipv6_network,
+            $raw_value,
+            $to_sql
+        );
+        text_and_binary_impl!(
+            @network IpNetwork,
+            binary_common::to_binary_ip_network,
+            binary_common::from_binary_ip_network,
+            $raw_value,
+            $to_sql
+        );
+    };
+    (@network $t:ty, $to_binary:path, $from_binary:path, $raw_value:ty, $to_sql:ident) => {
+        impl<DB> FromSql<Text, DB> for $t
+        where
+            DB: Backend,
+            String: FromSql<Text, DB>,
+        {
+            fn from_sql(bytes: $raw_value) -> deserialize::Result<Self> {
+                let string = <String as FromSql<Text, DB>>::from_sql(bytes)?;
+                Ok(string.parse()?)
+            }
+        }
+
+        impl<DB> FromSql<Binary, DB> for $t
+        where
+            DB: Backend,
+            Vec<u8>: FromSql<Binary, DB>,
+        {
+            fn from_sql(bytes: $raw_value) -> deserialize::Result<Self> {
+                let bytes = <Vec<u8> as FromSql<Binary, DB>>::from_sql(bytes)?;
+                Ok($from_binary(&bytes)?)
+            }
+        }
+
+        $to_sql!($t, $to_binary);
+    };
+}
+
+pub(crate) use text_and_binary_impl;
@@ -1,51 +1,162 @@
 use std::io::Write;
+use diesel::backend::Backend;
 use diesel::expression::{AsExpression, Expression};
 use diesel::pg::Pg;
+use diesel::query_source::Queryable;
+use diesel::row::Row;
 use diesel::serialize::{self, IsNull, Output, ToSql};
-use diesel::deserialize::{self, FromSql};
-use diesel::sql_types::Cidr;
-use crate::{IpNetwork, Ipv4Network, Ipv6Network};
-use crate::postgres_common;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::dsl::{And, Between, EqAny, Gt, LtEq};
+use diesel::{BoolExpressionMethods, ExpressionMethods};
+use diesel::sql_types::{BigInt, Binary, Cidr, Inet, SingleValue, Text};
+use crate::{IpInterface, IpNetwork, Ipv4Interface, Ipv4Network, Ipv6Interface, Ipv6Network};
+use crate::binary_common;
+use crate::diesel_common::{binary_extension_methods, pq_extension_methods, text_and_binary_impl};
+use crate::postgres_common::{self, SqlType, Truncated};
 
 impl FromSql<Cidr, Pg> for Ipv4Network {
     fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
         let bytes = not_none!(bytes);
-        postgres_common::from_sql_ipv4_network(bytes)
+        Ok(postgres_common::from_sql_ipv4_network(
+            bytes,
+            SqlType::Cidr,
+            false,
+        )?)
     }
 }
 
 impl FromSql<Cidr, Pg> for Ipv6Network {
     fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
         let bytes = not_none!(bytes);
-        postgres_common::from_sql_ipv6_network(bytes)
+        Ok(postgres_common::from_sql_ipv6_network(
+            bytes,
+            SqlType::Cidr,
+            false,
+        )?)
     }
 }
 
 impl FromSql<Cidr, Pg> for IpNetwork {
     fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
         let bytes = not_none!(bytes);
-        match bytes[0] {
-            postgres_common::IPV4_TYPE => Ok(IpNetwork::V4(
-                postgres_common::from_sql_ipv4_network(bytes)?,
-            )),
-            postgres_common::IPV6_TYPE => Ok(IpNetwork::V6(
-                postgres_common::from_sql_ipv6_network(bytes)?,
-            )),
-            _ => Err("CIDR is not IP version 4 or 6".into()),
-        }
+        Ok(postgres_common::from_sql_ip_network(
+            bytes,
+            SqlType::Cidr,
+            false,
+        )?)
+    }
+}
+
+impl FromSql<Inet, Pg> for Ipv4Network {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let bytes = not_none!(bytes);
+        Ok(postgres_common::from_sql_ipv4_network(
+            bytes,
+            SqlType::Inet,
+            false,
+        )?)
+    }
+}
+
+impl FromSql<Inet, Pg> for Ipv6Network {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let bytes = not_none!(bytes);
+        Ok(postgres_common::from_sql_ipv6_network(
+            bytes,
+            SqlType::Inet,
+            false,
+        )?)
+    }
+}
+
+impl FromSql<Inet, Pg> for IpNetwork {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let bytes = not_none!(bytes);
+        Ok(postgres_common::from_sql_ip_network(
+            bytes,
+            SqlType::Inet,
+            false,
+        )?)
+    }
+}
+
+impl FromSql<Inet, Pg> for Truncated<Ipv4Network> {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let bytes = not_none!(bytes);
+        let network = postgres_common::from_sql_ipv4_network(bytes, SqlType::Inet, true)?;
+        Ok(Truncated(network))
+    }
+}
+
+impl FromSql<Inet, Pg> for Truncated<Ipv6Network> {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let bytes = not_none!(bytes);
+        let network = postgres_common::from_sql_ipv6_network(bytes, SqlType::Inet, true)?;
+        Ok(Truncated(network))
+    }
+}
+
+impl FromSql<Inet, Pg> for Truncated<IpNetwork> {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let bytes = not_none!(bytes);
+        let network = postgres_common::from_sql_ip_network(bytes, SqlType::Inet, true)?;
+        Ok(Truncated(network))
+    }
+}
+
+impl<N, ST, DB> FromSqlRow<ST, DB> for Truncated<N>
+where
+    DB: Backend,
+    Truncated<N>: FromSql<ST, DB>,
+{
+    fn build_from_row<R: Row<DB>>(row: &mut R) -> deserialize::Result<Self> {
+        FromSql::<ST, DB>::from_sql(row.take())
+    }
+}
+
+impl<N, ST, DB> Queryable<ST, DB> for Truncated<N>
+where
+    DB: Backend,
+    Truncated<N>: FromSqlRow<ST, DB>,
+{
+    type Row = Self;
+
+    fn build(row: Self::Row) -> Self {
+        row
+    }
+}
+
+impl FromSql<Inet, Pg> for Ipv4Interface {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let bytes = not_none!(bytes);
+        Ok(postgres_common::from_sql_ipv4_interface(bytes)?)
+    }
+}
+
+impl FromSql<Inet, Pg> for Ipv6Interface {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let bytes = not_none!(bytes);
+        Ok(postgres_common::from_sql_ipv6_interface(bytes)?)
+    }
+}
+
+impl FromSql<Inet, Pg> for IpInterface {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let bytes = not_none!(bytes);
+        Ok(postgres_common::from_sql_ip_interface(bytes)?)
     }
 }
 
 impl ToSql<Cidr, Pg> for Ipv4Network {
     fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
-        let data = postgres_common::to_sql_ipv4_network(self);
+        let data = postgres_common::to_sql_ipv4_network(self, SqlType::Cidr);
         out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
     }
 }
 
 impl ToSql<Cidr, Pg> for Ipv6Network {
     fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
-        let data = postgres_common::to_sql_ipv6_network(self);
+        let data = postgres_common::to_sql_ipv6_network(self, SqlType::Cidr);
         out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
     }
 }
@@ -59,6 +170,79 @@ impl ToSql<Cidr, Pg> for IpNetwork {
     }
 }
 
+impl ToSql<Inet, Pg> for Ipv4Network {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        let data = postgres_common::to_sql_ipv4_network(self, SqlType::Inet);
+        out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
+    }
+}
+
+impl ToSql<Inet, Pg> for Ipv6Network {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        let data = postgres_common::to_sql_ipv6_network(self, SqlType::Inet);
+        out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
+    }
+}
+
+impl ToSql<Inet, Pg> for IpNetwork {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        match self {
+            IpNetwork::V4(network) => ToSql::<Inet, Pg>::to_sql(network, out),
+            IpNetwork::V6(network) => ToSql::<Inet, Pg>::to_sql(network, out),
+        }
+    }
+}
+
+impl ToSql<Inet, Pg> for Ipv4Interface {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        let data = postgres_common::to_sql_ipv4_interface(self);
+        out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
+    }
+}
+
+impl ToSql<Inet, Pg> for Ipv6Interface {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        let data = postgres_common::to_sql_ipv6_interface(self);
+        out.write_all(&data).map(|_| IsNull::No).map_err(Into::into)
+    }
+}
+
+impl ToSql<Inet, Pg> for IpInterface {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        match self {
+            IpInterface::V4(interface) => ToSql::<Inet, Pg>::to_sql(interface, out),
+            IpInterface::V6(interface) => ToSql::<Inet, Pg>::to_sql(interface, out),
+        }
+    }
+}
+
+/// Implements `ToSql` for text and binary encoding for every backend.
+macro_rules! text_and_binary_to_sql_impl {
+    ($t:ty, $to_binary:path) => {
+        impl<DB> ToSql<Text, DB> for $t
+        where
+            DB: Backend,
+            str: ToSql<Text, DB>,
+        {
+            fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> serialize::Result {
+                ToSql::<Text, DB>::to_sql(self.to_string().as_str(), out)
+            }
+        }
+
+        impl<DB> ToSql<Binary, DB> for $t
+        where
+            DB: Backend,
+            [u8]: ToSql<Binary, DB>,
+        {
+            fn to_sql<W: Write>(&self, out: &mut Output<W, DB>) -> serialize::Result {
+                ToSql::<Binary, DB>::to_sql(&$to_binary(self)[..], out)
+            }
+        }
+    };
+}
+
+text_and_binary_impl!(Option<&DB::RawValue>, text_and_binary_to_sql_impl);
+
 #[allow(dead_code)]
 mod foreign_derives {
     use super::*;
@@ -66,17 +250,41 @@ mod foreign_derives {
     #[derive(FromSqlRow, AsExpression)]
     #[diesel(foreign_derive)]
     #[sql_type = "Cidr"]
+    #[sql_type = "Inet"]
+    #[sql_type = "Text"]
+    #[sql_type = "Binary"]
     struct IpNetworkProxy(IpNetwork);
 
     #[derive(FromSqlRow, AsExpression)]
     #[diesel(foreign_derive)]
     #[sql_type = "Cidr"]
+    #[sql_type = "Inet"]
+    #[sql_type = "Text"]
+    #[sql_type = "Binary"]
     struct Ipv4NetworkProxy(Ipv4Network);
 
     #[derive(FromSqlRow, AsExpression)]
     #[diesel(foreign_derive)]
     #[sql_type = "Cidr"]
+    #[sql_type = "Inet"]
+    #[sql_type = "Text"]
+    #[sql_type = "Binary"]
     struct Ipv6NetworkProxy(Ipv6Network);
+
+    #[derive(FromSqlRow, AsExpression)]
+    #[diesel(foreign_derive)]
+    #[sql_type = "Inet"]
+    struct IpInterfaceProxy(IpInterface);
+
+    #[derive(FromSqlRow, AsExpression)]
+    #[diesel(foreign_derive)]
+    #[sql_type = "Inet"]
+    struct Ipv4InterfaceProxy(Ipv4Interface);
+
+    #[derive(FromSqlRow, AsExpression)]
+    #[diesel(foreign_derive)]
+    #[sql_type = "Inet"]
+    struct Ipv6InterfaceProxy(Ipv6Interface);
 }
 
 diesel_infix_operator!(IsContainedBy, " << ", backend: Pg);
@@ -84,60 +292,41 @@ diesel_infix_operator!(IsContainedByOrEquals, " <<= ", backend: Pg);
 diesel_infix_operator!(Contains, " >> ", backend: Pg);
 diesel_infix_operator!(ContainsOrEquals, " >>= ", backend: Pg);
 diesel_infix_operator!(ContainsOrIsContainedBy, " && ", backend: Pg);
+diesel_prefix_operator!(BitwiseNot, "~", Inet, backend: Pg);
+diesel_infix_operator!(BitwiseAnd, " & ", Inet, backend: Pg);
+diesel_infix_operator!(BitwiseOr, " | ", Inet, backend: Pg);
+diesel_infix_operator!(AddOffset, " + ", Inet, backend: Pg);
+diesel_infix_operator!(SubtractOffset, " - ", Inet, backend: Pg);
+diesel_infix_operator!(Difference, " - ", BigInt, backend: Pg);
 
-/// Support for PostgreSQL Network Address Operators for Diesel
-///
-/// See [PostgreSQL documentation for details](https://www.postgresql.org/docs/current/static/functions-net.html).
-pub trait PqCidrExtensionMethods: Expression<SqlType = Cidr> + Sized {
-    /// Creates a SQL `<<` expression.
-    fn is_contained_by<T>(self, other: T) -> IsContainedBy<Self, T::Expression>
-    where
-        T: AsExpression<Self::SqlType>,
-    {
-        IsContainedBy::new(self, other.as_expression())
-    }
-
-    /// Creates a SQL `<<=` expression.
-    fn is_contained_by_or_equals<T>(self, other: T) -> IsContainedByOrEquals<Self, T::Expression>
-    where
-        T: AsExpression<Self::SqlType>,
-    {
-        IsContainedByOrEquals::new(self, other.as_expression())
-    }
-
-    /// Creates a SQL `>>` expression.
-    fn contains<T>(self, other: T) -> Contains<Self, T::Expression>
-    where
-        T: AsExpression<Self::SqlType>,
-    {
-        Contains::new(self, other.as_expression())
-    }
-
-    /// Creates a SQL `>>=` expression.
-    fn contains_or_equals<T>(self, other: T) -> ContainsOrEquals<Self, T::Expression>
-    where
-        T: AsExpression<Self::SqlType>,
-    {
-        ContainsOrEquals::new(self, other.as_expression())
-    }
-
-    /// Creates a SQL `&&` expression.
-    fn contains_or_is_contained_by<T>(
-        self,
-        other: T,
-    ) -> ContainsOrIsContainedBy<Self, T::Expression>
-    where
-        T: AsExpression<Self::SqlType>,
-    {
-        ContainsOrIsContainedBy::new(self, other.as_expression())
-    }
-}
+pq_extension_methods!(
+    /// Support for PostgreSQL Network Address Operators for Diesel
+    ///
+    /// See [PostgreSQL documentation for details](https://www.postgresql.org/docs/current/static/functions-net.html).
+    PqCidrExtensionMethods,
+    Cidr
+);
+
+pq_extension_methods!(
+    /// Support for PostgreSQL Network Address Operators for Diesel on INET expressions
+    ///
+    /// See [PostgreSQL documentation for details](https://www.postgresql.org/docs/current/static/functions-net.html).
+    PqInetExtensionMethods,
+    Inet
+);
+
+binary_extension_methods!();
+
+/// SQL types for IP networks, that are accepted by network functions.
+pub trait IpNetworkSqlType: SingleValue {}
 
-impl<T> PqCidrExtensionMethods for T where T: Expression<SqlType = Cidr> {}
+impl IpNetworkSqlType for Cidr {}
+impl IpNetworkSqlType for Inet {}
 
-/// CIDR functions.
+/// CIDR and INET functions.
 pub mod functions {
-    use diesel::sql_types::Cidr;
+    use diesel::sql_types::{Cidr, Inet, Integer};
+    use super::IpNetworkSqlType;
 
     sql_function! {
         /// Extract family of address; 4 for IPv4, 6 for IPv6.
@@ -147,11 +336,62 @@ pub mod functions {
         /// Extract netmask length.
         fn masklen(x: Cidr) -> Integer;
     }
+    sql_function! {
+        /// Abbreviated display format as text.
+        fn abbrev<ST: IpNetworkSqlType>(x: ST) -> Text;
+    }
+    sql_function! {
+        /// Broadcast address for network.
+        fn broadcast<ST: IpNetworkSqlType>(x: ST) -> Inet;
+    }
+    sql_function! {
+        /// Extract IP address as text.
+        fn host<ST: IpNetworkSqlType>(x: ST) -> Text;
+    }
+    sql_function! {
+        /// Construct host mask for network.
+        fn hostmask<ST: IpNetworkSqlType>(x: ST) -> Inet;
+    }
+    sql_function! {
+        /// Construct netmask for network.
+        fn netmask<ST: IpNetworkSqlType>(x: ST) -> Inet;
+    }
+    sql_function! {
+        /// Extract network part of address.
+        fn network<ST: IpNetworkSqlType>(x: ST) -> Cidr;
+    }
+    sql_function! {
+        /// Set netmask length, returns value of the same type.
+        fn set_masklen<ST: IpNetworkSqlType>(x: ST, len: Integer) -> ST;
+    }
+    sql_function! {
+        /// Extract IP address and netmask length as text.
+        fn text<ST: IpNetworkSqlType>(x: ST) -> Text;
+    }
+    sql_function! {
+        /// Are the addresses from the same family?
+        fn inet_same_family<L: IpNetworkSqlType, R: IpNetworkSqlType>(x: L, y: R) -> Bool;
+    }
+    sql_function! {
+        /// The smallest network which includes both of the given networks.
+        fn inet_merge<L: IpNetworkSqlType, R: IpNetworkSqlType>(x: L, y: R) -> Cidr;
+    }
 }
 
 pub mod helper_types {
     pub type Family<Expr> = super::functions::family::HelperType<Expr>;
     pub type Masklen<Expr> = super::functions::masklen::HelperType<Expr>;
+    pub type Abbrev<ST, Expr> = super::functions::abbrev::HelperType<ST, Expr>;
+    pub type Broadcast<ST, Expr> = super::functions::broadcast::HelperType<ST, Expr>;
+    pub type Host<ST, Expr> = super::functions::host::HelperType<ST, Expr>;
+    pub type Hostmask<ST, Expr> = super::functions::hostmask::HelperType<ST, Expr>;
+    pub type Netmask<ST, Expr> = super::functions::netmask::HelperType<ST, Expr>;
+    pub type Network<ST, Expr> = super::functions::network::HelperType<ST, Expr>;
+    pub type SetMasklen<ST, Expr, Len> = super::functions::set_masklen::HelperType<ST, Expr, Len>;
+    pub type Text<ST, Expr> = super::functions::text::HelperType<ST, Expr>;
+    pub type InetSameFamily<L, R, X, Y> =
+        super::functions::inet_same_family::HelperType<L, R, X, Y>;
+    pub type InetMerge<L, R, X, Y> = super::functions::inet_merge::HelperType<L, R, X, Y>;
 }
 
 pub mod dsl {
@@ -162,15 +402,16 @@ pub mod dsl {
 #[cfg(test)]
 mod tests {
     use std::net::{Ipv4Addr, Ipv6Addr};
-    use diesel::sql_types::Cidr;
+    use diesel::sql_types::{BigInt, Binary, Cidr, Inet, Text};
     use diesel::pg::Pg;
     use diesel::serialize::{Output, ToSql};
-    use diesel::deserialize::FromSql;
+    use diesel::deserialize::{self, FromSql};
     use diesel::prelude::*;
     use diesel::debug_query;
-    use super::PqCidrExtensionMethods;
-    use super::{IpNetwork, Ipv4Network, Ipv6Network};
+    use super::{BinaryNetworkExtensionMethods, PqCidrExtensionMethods, PqInetExtensionMethods};
+    use super::{IpInterface, IpNetwork, Ipv4Network, Ipv6Network};
     use super::dsl::*;
+    use crate::{CidrDecodeError, Truncated};
 
     table! {
         test {
@@ -178,9 +419,19 @@ mod tests {
             ip_network -> Cidr,
             ipv4_network -> Cidr,
             ipv6_network -> Cidr,
+            ip_interface -> Inet,
+            ip_text -> Text,
+            ip_binary -> Binary,
         }
     }
 
+    #[derive(Insertable, Queryable)]
+    #[table_name = "test"]
+    pub struct Edge {
+        pub ip_text: IpNetwork,
+        pub ip_binary: IpNetwork,
+    }
+
     #[derive(Insertable)]
     #[table_name = "test"]
     pub struct NewPost {
@@ -188,6 +439,7 @@ mod tests {
         pub ip_network: IpNetwork,
         pub ipv4_network: Ipv4Network,
         pub ipv6_network: Ipv6Network,
+        pub ip_interface: IpInterface,
     }
 
     fn test_output() -> Output<'static, Vec<u8>, Pg> {
@@ -224,6 +476,46 @@ mod tests {
         assert_eq!(ip_network, converted);
     }
 
+    #[test]
+    fn ip_network_malformed() {
+        let result: deserialize::Result<IpNetwork> = FromSql::<Cidr, Pg>::from_sql(Some(&[3, 32]));
+        let error = result.unwrap_err();
+        assert_eq!(
+            Some(&CidrDecodeError::BadLength {
+                expected: 20,
+                actual: 2
+            }),
+            error.downcast_ref::<CidrDecodeError>()
+        );
+    }
+
+    #[test]
+    fn ip_interface_inet() {
+        let mut bytes = test_output();
+        let ip_interface: IpInterface = "2001:db8::1/64".parse().unwrap();
+        ToSql::<Inet, Pg>::to_sql(&ip_interface, &mut bytes).unwrap();
+        let converted: IpInterface = FromSql::<Inet, Pg>::from_sql(Some(bytes.as_ref())).unwrap();
+        assert_eq!(ip_interface, converted);
+
+        let result: deserialize::Result<IpNetwork> =
+            FromSql::<Inet, Pg>::from_sql(Some(bytes.as_ref()));
+        assert!(result.is_err());
+
+        let Truncated(network): Truncated<IpNetwork> =
+            FromSql::<Inet, Pg>::from_sql(Some(bytes.as_ref())).unwrap();
+        assert_eq!(ip_interface.network(), network);
+    }
+
+    #[test]
+    fn ip_network_inet() {
+        let mut bytes = test_output();
+        let ip_network: IpNetwork = "192.168.0.0/16".parse().unwrap();
+        ToSql::<Inet, Pg>::to_sql(&ip_network, &mut bytes).unwrap();
+        assert_eq!(0, bytes[2]);
+        let converted: IpNetwork = FromSql::<Inet, Pg>::from_sql(Some(bytes.as_ref())).unwrap();
+        assert_eq!(ip_network, converted);
+    }
+
     #[test]
     fn operators() {
         let ip = IpNetwork::new(Ipv4Addr::new(127, 0, 0, 1), 32).unwrap();
@@ -253,4 +545,177 @@ mod tests {
             string_query
         );
     }
+
+    #[test]
+    fn inet_operators() {
+        let ip: IpInterface = "127.0.0.1/8".parse().unwrap();
+        test::ip_interface.is_contained_by(&ip);
+        test::ip_interface.contains_or_is_contained_by(&ip);
+
+        let query = test::table.select(test::ip_interface.bitwise_not());
+        assert_eq!(
+            "SELECT ~\"test\".\"ip_interface\" FROM \"test\" -- binds: []",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+
+        let query = test::table.select(test::ip_interface.bitwise_and(&ip));
+        assert_eq!(
+            "SELECT \"test\".\"ip_interface\" & $1 FROM \"test\" \
+            -- binds: [V4(Ipv4Interface { address: 127.0.0.1, netmask: 8 })]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+
+        let query = test::table.select(test::ip_interface.bitwise_or(&ip));
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("SELECT \"test\".\"ip_interface\" | $1 FROM \"test\""));
+
+        let query = test::table.select(test::ip_interface.add_offset(10));
+        assert_eq!(
+            "SELECT \"test\".\"ip_interface\" + $1 FROM \"test\" -- binds: [10]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+
+        let query = test::table.select(test::ip_interface.subtract_offset(10));
+        assert_eq!(
+            "SELECT \"test\".\"ip_interface\" - $1 FROM \"test\" -- binds: [10]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+
+        let query = test::table.select(test::ip_interface.difference(&ip));
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("SELECT \"test\".\"ip_interface\" - $1 FROM \"test\""));
+    }
+
+    #[test]
+    fn operators_return_types() {
+        fn sql_type<E: Expression>(_: E) -> std::marker::PhantomData<E::SqlType> {
+            std::marker::PhantomData
+        }
+        let _: std::marker::PhantomData<Inet> = sql_type(test::ip_network.bitwise_not());
+        let _: std::marker::PhantomData<Inet> =
+            sql_type(test::ip_network.bitwise_and(test::ip_network));
+        let _: std::marker::PhantomData<Inet> = sql_type(test::ip_network.add_offset(1));
+        let _: std::marker::PhantomData<BigInt> =
+            sql_type(test::ip_network.difference(test::ip_network));
+        let _: std::marker::PhantomData<Cidr> = sql_type(network(test::ip_interface));
+        let _: std::marker::PhantomData<Cidr> = sql_type(set_masklen(test::ip_network, 8));
+        let _: std::marker::PhantomData<Inet> = sql_type(set_masklen(test::ip_interface, 8));
+        let _: std::marker::PhantomData<Cidr> =
+            sql_type(inet_merge(test::ip_interface, test::ip_network));
+    }
+
+    #[test]
+    fn functions() {
+        let query = test::table.select((
+            abbrev(test::ip_network),
+            broadcast(test::ip_interface),
+            host(test::ip_interface),
+            hostmask(test::ip_network),
+            netmask(test::ip_interface),
+            network(test::ip_interface),
+        ));
+        assert_eq!(
+            "SELECT abbrev(\"test\".\"ip_network\"), broadcast(\"test\".\"ip_interface\"), \
+            host(\"test\".\"ip_interface\"), hostmask(\"test\".\"ip_network\"), \
+            netmask(\"test\".\"ip_interface\"), network(\"test\".\"ip_interface\") FROM \"test\" -- binds: []",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+
+        let query = test::table.select((
+            set_masklen(test::ip_interface, 16),
+            text(test::ip_interface),
+            inet_same_family(test::ip_interface, test::ip_network),
+            inet_merge(test::ip_interface, test::ip_network),
+        ));
+        assert_eq!(
+            "SELECT set_masklen(\"test\".\"ip_interface\", $1), text(\"test\".\"ip_interface\"), \
+            inet_same_family(\"test\".\"ip_interface\", \"test\".\"ip_network\"), \
+            inet_merge(\"test\".\"ip_interface\", \"test\".\"ip_network\") FROM \"test\" -- binds: [16]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+    }
+
+    #[test]
+    fn ip_network_text() {
+        let mut bytes = test_output();
+        let ip_network: IpNetwork = "2001:db8::/32".parse().unwrap();
+        ToSql::<Text, Pg>::to_sql(&ip_network, &mut bytes).unwrap();
+        assert_eq!(b"2001:db8::/32", &bytes[..]);
+        let converted: IpNetwork = FromSql::<Text, Pg>::from_sql(Some(bytes.as_ref())).unwrap();
+        assert_eq!(ip_network, converted);
+
+        let result: deserialize::Result<Ipv4Network> = FromSql::<Text, Pg>::from_sql(Some(b"a"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn ip_network_binary() {
+        let mut bytes = test_output();
+        let ipv4_network: Ipv4Network = "192.168.0.0/16".parse().unwrap();
+        ToSql::<Binary, Pg>::to_sql(&ipv4_network, &mut bytes).unwrap();
+        assert_eq!(&[4, 192, 168, 0, 0, 16], &bytes[..]);
+        let converted: IpNetwork = FromSql::<Binary, Pg>::from_sql(Some(bytes.as_ref())).unwrap();
+        assert_eq!(IpNetwork::V4(ipv4_network), converted);
+
+        let result: deserialize::Result<Ipv6Network> =
+            FromSql::<Binary, Pg>::from_sql(Some(bytes.as_ref()));
+        assert_eq!(
+            Some(&CidrDecodeError::WrongFamily(4)),
+            result.unwrap_err().downcast_ref::<CidrDecodeError>()
+        );
+    }
+
+    #[test]
+    fn binary_containment() {
+        let ip: Ipv4Network = "10.0.0.0/8".parse().unwrap();
+
+        let query = test::table
+            .select(test::id)
+            .filter(test::ip_binary.is_contained_by_or_equals(ip));
+        assert_eq!(
+            "SELECT \"test\".\"id\" FROM \"test\" WHERE \"test\".\"ip_binary\" BETWEEN $1 AND $2 \
+            -- binds: [[4, 10, 0, 0, 0, 8], [4, 10, 255, 255, 255, 32]]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+
+        let query = test::table
+            .select(test::id)
+            .filter(test::ip_binary.is_contained_by(ip));
+        assert_eq!(
+            "SELECT \"test\".\"id\" FROM \"test\" WHERE \"test\".\"ip_binary\" > $1 \
+            AND \"test\".\"ip_binary\" <= $2 -- binds: [[4, 10, 0, 0, 0, 8], [4, 10, 255, 255, 255, 32]]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+
+        let query = test::table
+            .select(test::id)
+            .filter(test::ip_binary.contains(ip));
+        assert_eq!(
+            "SELECT \"test\".\"id\" FROM \"test\" WHERE \"test\".\"ip_binary\" IN ($1, $2, $3, $4, $5, $6, $7, $8) \
+            -- binds: [[4, 10, 0, 0, 0, 7], [4, 8, 0, 0, 0, 6], [4, 8, 0, 0, 0, 5], [4, 0, 0, 0, 0, 4], \
+            [4, 0, 0, 0, 0, 3], [4, 0, 0, 0, 0, 2], [4, 0, 0, 0, 0, 1], [4, 0, 0, 0, 0, 0]]",
+            debug_query::<Pg, _>(&query).to_string()
+        );
+
+        let query = test::table
+            .select(test::id)
+            .filter(test::ip_binary.contains_or_equals(ip));
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .contains("-- binds: [[4, 10, 0, 0, 0, 8], [4, 10, 0, 0, 0, 7]"));
+    }
+
+    #[test]
+    fn edge_model() {
+        let edge = Edge {
+            ip_text: "10.0.0.0/8".parse().unwrap(),
+            ip_binary: "2001:db8::/32".parse().unwrap(),
+        };
+        let query = diesel::insert_into(test::table).values(&edge);
+        assert!(debug_query::<Pg, _>(&query)
+            .to_string()
+            .starts_with("INSERT INTO \"test\" (\"ip_text\", \"ip_binary\") VALUES ($1, $2)"));
+    }
 }
@@ -1,3 +1,6 @@
+use std::str::FromStr;
+use crate::{IpFamily, IpNetworkError, IpNetworkParseError};
+
 pub fn bit_length(number: u32) -> u8 {
     32 - number.leading_zeros() as u8
 }
@@ -19,10 +22,7 @@ pub fn bite_mask_u128(mask: u8) -> u128 {
 }
 
 pub fn split_ip_netmask(input: &str) -> Option<(&str, &str)> {
-    let delimiter = match input.find('/') {
-        Some(pos) => pos,
-        None => return None,
-    };
+    let delimiter = input.find('/')?;
     let (ip, mask) = input.split_at(delimiter);
     let mask = &mask[1..];
 
@@ -33,9 +33,57 @@ pub fn split_ip_netmask(input: &str) -> Option<(&str, &str)> {
     }
 }
 
+/// Parses input in format `address/netmask` with error spans pointing to the input.
+pub fn parse_ip_netmask<A: FromStr>(
+    input: &str,
+    family: Option<IpFamily>,
+) -> Result<(A, u8), IpNetworkParseError> {
+    let (ip, netmask) = split_ip_netmask(input).ok_or_else(|| {
+        let span = match input.find('/') {
+            None => 0..input.len(),
+            Some(0) => 0..0,
+            Some(_) => input.len()..input.len(),
+        };
+        IpNetworkParseError::InvalidFormatError { span }
+    })?;
+
+    let ip_span = 0..ip.len();
+    let netmask_span = ip.len() + 1..input.len();
+
+    let network_address = A::from_str(ip).map_err(|_| IpNetworkParseError::AddrParseError {
+        span: ip_span,
+        family,
+    })?;
+    let netmask = u8::from_str(netmask)
+        .map_err(|_| IpNetworkParseError::InvalidNetmaskFormat { span: netmask_span })?;
+
+    Ok((network_address, netmask))
+}
+
+/// Parses input in format `address/netmask` and constructs value using `new`. Errors returned by
+/// `new` get span of network mask in input, except of `HostBitsSet` error, that gets span of
+/// address.
+pub fn parse_network<A: FromStr, T, F: FnOnce(A, u8) -> Result<T, IpNetworkError>>(
+    input: &str,
+    family: Option<IpFamily>,
+    new: F,
+) -> Result<T, IpNetworkParseError> {
+    let (address, netmask) = parse_ip_netmask(input, family)?;
+    new(address, netmask).map_err(|error| {
+        let separator = input.find('/').unwrap_or(input.len());
+        let span = match error {
+            IpNetworkError::HostBitsSet { .. } => 0..separator,
+            _ => (separator + 1).min(input.len())..input.len(),
+        };
+        IpNetworkParseError::IpNetworkError { error, span }
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{bite_mask, bite_mask_u128, split_ip_netmask};
+    use std::net::Ipv4Addr;
+    use crate::{IpFamily, IpNetworkParseError};
+    use super::{bite_mask, bite_mask_u128, parse_ip_netmask, split_ip_netmask};
 
     #[test]
     fn get_bite_mask_32() {
@@ -81,4 +129,37 @@ mod tests {
         let a = split_ip_netmask("192.168.1.1/");
         assert!(a.is_none());
     }
+
+    #[test]
+    fn parse_ip_netmask_spans() {
+        let parse = |input| parse_ip_netmask::<Ipv4Addr>(input, Some(IpFamily::V4));
+
+        assert_eq!(
+            Ok((Ipv4Addr::new(192, 168, 1, 0), 24)),
+            parse("192.168.1.0/24")
+        );
+        assert_eq!(
+            Err(IpNetworkParseError::InvalidFormatError { span: 0..11 }),
+            parse("192.168.1.0")
+        );
+        assert_eq!(
+            Err(IpNetworkParseError::InvalidFormatError { span: 0..0 }),
+            parse("/24")
+        );
+        assert_eq!(
+            Err(IpNetworkParseError::InvalidFormatError { span: 12..12 }),
+            parse("192.168.1.0/")
+        );
+        assert_eq!(
+            Err(IpNetworkParseError::AddrParseError {
+                span: 0..11,
+                family: Some(IpFamily::V4)
+            }),
+            parse("192.168.1.x/24")
+        );
+        assert_eq!(
+            Err(IpNetworkParseError::InvalidNetmaskFormat { span: 12..15 }),
+            parse("192.168.1.0/256")
+        );
+    }
 }
@@ -0,0 +1,368 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use crate::{IpFamily, IpNetwork, IpNetworkError, IpNetworkParseError};
+use crate::{Ipv4Network, Ipv6Network};
+use crate::helpers;
+
+/// IPv4 interface, IPv4 address with network mask, that can have host bits set.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct Ipv4Interface {
+    address: Ipv4Addr,
+    netmask: u8,
+}
+
+impl Ipv4Interface {
+    /// Constructs new `Ipv4Interface` based on [`Ipv4Addr`] and `netmask`.
+    ///
+    /// Returns error if netmask is bigger than 32.
+    ///
+    /// [`Ipv4Addr`]: https://doc.rust-lang.org/std/net/struct.Ipv4Addr.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::{Ipv4Interface, Ipv4Network};
+    ///
+    /// let interface = Ipv4Interface::new(Ipv4Addr::new(192, 168, 1, 10), 24)?;
+    /// assert_eq!(interface.address(), Ipv4Addr::new(192, 168, 1, 10));
+    /// assert_eq!(interface.netmask(), 24);
+    /// assert_eq!(interface.network(), Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)?);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn new(address: Ipv4Addr, netmask: u8) -> Result<Self, IpNetworkError> {
+        if netmask > Ipv4Network::LENGTH {
+            return Err(IpNetworkError::NetmaskError {
+                netmask,
+                max_netmask: Ipv4Network::LENGTH,
+            });
+        }
+
+        Ok(Self { address, netmask })
+    }
+
+    /// Returns IP address of interface.
+    #[inline]
+    pub fn address(&self) -> Ipv4Addr {
+        self.address
+    }
+
+    /// Returns network mask as integer.
+    #[inline]
+    pub fn netmask(&self) -> u8 {
+        self.netmask
+    }
+
+    /// Returns network of interface, address with truncated host bits.
+    pub fn network(&self) -> Ipv4Network {
+        Ipv4Network::new_truncate(self.address, self.netmask).unwrap()
+    }
+}
+
+impl fmt::Display for Ipv4Interface {
+    /// Converts `Ipv4Interface` to string in format X.X.X.X/Y.
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}/{}", self.address, self.netmask)
+    }
+}
+
+impl FromStr for Ipv4Interface {
+    type Err = IpNetworkParseError;
+
+    /// Converts string in format X.X.X.X/Y to `Ipv4Interface`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::Ipv4Interface;
+    ///
+    /// let interface: Ipv4Interface = "192.168.1.10/24".parse()?;
+    /// assert_eq!(interface.address(), Ipv4Addr::new(192, 168, 1, 10));
+    /// # Ok::<(), ip_network::IpNetworkParseError>(())
+    /// ```
+    fn from_str(s: &str) -> Result<Self, IpNetworkParseError> {
+        helpers::parse_network(s, Some(IpFamily::V4), Self::new)
+    }
+}
+
+impl From<Ipv4Addr> for Ipv4Interface {
+    /// Converts `Ipv4Addr` to `Ipv4Interface` with netmask 32.
+    #[inline]
+    fn from(address: Ipv4Addr) -> Self {
+        Self {
+            address,
+            netmask: Ipv4Network::LENGTH,
+        }
+    }
+}
+
+impl From<Ipv4Network> for Ipv4Interface {
+    /// Converts `Ipv4Network` to `Ipv4Interface` with network address.
+    #[inline]
+    fn from(network: Ipv4Network) -> Self {
+        Self {
+            address: network.network_address(),
+            netmask: network.netmask(),
+        }
+    }
+}
+
+/// IPv6 interface, IPv6 address with network mask, that can have host bits set.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct Ipv6Interface {
+    address: Ipv6Addr,
+    netmask: u8,
+}
+
+impl Ipv6Interface {
+    /// Constructs new `Ipv6Interface` based on [`Ipv6Addr`] and `netmask`.
+    ///
+    /// Returns error if netmask is bigger than 128.
+    ///
+    /// [`Ipv6Addr`]: https://doc.rust-lang.org/std/net/struct.Ipv6Addr.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::{Ipv6Interface, Ipv6Network};
+    ///
+    /// let interface = Ipv6Interface::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1), 32)?;
+    /// assert_eq!(interface.network(), Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32)?);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn new(address: Ipv6Addr, netmask: u8) -> Result<Self, IpNetworkError> {
+        if netmask > Ipv6Network::LENGTH {
+            return Err(IpNetworkError::NetmaskError {
+                netmask,
+                max_netmask: Ipv6Network::LENGTH,
+            });
+        }
+
+        Ok(Self { address, netmask })
+    }
+
+    /// Returns IP address of interface.
+    #[inline]
+    pub fn address(&self) -> Ipv6Addr {
+        self.address
+    }
+
+    /// Returns network mask as integer.
+    #[inline]
+    pub fn netmask(&self) -> u8 {
+        self.netmask
+    }
+
+    /// Returns network of interface, address with truncated host bits.
+    pub fn network(&self) -> Ipv6Network {
+        Ipv6Network::new_truncate(self.address, self.netmask).unwrap()
+    }
+}
+
+impl fmt::Display for Ipv6Interface {
+    /// Converts `Ipv6Interface` to string in format X:X::X/Y.
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}/{}", self.address, self.netmask)
+    }
+}
+
+impl FromStr for Ipv6Interface {
+    type Err = IpNetworkParseError;
+
+    /// Converts string in format X:X::X/Y to `Ipv6Interface`.
+    fn from_str(s: &str) -> Result<Self, IpNetworkParseError> {
+        helpers::parse_network(s, Some(IpFamily::V6), Self::new)
+    }
+}
+
+impl From<Ipv6Addr> for Ipv6Interface {
+    /// Converts `Ipv6Addr` to `Ipv6Interface` with netmask 128.
+    #[inline]
+    fn from(address: Ipv6Addr) -> Self {
+        Self {
+            address,
+            netmask: Ipv6Network::LENGTH,
+        }
+    }
+}
+
+impl From<Ipv6Network> for Ipv6Interface {
+    /// Converts `Ipv6Network` to `Ipv6Interface` with network address.
+    #[inline]
+    fn from(network: Ipv6Network) -> Self {
+        Self {
+            address: network.network_address(),
+            netmask: network.netmask(),
+        }
+    }
+}
+
+/// Holds IPv4 or IPv6 interface.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum IpInterface {
+    V4(Ipv4Interface),
+    V6(Ipv6Interface),
+}
+
+impl IpInterface {
+    /// Constructs new `IpInterface` based on [`IpAddr`] and `netmask`.
+    ///
+    /// [`IpAddr`]: https://doc.rust-lang.org/std/net/enum.IpAddr.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::{IpAddr, Ipv4Addr};
+    /// use ip_network::IpInterface;
+    ///
+    /// let interface = IpInterface::new(Ipv4Addr::new(192, 168, 1, 10), 24)?;
+    /// assert_eq!(interface.address(), IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)));
+    /// assert_eq!(interface.network().to_string(), "192.168.1.0/24");
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn new<I: Into<IpAddr>>(address: I, netmask: u8) -> Result<Self, IpNetworkError> {
+        Ok(match address.into() {
+            IpAddr::V4(ip) => IpInterface::V4(Ipv4Interface::new(ip, netmask)?),
+            IpAddr::V6(ip) => IpInterface::V6(Ipv6Interface::new(ip, netmask)?),
+        })
+    }
+
+    /// Returns IP address of interface.
+    pub fn address(&self) -> IpAddr {
+        match self {
+            IpInterface::V4(interface) => IpAddr::V4(interface.address()),
+            IpInterface::V6(interface) => IpAddr::V6(interface.address()),
+        }
+    }
+
+    /// Returns network mask as integer.
+    pub fn netmask(&self) -> u8 {
+        match self {
+            IpInterface::V4(interface) => interface.netmask(),
+            IpInterface::V6(interface) => interface.netmask(),
+        }
+    }
+
+    /// Returns network of interface, address with truncated host bits.
+    pub fn network(&self) -> IpNetwork {
+        match self {
+            IpInterface::V4(interface) => IpNetwork::V4(interface.network()),
+            IpInterface::V6(interface) => IpNetwork::V6(interface.network()),
+        }
+    }
+}
+
+impl fmt::Display for IpInterface {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IpInterface::V4(ref interface) => interface.fmt(f),
+            IpInterface::V6(ref interface) => interface.fmt(f),
+        }
+    }
+}
+
+impl FromStr for IpInterface {
+    type Err = IpNetworkParseError;
+
+    /// Converts string in format X.X.X.X/Y or X:X::X/Y to `IpInterface`.
+    fn from_str(s: &str) -> Result<Self, IpNetworkParseError> {
+        helpers::parse_network(s, None, Self::new::<IpAddr>)
+    }
+}
+
+impl From<IpAddr> for IpInterface {
+    /// Converts `IpAddr` to `IpInterface` with netmask 32 for IPv4 address and 128 for IPv6 address.
+    fn from(address: IpAddr) -> Self {
+        match address {
+            IpAddr::V4(ip) => IpInterface::V4(Ipv4Interface::from(ip)),
+            IpAddr::V6(ip) => IpInterface::V6(Ipv6Interface::from(ip)),
+        }
+    }
+}
+
+impl From<Ipv4Interface> for IpInterface {
+    #[inline]
+    fn from(interface: Ipv4Interface) -> Self {
+        IpInterface::V4(interface)
+    }
+}
+
+impl From<Ipv6Interface> for IpInterface {
+    #[inline]
+    fn from(interface: Ipv6Interface) -> Self {
+        IpInterface::V6(interface)
+    }
+}
+
+impl From<IpNetwork> for IpInterface {
+    /// Converts `IpNetwork` to `IpInterface` with network address.
+    fn from(network: IpNetwork) -> Self {
+        match network {
+            IpNetwork::V4(network) => IpInterface::V4(Ipv4Interface::from(network)),
+            IpNetwork::V6(network) => IpInterface::V6(Ipv6Interface::from(network)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use crate::{IpInterface, IpNetworkError, IpNetworkParseError, Ipv4Interface, Ipv6Interface};
+    use crate::{Ipv4Network, Ipv6Network};
+
+    #[test]
+    fn ipv4_keeps_host_bits() {
+        let interface = Ipv4Interface::new(Ipv4Addr::new(10, 1, 2, 3), 8).unwrap();
+        assert_eq!(Ipv4Addr::new(10, 1, 2, 3), interface.address());
+        assert_eq!(
+            Ipv4Network::new(Ipv4Addr::new(10, 0, 0, 0), 8).unwrap(),
+            interface.network()
+        );
+        assert_eq!("10.1.2.3/8", interface.to_string());
+    }
+
+    #[test]
+    fn ipv4_invalid_netmask() {
+        assert_eq!(
+            IpNetworkError::NetmaskError {
+                netmask: 33,
+                max_netmask: 32
+            },
+            Ipv4Interface::new(Ipv4Addr::new(10, 1, 2, 3), 33).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn ipv6_parse() {
+        let interface: Ipv6Interface = "2001:db8::1/32".parse().unwrap();
+        assert_eq!(
+            Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1),
+            interface.address()
+        );
+        assert_eq!(
+            Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32).unwrap(),
+            interface.network()
+        );
+    }
+
+    #[test]
+    fn ip_parse() {
+        let interface: IpInterface = "192.168.1.10/24".parse().unwrap();
+        assert_eq!(
+            IpInterface::V4(Ipv4Interface::new(Ipv4Addr::new(192, 168, 1, 10), 24).unwrap()),
+            interface
+        );
+        assert_eq!("192.168.1.10/24", interface.to_string());
+    }
+
+    #[test]
+    fn ip_parse_invalid() {
+        assert_eq!(
+            IpNetworkParseError::InvalidNetmaskFormat { span: 13..14 },
+            "192.168.1.10/x".parse::<IpInterface>().unwrap_err()
+        );
+    }
+}
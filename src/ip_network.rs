@@ -1,9 +1,10 @@
 use std::cmp::Ordering;
 use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::ops::{Add, Sub};
 use std::str::FromStr;
-use crate::{IpNetworkError, IpNetworkParseError};
-use crate::helpers;
+use crate::{HostPolicy, IpNetworkError, IpNetworkParseError};
+use crate::{helpers, iterator};
 use crate::{Ipv4Network, Ipv6Network};
 
 /// Holds IPv4 or IPv6 network.
@@ -141,6 +142,164 @@ impl IpNetwork {
         }
     }
 
+    /// Returns address at position `n` in network, where network address has position zero. If `n`
+    /// is outside of network, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::IpNetwork;
+    ///
+    /// let ip_network = IpNetwork::new(Ipv4Addr::new(192, 168, 1, 0), 24)?;
+    /// assert_eq!(ip_network.nth_address(5), Some(Ipv4Addr::new(192, 168, 1, 5).into()));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn nth_address(&self, n: u128) -> Option<IpAddr> {
+        match self {
+            IpNetwork::V4(ip_network) => ip_network.nth_address(n).map(IpAddr::V4),
+            IpNetwork::V6(ip_network) => ip_network.nth_address(n).map(IpAddr::V6),
+        }
+    }
+
+    /// Returns position of `ip` in network, where network address has position zero. If `ip` is not
+    /// inside this network, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::{Ipv4Addr, Ipv6Addr};
+    /// use ip_network::IpNetwork;
+    ///
+    /// let ip_network = IpNetwork::new(Ipv4Addr::new(192, 168, 1, 0), 24)?;
+    /// assert_eq!(ip_network.address_index(Ipv4Addr::new(192, 168, 1, 5)), Some(5));
+    /// assert_eq!(ip_network.address_index(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 5)), None);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn address_index<I: Into<IpAddr>>(&self, ip: I) -> Option<u128> {
+        match (self, ip.into()) {
+            (IpNetwork::V4(network), IpAddr::V4(ip)) => network.address_index(ip),
+            (IpNetwork::V6(network), IpAddr::V6(ip)) => network.address_index(ip),
+            _ => None,
+        }
+    }
+
+    /// Returns iterator over host IP addresses in range selected by `policy`, see [`HostPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::{IpAddr, Ipv6Addr};
+    /// use ip_network::{HostPolicy, IpNetwork};
+    ///
+    /// let ip_network = IpNetwork::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 127)?;
+    /// let hosts: Vec<IpAddr> = ip_network.hosts_with(HostPolicy::PointToPoint).collect();
+    /// assert_eq!(2, hosts.len());
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn hosts_with(&self, policy: HostPolicy) -> iterator::IpRangeIterator {
+        match self {
+            IpNetwork::V4(ip_network) => iterator::IpRangeIterator::V4(
+                iterator::Ipv4RangeIterator::hosts_with(*ip_network, policy),
+            ),
+            IpNetwork::V6(ip_network) => {
+                iterator::IpRangeIterator::V6(ip_network.hosts_with(policy))
+            }
+        }
+    }
+
+    /// Returns number of host IP addresses in range selected by `policy`, see [`HostPolicy`]. When
+    /// IPv6 network contains all 2^128 addresses, `u128::MAX` is returned.
+    pub fn host_count(&self, policy: HostPolicy) -> u128 {
+        match self {
+            IpNetwork::V4(ip_network) => ip_network.host_count(policy),
+            IpNetwork::V6(ip_network) => ip_network.host_count(policy),
+        }
+    }
+
+    /// Returns number of subnets with netmask `prefix` in this network, see
+    /// [`Ipv4Network::subnet_count`] and [`Ipv6Network::subnet_count`].
+    pub fn subnet_count(&self, prefix: u8) -> u128 {
+        match self {
+            IpNetwork::V4(ip_network) => ip_network.subnet_count(prefix),
+            IpNetwork::V6(ip_network) => ip_network.subnet_count(prefix),
+        }
+    }
+
+    /// Returns subnet with netmask `prefix` at position `index` in this network without iterating
+    /// over previous subnets. If there is no such subnet, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::IpNetwork;
+    ///
+    /// let ip_network = IpNetwork::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 48)?;
+    /// let subnet = ip_network.subnet_at(64, 42).unwrap();
+    /// assert_eq!(subnet, IpNetwork::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 42, 0, 0, 0, 0), 64)?);
+    /// assert_eq!(ip_network.subnet_index(&subnet), Some(42));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn subnet_at(&self, prefix: u8, index: u128) -> Option<Self> {
+        match self {
+            IpNetwork::V4(ip_network) => ip_network.subnet_at(prefix, index).map(IpNetwork::V4),
+            IpNetwork::V6(ip_network) => ip_network.subnet_at(prefix, index).map(IpNetwork::V6),
+        }
+    }
+
+    /// Returns position of `subnet` between all subnets with the same netmask in this network. If
+    /// `subnet` is not inside this network, `None` will be returned.
+    pub fn subnet_index(&self, subnet: &Self) -> Option<u128> {
+        match (self, subnet) {
+            (IpNetwork::V4(network), IpNetwork::V4(subnet)) => network.subnet_index(subnet),
+            (IpNetwork::V6(network), IpNetwork::V6(subnet)) => network.subnet_index(subnet),
+            _ => None,
+        }
+    }
+
+    /// Returns network with the same netmask, that follows directly after this network. If there is
+    /// no such network, `None` will be returned.
+    pub fn next(&self) -> Option<Self> {
+        self.checked_add(1)
+    }
+
+    /// Returns network with the same netmask, that directly precedes this network. If there is no
+    /// such network, `None` will be returned.
+    pub fn prev(&self) -> Option<Self> {
+        self.checked_sub(1)
+    }
+
+    /// Returns network with the same netmask moved `offset` networks up. If result would be
+    /// outside of address space, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::IpNetwork;
+    ///
+    /// let ip_network = IpNetwork::new(Ipv4Addr::new(192, 168, 1, 0), 24)?;
+    /// assert_eq!(ip_network.checked_add(4), Some(IpNetwork::new(Ipv4Addr::new(192, 168, 5, 0), 24)?));
+    /// assert_eq!(ip_network + 4, ip_network.checked_add(4));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn checked_add(&self, offset: u128) -> Option<Self> {
+        match self {
+            IpNetwork::V4(ip_network) => ip_network.checked_add(offset).map(IpNetwork::V4),
+            IpNetwork::V6(ip_network) => ip_network.checked_add(offset).map(IpNetwork::V6),
+        }
+    }
+
+    /// Returns network with the same netmask moved `offset` networks down. If result would be
+    /// outside of address space, `None` will be returned.
+    pub fn checked_sub(&self, offset: u128) -> Option<Self> {
+        match self {
+            IpNetwork::V4(ip_network) => ip_network.checked_sub(offset).map(IpNetwork::V4),
+            IpNetwork::V6(ip_network) => ip_network.checked_sub(offset).map(IpNetwork::V6),
+        }
+    }
+
     /// Returns `true` if the network is default route, that contains all IP addresses.
     pub fn is_default_route(&self) -> bool {
         match self {
@@ -193,16 +352,7 @@ impl IpNetwork {
     /// assert_eq!(ip_network, IpNetwork::V4(Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 24).unwrap()));
     /// ```
     pub fn from_str_truncate(s: &str) -> Result<Self, IpNetworkParseError> {
-        let (ip, netmask) =
-            helpers::split_ip_netmask(s).ok_or(IpNetworkParseError::InvalidFormatError)?;
-
-        let network_address =
-            IpAddr::from_str(ip).map_err(|_| IpNetworkParseError::AddrParseError)?;
-        let netmask =
-            u8::from_str(netmask).map_err(|_| IpNetworkParseError::InvalidNetmaskFormat)?;
-
-        IpNetwork::new_truncate(network_address, netmask)
-            .map_err(IpNetworkParseError::IpNetworkError)
+        helpers::parse_network(s, None, IpNetwork::new_truncate::<IpAddr>)
     }
 
     /// Return an iterator of the collapsed IpNetworks.
@@ -266,15 +416,7 @@ impl FromStr for IpNetwork {
     /// assert_eq!(ip_network, IpNetwork::V4(Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 24).unwrap()));
     /// ```
     fn from_str(s: &str) -> Result<IpNetwork, IpNetworkParseError> {
-        let (ip, netmask) =
-            helpers::split_ip_netmask(s).ok_or(IpNetworkParseError::InvalidFormatError)?;
-
-        let network_address =
-            IpAddr::from_str(ip).map_err(|_| IpNetworkParseError::AddrParseError)?;
-        let netmask =
-            u8::from_str(netmask).map_err(|_| IpNetworkParseError::InvalidNetmaskFormat)?;
-
-        IpNetwork::new(network_address, netmask).map_err(IpNetworkParseError::IpNetworkError)
+        helpers::parse_network(s, None, IpNetwork::new::<IpAddr>)
     }
 }
 
@@ -390,6 +532,24 @@ impl PartialOrd<IpNetwork> for Ipv6Network {
     }
 }
 
+impl Add<u128> for IpNetwork {
+    type Output = Option<Self>;
+
+    /// Same as [`IpNetwork::checked_add`].
+    fn add(self, offset: u128) -> Self::Output {
+        self.checked_add(offset)
+    }
+}
+
+impl Sub<u128> for IpNetwork {
+    type Output = Option<Self>;
+
+    /// Same as [`IpNetwork::checked_sub`].
+    fn sub(self, offset: u128) -> Self::Output {
+        self.checked_sub(offset)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
@@ -453,7 +613,7 @@ mod tests {
         let ip_network = "".parse::<IpNetwork>();
         assert!(ip_network.is_err());
         assert_eq!(
-            IpNetworkParseError::InvalidFormatError,
+            IpNetworkParseError::InvalidFormatError { span: 0..0 },
             ip_network.unwrap_err()
         );
     }
@@ -463,7 +623,7 @@ mod tests {
         let ip_network = "192.168.0.0/a".parse::<IpNetwork>();
         assert!(ip_network.is_err());
         assert_eq!(
-            IpNetworkParseError::InvalidNetmaskFormat,
+            IpNetworkParseError::InvalidNetmaskFormat { span: 12..13 },
             ip_network.unwrap_err()
         );
     }
@@ -472,7 +632,13 @@ mod tests {
     fn parse_invalid_ip() {
         let ip_network = "192.168.0.0a/16".parse::<IpNetwork>();
         assert!(ip_network.is_err());
-        assert_eq!(IpNetworkParseError::AddrParseError, ip_network.unwrap_err());
+        assert_eq!(
+            IpNetworkParseError::AddrParseError {
+                span: 0..12,
+                family: None
+            },
+            ip_network.unwrap_err()
+        );
     }
 
     #[test]
@@ -480,7 +646,12 @@ mod tests {
         let ip_network = "192.168.0.1/16".parse::<IpNetwork>();
         assert!(ip_network.is_err());
         assert_eq!(
-            IpNetworkParseError::IpNetworkError(IpNetworkError::HostBitsSet),
+            IpNetworkParseError::IpNetworkError {
+                error: IpNetworkError::HostBitsSet {
+                    truncated: IpNetwork::V4(return_test_ipv4_network())
+                },
+                span: 0..11,
+            },
             ip_network.unwrap_err()
         );
     }
@@ -490,11 +661,35 @@ mod tests {
         let ip_network = "2001:db8::1/32".parse::<IpNetwork>();
         assert!(ip_network.is_err());
         assert_eq!(
-            IpNetworkParseError::IpNetworkError(IpNetworkError::HostBitsSet),
+            IpNetworkParseError::IpNetworkError {
+                error: IpNetworkError::HostBitsSet {
+                    truncated: IpNetwork::V6(return_test_ipv6_network())
+                },
+                span: 0..11,
+            },
             ip_network.unwrap_err()
         );
     }
 
+    #[test]
+    fn parse_invalid_netmask_span() {
+        let error = "10.0.0.0/33".parse::<IpNetwork>().unwrap_err();
+        assert_eq!(
+            IpNetworkParseError::IpNetworkError {
+                error: IpNetworkError::NetmaskError {
+                    netmask: 33,
+                    max_netmask: 32,
+                },
+                span: 9..11,
+            },
+            error
+        );
+        assert_eq!(Some(9..11), error.span());
+
+        let error = IpNetwork::from_str_truncate("2001:db8::/129").unwrap_err();
+        assert_eq!(Some(11..14), error.span());
+    }
+
     #[test]
     fn format_ipv4() {
         let ip_network = IpNetwork::V4(return_test_ipv4_network());
@@ -622,4 +817,53 @@ mod tests {
         let collapsed = IpNetwork::collapse_addresses(&addresses);
         assert_eq!(2, collapsed.len());
     }
+
+    #[test]
+    fn arithmetic() {
+        let ip_network = IpNetwork::from_str("192.0.2.0/24").unwrap();
+        assert_eq!(
+            Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 5))),
+            ip_network.nth_address(5)
+        );
+        assert_eq!(
+            Some(5),
+            ip_network.address_index(Ipv4Addr::new(192, 0, 2, 5))
+        );
+        assert_eq!(None, ip_network.address_index(Ipv6Addr::UNSPECIFIED));
+        assert_eq!(IpNetwork::from_str("192.0.3.0/24").ok(), ip_network.next());
+        assert_eq!(IpNetwork::from_str("192.0.1.0/24").ok(), ip_network.prev());
+        assert_eq!(None, ip_network + (1 << 24));
+
+        let ip_network = IpNetwork::from_str("2001:db8::/48").unwrap();
+        assert_eq!(IpNetwork::from_str("2001:db8:2::/48").ok(), ip_network + 2);
+        assert_eq!(
+            IpNetwork::from_str("2001:db7:ffff::/48").ok(),
+            ip_network - 1
+        );
+    }
+
+    #[test]
+    fn subnet_indexing() {
+        let ip_network = IpNetwork::from_str("192.0.2.0/24").unwrap();
+        assert_eq!(4, ip_network.subnet_count(26));
+        assert_eq!(
+            IpNetwork::from_str("192.0.2.192/26").ok(),
+            ip_network.subnet_at(26, 3)
+        );
+        assert_eq!(None, ip_network.subnet_at(26, 4));
+        assert_eq!(
+            Some(3),
+            ip_network.subnet_index(&IpNetwork::from_str("192.0.2.192/26").unwrap())
+        );
+        assert_eq!(
+            None,
+            ip_network.subnet_index(&IpNetwork::from_str("2001:db8::/48").unwrap())
+        );
+
+        let ip_network = IpNetwork::from_str("2001:db8::/48").unwrap();
+        assert_eq!(
+            IpNetwork::from_str("2001:db8:0:2a::/64").ok(),
+            ip_network.subnet_at(64, 42)
+        );
+    }
 }
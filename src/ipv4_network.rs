@@ -3,7 +3,9 @@ use std::fmt;
 use std::net::Ipv4Addr;
 use std::str::FromStr;
 use std::hash::{Hash, Hasher};
-use crate::{IpNetworkError, IpNetworkParseError};
+use std::convert::TryFrom;
+use std::ops::{Add, Sub};
+use crate::{HostPolicy, IpFamily, IpNetwork, IpNetworkError, IpNetworkParseError};
 use crate::helpers;
 use crate::iterator;
 use std::collections::BTreeMap;
@@ -45,18 +47,14 @@ impl Ipv4Network {
     /// ```
     #[allow(clippy::new_ret_no_self)]
     pub fn new(network_address: Ipv4Addr, netmask: u8) -> Result<Self, IpNetworkError> {
-        if netmask > Self::LENGTH {
-            return Err(IpNetworkError::NetmaskError(netmask));
-        }
-
-        if u32::from(network_address).trailing_zeros() < u32::from(Self::LENGTH - netmask) {
-            return Err(IpNetworkError::HostBitsSet);
+        let network = Self::new_truncate(network_address, netmask)?;
+        if network.network_address != network_address {
+            return Err(IpNetworkError::HostBitsSet {
+                truncated: IpNetwork::V4(network),
+            });
         }
 
-        Ok(Self {
-            network_address,
-            netmask,
-        })
+        Ok(network)
     }
 
     /// Constructs new `Ipv4Network` based on [`Ipv4Addr`] and `netmask` with truncating host bits
@@ -79,7 +77,10 @@ impl Ipv4Network {
     /// ```
     pub fn new_truncate(network_address: Ipv4Addr, netmask: u8) -> Result<Self, IpNetworkError> {
         if netmask > Self::LENGTH {
-            return Err(IpNetworkError::NetmaskError(netmask));
+            return Err(IpNetworkError::NetmaskError {
+                netmask,
+                max_netmask: Self::LENGTH,
+            });
         }
 
         let network_address =
@@ -177,6 +178,49 @@ impl Ipv4Network {
         u32::from(ip) & helpers::bite_mask(self.netmask) == u32::from(self.network_address)
     }
 
+    /// Returns address at position `n` in network, where network address has position zero. If `n`
+    /// is outside of network, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::Ipv4Network;
+    ///
+    /// let ip_network = Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)?;
+    /// assert_eq!(ip_network.nth_address(5), Some(Ipv4Addr::new(192, 168, 1, 5)));
+    /// assert_eq!(ip_network.nth_address(256), None);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn nth_address(&self, n: u128) -> Option<Ipv4Addr> {
+        if n > u128::from(!helpers::bite_mask(self.netmask)) {
+            return None;
+        }
+        Some(Ipv4Addr::from(u32::from(self.network_address) + n as u32))
+    }
+
+    /// Returns position of `ip` in network, where network address has position zero. If `ip` is not
+    /// inside this network, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::Ipv4Network;
+    ///
+    /// let ip_network = Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)?;
+    /// assert_eq!(ip_network.address_index(Ipv4Addr::new(192, 168, 1, 5)), Some(5));
+    /// assert_eq!(ip_network.address_index(Ipv4Addr::new(192, 168, 2, 5)), None);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn address_index(&self, ip: Ipv4Addr) -> Option<u128> {
+        if self.contains(ip) {
+            Some(u128::from(u32::from(ip) - u32::from(self.network_address)))
+        } else {
+            None
+        }
+    }
+
     /// Returns iterator over host IP addresses in range (without network and broadcast address). You
     /// can also use this method to check how much hosts address are in range by calling [`len()`] method
     /// on iterator (see Examples).
@@ -200,6 +244,42 @@ impl Ipv4Network {
         iterator::Ipv4RangeIterator::hosts(*self)
     }
 
+    /// Returns iterator over host IP addresses in range selected by `policy`, see [`HostPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::{HostPolicy, Ipv4Network};
+    ///
+    /// let ip_network = Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 31)?;
+    /// let mut hosts = ip_network.hosts_with(HostPolicy::PointToPoint);
+    /// assert_eq!(hosts.next().unwrap(), Ipv4Addr::new(192, 168, 1, 0));
+    /// assert_eq!(hosts.next().unwrap(), Ipv4Addr::new(192, 168, 1, 1));
+    /// assert!(hosts.next().is_none());
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn hosts_with(&self, policy: HostPolicy) -> impl ExactSizeIterator<Item = Ipv4Addr> {
+        iterator::Ipv4RangeIterator::hosts_with(*self, policy)
+    }
+
+    /// Returns number of host IP addresses in range selected by `policy`, see [`HostPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::{HostPolicy, Ipv4Network};
+    ///
+    /// let ip_network = Ipv4Network::new(Ipv4Addr::new(0, 0, 0, 0), 0)?;
+    /// assert_eq!(1 << 32, ip_network.host_count(HostPolicy::All));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn host_count(&self, policy: HostPolicy) -> u128 {
+        iterator::IpRangeIterator::V4(iterator::Ipv4RangeIterator::hosts_with(*self, policy))
+            .real_len()
+    }
+
     /// Returns network with smaller netmask by one. If netmask is already zero, `None` will be returned.
     ///
     /// # Examples
@@ -263,6 +343,177 @@ impl Ipv4Network {
         iterator::Ipv4NetworkIterator::new(*self, prefix)
     }
 
+    /// Returns number of subnets with netmask `prefix` in this network. When `prefix` is smaller than
+    /// netmask or bigger than 32, zero is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::Ipv4Network;
+    ///
+    /// let ip_network = Ipv4Network::new(Ipv4Addr::new(10, 0, 0, 0), 8)?;
+    /// assert_eq!(ip_network.subnet_count(24), 65536);
+    /// assert_eq!(ip_network.subnet_count(7), 0);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn subnet_count(&self, prefix: u8) -> u128 {
+        if prefix < self.netmask || prefix > Self::LENGTH {
+            0
+        } else {
+            1 << (prefix - self.netmask)
+        }
+    }
+
+    /// Returns subnet with netmask `prefix` at position `index` in this network without iterating
+    /// over previous subnets. When `prefix` is smaller than netmask, bigger than 32 or when `index`
+    /// is not smaller than [`subnet_count`], `None` will be returned.
+    ///
+    /// [`subnet_count`]: #method.subnet_count
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::Ipv4Network;
+    ///
+    /// let ip_network = Ipv4Network::new(Ipv4Addr::new(10, 0, 0, 0), 8)?;
+    /// assert_eq!(ip_network.subnet_at(24, 258), Some(Ipv4Network::new(Ipv4Addr::new(10, 1, 2, 0), 24)?));
+    /// assert_eq!(ip_network.subnet_at(24, 65536), None);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn subnet_at(&self, prefix: u8, index: u128) -> Option<Self> {
+        if index >= self.subnet_count(prefix) {
+            return None;
+        }
+        let offset = (index as u32)
+            .checked_shl(u32::from(Self::LENGTH - prefix))
+            .unwrap_or(0);
+        Some(Self {
+            network_address: Ipv4Addr::from(u32::from(self.network_address) | offset),
+            netmask: prefix,
+        })
+    }
+
+    /// Returns position of `subnet` between all subnets with the same netmask in this network, this
+    /// is inverse of [`subnet_at`]. If `subnet` is not inside this network, `None` will be returned.
+    ///
+    /// [`subnet_at`]: #method.subnet_at
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::Ipv4Network;
+    ///
+    /// let ip_network = Ipv4Network::new(Ipv4Addr::new(10, 0, 0, 0), 8)?;
+    /// let subnet = Ipv4Network::new(Ipv4Addr::new(10, 1, 2, 0), 24)?;
+    /// assert_eq!(ip_network.subnet_index(&subnet), Some(258));
+    /// assert_eq!(subnet.subnet_index(&ip_network), None);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn subnet_index(&self, subnet: &Self) -> Option<u128> {
+        if subnet.netmask < self.netmask || !self.contains(subnet.network_address) {
+            return None;
+        }
+        let offset = u32::from(subnet.network_address) - u32::from(self.network_address);
+        Some(
+            offset
+                .checked_shr(u32::from(Self::LENGTH - subnet.netmask))
+                .unwrap_or(0)
+                .into(),
+        )
+    }
+
+    /// Returns network with the same netmask, that follows directly after this network. If there is
+    /// no such network, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::Ipv4Network;
+    ///
+    /// let ip_network = Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)?;
+    /// assert_eq!(ip_network.next(), Some(Ipv4Network::new(Ipv4Addr::new(192, 168, 2, 0), 24)?));
+    /// assert_eq!(Ipv4Network::new(Ipv4Addr::new(255, 255, 255, 0), 24)?.next(), None);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn next(&self) -> Option<Self> {
+        self.checked_add(1)
+    }
+
+    /// Returns network with the same netmask, that directly precedes this network. If there is no
+    /// such network, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::Ipv4Network;
+    ///
+    /// let ip_network = Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)?;
+    /// assert_eq!(ip_network.prev(), Some(Ipv4Network::new(Ipv4Addr::new(192, 168, 0, 0), 24)?));
+    /// assert_eq!(Ipv4Network::new(Ipv4Addr::new(0, 0, 0, 0), 24)?.prev(), None);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn prev(&self) -> Option<Self> {
+        self.checked_sub(1)
+    }
+
+    /// Returns network with the same netmask moved `offset` networks up. If result would be
+    /// outside of IPv4 address space, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::Ipv4Network;
+    ///
+    /// let ip_network = Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)?;
+    /// assert_eq!(ip_network.checked_add(4), Some(Ipv4Network::new(Ipv4Addr::new(192, 168, 5, 0), 24)?));
+    /// assert_eq!(ip_network + 4, ip_network.checked_add(4));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn checked_add(&self, offset: u128) -> Option<Self> {
+        let address = self
+            .size()
+            .checked_mul(offset)?
+            .checked_add(u32::from(self.network_address).into())?;
+        Some(Self {
+            network_address: Ipv4Addr::from(u32::try_from(address).ok()?),
+            netmask: self.netmask,
+        })
+    }
+
+    /// Returns network with the same netmask moved `offset` networks down. If result would be
+    /// outside of IPv4 address space, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv4Addr;
+    /// use ip_network::Ipv4Network;
+    ///
+    /// let ip_network = Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 24)?;
+    /// assert_eq!(ip_network.checked_sub(1), Some(Ipv4Network::new(Ipv4Addr::new(192, 168, 0, 0), 24)?));
+    /// assert_eq!(ip_network - 1, ip_network.checked_sub(1));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn checked_sub(&self, offset: u128) -> Option<Self> {
+        let address = u128::from(u32::from(self.network_address))
+            .checked_sub(self.size().checked_mul(offset)?)?;
+        Some(Self {
+            network_address: Ipv4Addr::from(address as u32),
+            netmask: self.netmask,
+        })
+    }
+
+    /// Returns number of addresses in network.
+    fn size(&self) -> u128 {
+        1 << (Self::LENGTH - self.netmask)
+    }
+
     /// Returns [`true`] for the default route network (0.0.0.0/0), that contains all IPv4 addresses.
     ///
     /// [`true`]: https://doc.rust-lang.org/std/primitive.bool.html
@@ -554,7 +805,7 @@ impl Ipv4Network {
     /// - Shared Address Space (100.64.0.0/10)
     /// - the loopback address (127.0.0.0/8)
     /// - the link-local address (169.254.0.0/16)
-    /// - IETF Protocol Assignments	(192.0.0.0/24, except 192.0.0.9/32 and 192.0.0.10/32)
+    /// - IETF Protocol Assignments (192.0.0.0/24, except 192.0.0.9/32 and 192.0.0.10/32)
     /// - the broadcast address (255.255.255.255/32)
     /// - test addresses used for documentation (192.0.2.0/24, 198.51.100.0/24 and 203.0.113.0/24)
     /// - benchmarking (198.18.0.0/15)
@@ -688,9 +939,12 @@ impl Ipv4Network {
             }
         }
 
+        // Map is sorted by supernets, but networks must be sorted by itself to remove contained ones.
+        let mut networks: Vec<Ipv4Network> = subnets.into_values().collect();
+        networks.sort_unstable();
+
         let mut output: Vec<Ipv4Network> = vec![];
-        // into_values() is unstable
-        for (_, net) in subnets.into_iter() {
+        for net in networks {
             if let Some(last) = output.last() {
                 // Since they are sorted, last.network_address <= net.network_address is a given.
                 if last.broadcast_address() >= net.broadcast_address() {
@@ -716,15 +970,7 @@ impl Ipv4Network {
     /// # Ok::<(), ip_network::IpNetworkParseError>(())
     /// ```
     pub fn from_str_truncate(s: &str) -> Result<Self, IpNetworkParseError> {
-        let (ip, netmask) =
-            helpers::split_ip_netmask(s).ok_or(IpNetworkParseError::InvalidFormatError)?;
-
-        let network_address =
-            Ipv4Addr::from_str(ip).map_err(|_| IpNetworkParseError::AddrParseError)?;
-        let netmask =
-            u8::from_str(netmask).map_err(|_| IpNetworkParseError::InvalidNetmaskFormat)?;
-
-        Self::new_truncate(network_address, netmask).map_err(IpNetworkParseError::IpNetworkError)
+        helpers::parse_network(s, Some(IpFamily::V4), Self::new_truncate)
     }
 }
 
@@ -763,15 +1009,7 @@ impl FromStr for Ipv4Network {
     /// # Ok::<(), ip_network::IpNetworkParseError>(())
     /// ```
     fn from_str(s: &str) -> Result<Ipv4Network, IpNetworkParseError> {
-        let (ip, netmask) =
-            helpers::split_ip_netmask(s).ok_or(IpNetworkParseError::InvalidFormatError)?;
-
-        let network_address =
-            Ipv4Addr::from_str(ip).map_err(|_| IpNetworkParseError::AddrParseError)?;
-        let netmask =
-            u8::from_str(netmask).map_err(|_| IpNetworkParseError::InvalidNetmaskFormat)?;
-
-        Self::new(network_address, netmask).map_err(IpNetworkParseError::IpNetworkError)
+        helpers::parse_network(s, Some(IpFamily::V4), Self::new)
     }
 }
 
@@ -800,6 +1038,24 @@ impl Hash for Ipv4Network {
     }
 }
 
+impl Add<u128> for Ipv4Network {
+    type Output = Option<Self>;
+
+    /// Same as [`Ipv4Network::checked_add`].
+    fn add(self, offset: u128) -> Self::Output {
+        self.checked_add(offset)
+    }
+}
+
+impl Sub<u128> for Ipv4Network {
+    type Output = Option<Self>;
+
+    /// Same as [`Ipv4Network::checked_sub`].
+    fn sub(self, offset: u128) -> Self::Output {
+        self.checked_sub(offset)
+    }
+}
+
 impl IntoIterator for Ipv4Network {
     type Item = Ipv4Addr;
     type IntoIter = iterator::Ipv4RangeIterator;
@@ -827,7 +1083,7 @@ impl IntoIterator for Ipv4Network {
 #[cfg(test)]
 mod tests {
     use std::net::Ipv4Addr;
-    use crate::{IpNetworkError, Ipv4Network};
+    use crate::{HostPolicy, IpFamily, IpNetwork, IpNetworkError, IpNetworkParseError, Ipv4Network};
     use std::str::FromStr;
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
@@ -847,7 +1103,12 @@ mod tests {
         let ip = Ipv4Addr::new(127, 0, 0, 1);
         let ip_network = Ipv4Network::new(ip, 8);
         assert!(ip_network.is_err());
-        assert_eq!(IpNetworkError::HostBitsSet, ip_network.unwrap_err());
+        assert_eq!(
+            IpNetworkError::HostBitsSet {
+                truncated: IpNetwork::V4(Ipv4Network::new(Ipv4Addr::new(127, 0, 0, 0), 8).unwrap())
+            },
+            ip_network.unwrap_err()
+        );
     }
 
     #[test]
@@ -876,7 +1137,13 @@ mod tests {
         let ip = Ipv4Addr::new(127, 0, 0, 1);
         let ip_network = Ipv4Network::new(ip, 33);
         assert!(ip_network.is_err());
-        assert_eq!(IpNetworkError::NetmaskError(33), ip_network.unwrap_err());
+        assert_eq!(
+            IpNetworkError::NetmaskError {
+                netmask: 33,
+                max_netmask: 32
+            },
+            ip_network.unwrap_err()
+        );
     }
 
     #[test]
@@ -891,7 +1158,13 @@ mod tests {
         let ip = Ipv4Addr::new(127, 0, 0, 1);
         let ip_network = Ipv4Network::new_truncate(ip, 33);
         assert!(ip_network.is_err());
-        assert_eq!(IpNetworkError::NetmaskError(33), ip_network.unwrap_err());
+        assert_eq!(
+            IpNetworkError::NetmaskError {
+                netmask: 33,
+                max_netmask: 32
+            },
+            ip_network.unwrap_err()
+        );
     }
 
     #[test]
@@ -918,6 +1191,42 @@ mod tests {
         assert_eq!(0, ip_network.hosts().len());
     }
 
+    #[test]
+    fn hosts_with_policy() {
+        let cases = [
+            (24, HostPolicy::Classic, 254, 1, 254),
+            (24, HostPolicy::PointToPoint, 254, 1, 254),
+            (24, HostPolicy::Usable, 254, 1, 254),
+            (24, HostPolicy::All, 256, 0, 255),
+            (31, HostPolicy::PointToPoint, 2, 0, 1),
+            (31, HostPolicy::Usable, 2, 0, 1),
+            (31, HostPolicy::All, 2, 0, 1),
+            (32, HostPolicy::PointToPoint, 1, 0, 0),
+        ];
+        for &(netmask, policy, count, first, last) in &cases {
+            let ip_network = Ipv4Network::new(Ipv4Addr::new(192, 0, 2, 0), netmask).unwrap();
+            let hosts = ip_network.hosts_with(policy);
+            assert_eq!(count, hosts.len(), "{:?} {}", policy, netmask);
+            assert_eq!(count as u128, ip_network.host_count(policy));
+            let hosts: Vec<_> = hosts.collect();
+            assert_eq!(count, hosts.len());
+            assert_eq!(Ipv4Addr::new(192, 0, 2, first), hosts[0]);
+            assert_eq!(Ipv4Addr::new(192, 0, 2, last), hosts[count - 1]);
+        }
+
+        for &netmask in &[31, 32] {
+            let ip_network = Ipv4Network::new(Ipv4Addr::new(192, 0, 2, 0), netmask).unwrap();
+            assert_eq!(0, ip_network.hosts_with(HostPolicy::Classic).len());
+            assert_eq!(0, ip_network.host_count(HostPolicy::Classic));
+        }
+
+        let ip_network = Ipv4Network::new(Ipv4Addr::new(0, 0, 0, 0), 0).unwrap();
+        assert_eq!(1 << 32, ip_network.host_count(HostPolicy::All));
+        assert_eq!((1 << 32) - 2, ip_network.host_count(HostPolicy::Classic));
+        let mut hosts = ip_network.hosts_with(HostPolicy::All);
+        assert_eq!(Some(Ipv4Addr::new(0, 0, 0, 0)), hosts.next());
+    }
+
     #[test]
     fn supernet_none() {
         let ipv4_network = Ipv4Network::new(Ipv4Addr::new(0, 0, 0, 0), 0).unwrap();
@@ -1000,6 +1309,37 @@ mod tests {
         assert_eq!(ip_network, return_test_ipv4_network());
     }
 
+    #[test]
+    fn parse_invalid_ip() {
+        let error = "192.168.0.256/16".parse::<Ipv4Network>().unwrap_err();
+        assert_eq!(
+            IpNetworkParseError::AddrParseError {
+                span: 0..13,
+                family: Some(IpFamily::V4)
+            },
+            error
+        );
+        assert_eq!(
+            "invalid IPv4 address syntax at position 0",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn parse_host_bits_set() {
+        let error = "10.1.2.3/8".parse::<Ipv4Network>().unwrap_err();
+        assert_eq!(
+            "IP network address has host bits set, expected 10.0.0.0/8",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_netmask() {
+        let error = "10.0.0.0/33".parse::<Ipv4Network>().unwrap_err();
+        assert_eq!("invalid netmask 33, maximum is 32", error.to_string());
+    }
+
     #[test]
     fn format() {
         let ip_network = return_test_ipv4_network();
@@ -1264,6 +1604,19 @@ mod tests {
         assert_eq!(Ipv4Network::from_str("0.0.0.0/0").unwrap(), collapsed[0]);
     }
 
+    #[test]
+    fn collapse_addresses_9() {
+        // bigger network with lower supernet must not hide unrelated network
+        let addresses = [
+            Ipv4Network::from_str("96.0.0.0/7").unwrap(),
+            Ipv4Network::from_str("100.0.0.0/6").unwrap(),
+        ];
+        let collapsed = Ipv4Network::collapse_addresses(&addresses);
+        assert_eq!(2, collapsed.len());
+        assert_eq!(Ipv4Network::from_str("96.0.0.0/7").unwrap(), collapsed[0]);
+        assert_eq!(Ipv4Network::from_str("100.0.0.0/6").unwrap(), collapsed[1]);
+    }
+
     #[test]
     fn from_ipv4addr() {
         let ip = Ipv4Addr::new(127, 0, 0, 1);
@@ -1285,4 +1638,105 @@ mod tests {
 
         assert_ne!(hasher1.finish(), hasher2.finish());
     }
+
+    #[test]
+    fn address_arithmetic() {
+        let ip_network = Ipv4Network::new(Ipv4Addr::new(192, 0, 2, 0), 24).unwrap();
+        assert_eq!(Some(Ipv4Addr::new(192, 0, 2, 0)), ip_network.nth_address(0));
+        assert_eq!(
+            Some(Ipv4Addr::new(192, 0, 2, 255)),
+            ip_network.nth_address(255)
+        );
+        assert_eq!(None, ip_network.nth_address(256));
+        assert_eq!(None, ip_network.nth_address(u128::MAX));
+        assert_eq!(
+            Some(255),
+            ip_network.address_index(Ipv4Addr::new(192, 0, 2, 255))
+        );
+        assert_eq!(None, ip_network.address_index(Ipv4Addr::new(192, 0, 3, 0)));
+
+        let ip_network = Ipv4Network::DEFAULT_ROUTE;
+        assert_eq!(
+            Some(Ipv4Addr::BROADCAST),
+            ip_network.nth_address(u32::MAX.into())
+        );
+        assert_eq!(None, ip_network.nth_address(1 << 32));
+        assert_eq!(
+            Some(u32::MAX.into()),
+            ip_network.address_index(Ipv4Addr::BROADCAST)
+        );
+    }
+
+    #[test]
+    fn network_arithmetic() {
+        let ip_network = Ipv4Network::new(Ipv4Addr::new(192, 0, 2, 0), 24).unwrap();
+        let next = Ipv4Network::new(Ipv4Addr::new(192, 0, 3, 0), 24).unwrap();
+        assert_eq!(Some(next), ip_network.next());
+        assert_eq!(Some(ip_network), next.prev());
+        assert_eq!(Some(ip_network), ip_network + 0);
+        assert_eq!(ip_network.checked_add(3), next + 2);
+        assert_eq!(ip_network.checked_sub(1), next - 2);
+
+        let last = Ipv4Network::new(Ipv4Addr::new(255, 255, 255, 0), 24).unwrap();
+        assert_eq!(None, last.next());
+        assert_eq!(
+            Some(last),
+            Ipv4Network::new(Ipv4Addr::new(0, 0, 0, 0), 24).unwrap() + 0xffffff
+        );
+        assert_eq!(
+            None,
+            Ipv4Network::new(Ipv4Addr::new(0, 0, 0, 0), 24)
+                .unwrap()
+                .prev()
+        );
+        assert_eq!(None, ip_network + u128::MAX);
+        assert_eq!(None, ip_network - u128::MAX);
+
+        let ip_network = Ipv4Network::DEFAULT_ROUTE;
+        assert_eq!(Some(ip_network), ip_network + 0);
+        assert_eq!(None, ip_network.next());
+        assert_eq!(None, ip_network.prev());
+    }
+
+    #[test]
+    fn subnet_indexing() {
+        let ip_network = Ipv4Network::new(Ipv4Addr::new(10, 0, 0, 0), 8).unwrap();
+        assert_eq!(1, ip_network.subnet_count(8));
+        assert_eq!(1 << 24, ip_network.subnet_count(32));
+        assert_eq!(0, ip_network.subnet_count(33));
+        assert_eq!(1 << 32, Ipv4Network::DEFAULT_ROUTE.subnet_count(32));
+
+        assert_eq!(Some(ip_network), ip_network.subnet_at(8, 0));
+        assert_eq!(None, ip_network.subnet_at(8, 1));
+        assert_eq!(None, ip_network.subnet_at(7, 0));
+        assert_eq!(None, ip_network.subnet_at(33, 0));
+        assert_eq!(
+            Some(Ipv4Network::new(Ipv4Addr::new(10, 255, 255, 255), 32).unwrap()),
+            ip_network.subnet_at(32, (1 << 24) - 1)
+        );
+        assert_eq!(None, ip_network.subnet_at(32, 1 << 24));
+        assert_eq!(
+            Some(Ipv4Network::from(Ipv4Addr::BROADCAST)),
+            Ipv4Network::DEFAULT_ROUTE.subnet_at(32, u32::MAX.into())
+        );
+        assert_eq!(
+            Some(Ipv4Network::DEFAULT_ROUTE),
+            Ipv4Network::DEFAULT_ROUTE.subnet_at(0, 0)
+        );
+
+        for (index, subnet) in ip_network.subnets_with_prefix(12).enumerate() {
+            assert_eq!(Some(subnet), ip_network.subnet_at(12, index as u128));
+            assert_eq!(Some(index as u128), ip_network.subnet_index(&subnet));
+        }
+        assert_eq!(Some(0), ip_network.subnet_index(&ip_network));
+        assert_eq!(
+            Some(0),
+            Ipv4Network::DEFAULT_ROUTE.subnet_index(&Ipv4Network::DEFAULT_ROUTE)
+        );
+        assert_eq!(None, ip_network.subnet_index(&Ipv4Network::DEFAULT_ROUTE));
+        assert_eq!(
+            None,
+            ip_network.subnet_index(&Ipv4Network::new(Ipv4Addr::new(11, 0, 0, 0), 24).unwrap())
+        );
+    }
 }
@@ -1,8 +1,10 @@
+use std::cmp;
 use std::fmt;
 use std::net::Ipv6Addr;
 use std::str::FromStr;
 use std::hash::{Hash, Hasher};
-use crate::{IpNetworkError, IpNetworkParseError};
+use std::ops::{Add, Sub};
+use crate::{HostPolicy, IpFamily, IpNetwork, IpNetworkError, IpNetworkParseError};
 use crate::helpers;
 use crate::iterator;
 use std::collections::BTreeMap;
@@ -57,18 +59,14 @@ impl Ipv6Network {
     /// ```
     #[allow(clippy::new_ret_no_self)]
     pub fn new(network_address: Ipv6Addr, netmask: u8) -> Result<Self, IpNetworkError> {
-        if netmask > Self::LENGTH {
-            return Err(IpNetworkError::NetmaskError(netmask));
-        }
-
-        if u128::from(network_address).trailing_zeros() < u32::from(Self::LENGTH - netmask) {
-            return Err(IpNetworkError::HostBitsSet);
+        let network = Self::new_truncate(network_address, netmask)?;
+        if network.network_address != network_address {
+            return Err(IpNetworkError::HostBitsSet {
+                truncated: IpNetwork::V6(network),
+            });
         }
 
-        Ok(Self {
-            network_address,
-            netmask,
-        })
+        Ok(network)
     }
 
     /// Constructs new `Ipv6Network` based on [`Ipv6Addr`] and `netmask` with truncating host bits
@@ -92,7 +90,10 @@ impl Ipv6Network {
     /// ```
     pub fn new_truncate(network_address: Ipv6Addr, netmask: u8) -> Result<Self, IpNetworkError> {
         if netmask > Self::LENGTH {
-            return Err(IpNetworkError::NetmaskError(netmask));
+            return Err(IpNetworkError::NetmaskError {
+                netmask,
+                max_netmask: Self::LENGTH,
+            });
         }
 
         let network_address_u128 = u128::from(network_address) & helpers::bite_mask_u128(netmask);
@@ -178,6 +179,91 @@ impl Ipv6Network {
         truncated_ip == u128::from(self.network_address)
     }
 
+    /// Returns address at position `n` in network, where network address has position zero. If `n`
+    /// is outside of network, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::Ipv6Network;
+    ///
+    /// let ip_network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 120)?;
+    /// assert_eq!(ip_network.nth_address(5), Some(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 5)));
+    /// assert_eq!(ip_network.nth_address(256), None);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn nth_address(&self, n: u128) -> Option<Ipv6Addr> {
+        if n > !helpers::bite_mask_u128(self.netmask) {
+            return None;
+        }
+        Some(Ipv6Addr::from(u128::from(self.network_address) + n))
+    }
+
+    /// Returns position of `ip` in network, where network address has position zero. If `ip` is not
+    /// inside this network, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::Ipv6Network;
+    ///
+    /// let ip_network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 64)?;
+    /// assert_eq!(ip_network.address_index(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 1, 0)), Some(0x10000));
+    /// assert_eq!(ip_network.address_index(Ipv6Addr::new(0x2001, 0xdb9, 0, 0, 0, 0, 0, 0)), None);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn address_index(&self, ip: Ipv6Addr) -> Option<u128> {
+        if self.contains(ip) {
+            Some(u128::from(ip) - u128::from(self.network_address))
+        } else {
+            None
+        }
+    }
+
+    /// Returns iterator over host IP addresses in range selected by `policy`, see [`HostPolicy`].
+    /// Number of addresses can be bigger than `usize`, so iterator doesn't implement
+    /// `ExactSizeIterator`. Use `real_len()` method of iterator or [`host_count()`] to get exact
+    /// number of addresses.
+    ///
+    /// [`host_count()`]: Self::host_count
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::{HostPolicy, Ipv6Network};
+    ///
+    /// let ip_network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 120)?;
+    /// let mut hosts = ip_network.hosts_with(HostPolicy::Usable);
+    /// assert_eq!(127, hosts.real_len());
+    /// assert_eq!(hosts.next().unwrap(), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+    /// assert_eq!(hosts.last().unwrap(), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0x7f));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn hosts_with(&self, policy: HostPolicy) -> iterator::Ipv6RangeIterator {
+        iterator::Ipv6RangeIterator::hosts_with(*self, policy)
+    }
+
+    /// Returns number of host IP addresses in range selected by `policy`, see [`HostPolicy`]. When
+    /// network contains all 2^128 addresses, `u128::MAX` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::{HostPolicy, Ipv6Network};
+    ///
+    /// let ip_network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 64)?;
+    /// assert_eq!((1 << 64) - 1, ip_network.host_count(HostPolicy::Classic));
+    /// assert_eq!((1 << 64) - 129, ip_network.host_count(HostPolicy::Usable));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn host_count(&self, policy: HostPolicy) -> u128 {
+        self.hosts_with(policy).real_len()
+    }
+
     /// Returns network with smaller netmask by one. If netmask is already zero, `None` will be returned.
     ///
     /// # Examples
@@ -245,6 +331,181 @@ impl Ipv6Network {
         iterator::Ipv6NetworkIterator::new(*self, prefix)
     }
 
+    /// Returns number of subnets with netmask `prefix` in this network. When `prefix` is smaller than
+    /// netmask or bigger than 128, zero is returned. When there are 2^128 subnets, `u128::MAX` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::Ipv6Network;
+    ///
+    /// let ip_network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 48)?;
+    /// assert_eq!(ip_network.subnet_count(64), 65536);
+    /// assert_eq!(ip_network.subnet_count(128), 1 << 80);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn subnet_count(&self, prefix: u8) -> u128 {
+        if prefix < self.netmask || prefix > Self::LENGTH {
+            0
+        } else {
+            1u128
+                .checked_shl(u32::from(prefix - self.netmask))
+                .unwrap_or(u128::MAX)
+        }
+    }
+
+    /// Returns subnet with netmask `prefix` at position `index` in this network without iterating
+    /// over previous subnets. When `prefix` is smaller than netmask, bigger than 128 or when `index`
+    /// is outside of this network, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::Ipv6Network;
+    ///
+    /// let ip_network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 48)?;
+    /// let subnet = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 42, 0, 0, 0, 0), 64)?;
+    /// assert_eq!(ip_network.subnet_at(64, 42), Some(subnet));
+    /// assert_eq!(ip_network.subnet_at(64, 65536), None);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn subnet_at(&self, prefix: u8, index: u128) -> Option<Self> {
+        if prefix < self.netmask || prefix > Self::LENGTH {
+            return None;
+        }
+        let bits = u32::from(prefix - self.netmask);
+        if bits < 128 && index >> bits != 0 {
+            return None;
+        }
+        let offset = index
+            .checked_shl(u32::from(Self::LENGTH - prefix))
+            .unwrap_or(0);
+        Some(Self {
+            network_address: Ipv6Addr::from(u128::from(self.network_address) | offset),
+            netmask: prefix,
+        })
+    }
+
+    /// Returns position of `subnet` between all subnets with the same netmask in this network, this
+    /// is inverse of [`subnet_at`]. If `subnet` is not inside this network, `None` will be returned.
+    ///
+    /// [`subnet_at`]: #method.subnet_at
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::Ipv6Network;
+    ///
+    /// let ip_network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 48)?;
+    /// let subnet = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 42, 0, 0, 0, 0), 64)?;
+    /// assert_eq!(ip_network.subnet_index(&subnet), Some(42));
+    /// assert_eq!(subnet.subnet_index(&ip_network), None);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn subnet_index(&self, subnet: &Self) -> Option<u128> {
+        if subnet.netmask < self.netmask || !self.contains(subnet.network_address) {
+            return None;
+        }
+        let offset = u128::from(subnet.network_address) - u128::from(self.network_address);
+        Some(
+            offset
+                .checked_shr(u32::from(Self::LENGTH - subnet.netmask))
+                .unwrap_or(0),
+        )
+    }
+
+    /// Returns network with the same netmask, that follows directly after this network. If there is
+    /// no such network, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::Ipv6Network;
+    ///
+    /// let ip_network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 48)?;
+    /// assert_eq!(ip_network.next(), Some(Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 1, 0, 0, 0, 0, 0), 48)?));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn next(&self) -> Option<Self> {
+        self.checked_add(1)
+    }
+
+    /// Returns network with the same netmask, that directly precedes this network. If there is no
+    /// such network, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::Ipv6Network;
+    ///
+    /// let ip_network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 48)?;
+    /// assert_eq!(ip_network.prev(), Some(Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb7, 0xffff, 0, 0, 0, 0, 0), 48)?));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn prev(&self) -> Option<Self> {
+        self.checked_sub(1)
+    }
+
+    /// Returns network with the same netmask moved `offset` networks up. If result would be
+    /// outside of IPv6 address space, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::Ipv6Network;
+    ///
+    /// let ip_network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 64)?;
+    /// assert_eq!(ip_network.checked_add(0x100), Some(Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0x100, 0, 0, 0, 0), 64)?));
+    /// assert_eq!(ip_network + 0x100, ip_network.checked_add(0x100));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn checked_add(&self, offset: u128) -> Option<Self> {
+        let address = u128::from(self.network_address).checked_add(self.shift(offset)?)?;
+        Some(Self {
+            network_address: Ipv6Addr::from(address),
+            netmask: self.netmask,
+        })
+    }
+
+    /// Returns network with the same netmask moved `offset` networks down. If result would be
+    /// outside of IPv6 address space, `None` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::Ipv6Network;
+    ///
+    /// let ip_network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0x100, 0, 0, 0, 0), 64)?;
+    /// assert_eq!(ip_network.checked_sub(0x100), Some(Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 64)?));
+    /// assert_eq!(ip_network - 0x100, ip_network.checked_sub(0x100));
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn checked_sub(&self, offset: u128) -> Option<Self> {
+        let address = u128::from(self.network_address).checked_sub(self.shift(offset)?)?;
+        Some(Self {
+            network_address: Ipv6Addr::from(address),
+            netmask: self.netmask,
+        })
+    }
+
+    /// Returns number of addresses in `offset` networks, `None` when it doesn't fit to `u128`.
+    fn shift(&self, offset: u128) -> Option<u128> {
+        if offset == 0 {
+            return Some(0);
+        }
+        1u128
+            .checked_shl(u32::from(Self::LENGTH - self.netmask))?
+            .checked_mul(offset)
+    }
+
     /// Returns [`true`] for the default route network (::/0), that contains all IPv6 addresses.
     ///
     /// [`true`]: https://doc.rust-lang.org/std/primitive.bool.html
@@ -519,15 +780,60 @@ impl Ipv6Network {
     /// # Ok::<(), ip_network::IpNetworkParseError>(())
     /// ```
     pub fn from_str_truncate(s: &str) -> Result<Self, IpNetworkParseError> {
-        let (ip, netmask) =
-            helpers::split_ip_netmask(s).ok_or(IpNetworkParseError::InvalidFormatError)?;
+        helpers::parse_network(s, Some(IpFamily::V6), Self::new_truncate)
+    }
 
-        let network_address =
-            Ipv6Addr::from_str(ip).map_err(|_| IpNetworkParseError::AddrParseError)?;
-        let netmask =
-            u8::from_str(netmask).map_err(|_| IpNetworkParseError::InvalidNetmaskFormat)?;
+    /// Return a vector of the summarized network range given the first and last IPv6 addresses.
+    /// Implementation of this method was inspired by Python [`ipaddress.summarize_address_range`]
+    /// method. If first IP address is bigger than last, empty vector is returned.
+    ///
+    /// [`ipaddress.summarize_address_range`]: https://docs.python.org/3/library/ipaddress.html#ipaddress.summarize_address_range
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::Ipv6Network;
+    ///
+    /// let ranges = Ipv6Network::summarize_address_range(
+    ///     Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0),
+    ///     Ipv6Addr::new(0x2001, 0xdb9, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff),
+    /// );
+    ///
+    /// assert_eq!(Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 31)?, ranges[0]);
+    /// # Ok::<(), ip_network::IpNetworkError>(())
+    /// ```
+    pub fn summarize_address_range(first: Ipv6Addr, last: Ipv6Addr) -> Vec<Self> {
+        let mut first_int = u128::from(first);
+        let last_int = u128::from(last);
+
+        let mut vector = vec![];
+
+        while first_int <= last_int {
+            let bit_length_diff = if last_int - first_int == u128::MAX {
+                Self::LENGTH
+            } else {
+                Self::LENGTH - (last_int - first_int + 1).leading_zeros() as u8 - 1
+            };
+
+            let nbits = cmp::min(first_int.trailing_zeros() as u8, bit_length_diff);
 
-        Self::new_truncate(network_address, netmask).map_err(IpNetworkParseError::IpNetworkError)
+            vector.push(Self {
+                network_address: Ipv6Addr::from(first_int),
+                netmask: Self::LENGTH - nbits,
+            });
+
+            if nbits == Self::LENGTH {
+                break;
+            }
+
+            match first_int.checked_add(1 << nbits) {
+                Some(x) => first_int = x,
+                None => break,
+            }
+        }
+
+        vector
     }
 
     /// Return an iterator of the collapsed Ipv6Networks.
@@ -570,9 +876,12 @@ impl Ipv6Network {
             }
         }
 
+        // Map is sorted by supernets, but networks must be sorted by itself to remove contained ones.
+        let mut networks: Vec<Ipv6Network> = subnets.into_values().collect();
+        networks.sort_unstable();
+
         let mut output: Vec<Ipv6Network> = vec![];
-        // into_values() is unstable
-        for (_, net) in subnets.into_iter() {
+        for net in networks {
             if let Some(last) = output.last() {
                 // Since they are sorted, last.network_address <= net.network_address is a given.
                 if last.last_address() >= net.last_address() {
@@ -621,15 +930,7 @@ impl FromStr for Ipv6Network {
     /// # Ok::<(), ip_network::IpNetworkParseError>(())
     /// ```
     fn from_str(s: &str) -> Result<Ipv6Network, IpNetworkParseError> {
-        let (ip, netmask) =
-            helpers::split_ip_netmask(s).ok_or(IpNetworkParseError::InvalidFormatError)?;
-
-        let network_address =
-            Ipv6Addr::from_str(ip).map_err(|_| IpNetworkParseError::AddrParseError)?;
-        let netmask =
-            u8::from_str(netmask).map_err(|_| IpNetworkParseError::InvalidNetmaskFormat)?;
-
-        Self::new(network_address, netmask).map_err(IpNetworkParseError::IpNetworkError)
+        helpers::parse_network(s, Some(IpFamily::V6), Self::new)
     }
 }
 
@@ -658,10 +959,28 @@ impl Hash for Ipv6Network {
     }
 }
 
+impl Add<u128> for Ipv6Network {
+    type Output = Option<Self>;
+
+    /// Same as [`Ipv6Network::checked_add`].
+    fn add(self, offset: u128) -> Self::Output {
+        self.checked_add(offset)
+    }
+}
+
+impl Sub<u128> for Ipv6Network {
+    type Output = Option<Self>;
+
+    /// Same as [`Ipv6Network::checked_sub`].
+    fn sub(self, offset: u128) -> Self::Output {
+        self.checked_sub(offset)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::Ipv6Addr;
-    use crate::{Ipv6Network, IpNetworkError, Ipv6MulticastScope};
+    use crate::{HostPolicy, Ipv6Network, IpNetworkError, Ipv6MulticastScope};
     use std::str::FromStr;
     use std::hash::{Hash, Hasher};
     use std::collections::hash_map::DefaultHasher;
@@ -692,7 +1011,13 @@ mod tests {
         let ip = Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 0);
         let network = Ipv6Network::new(ip, 129);
         assert!(network.is_err());
-        assert_eq!(IpNetworkError::NetmaskError(129), network.unwrap_err());
+        assert_eq!(
+            IpNetworkError::NetmaskError {
+                netmask: 129,
+                max_netmask: 128
+            },
+            network.unwrap_err()
+        );
     }
 
     #[test]
@@ -700,7 +1025,13 @@ mod tests {
         let ip = Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 0);
         let network = Ipv6Network::new_truncate(ip, 129);
         assert!(network.is_err());
-        assert_eq!(IpNetworkError::NetmaskError(129), network.unwrap_err());
+        assert_eq!(
+            IpNetworkError::NetmaskError {
+                netmask: 129,
+                max_netmask: 128
+            },
+            network.unwrap_err()
+        );
     }
 
     #[test]
@@ -731,6 +1062,62 @@ mod tests {
         assert_eq!(None, ipv6_network.supernet());
     }
 
+    #[test]
+    fn hosts_with_policy() {
+        let address = |last: u128| Ipv6Addr::from(0x2001_0db8_u128 << 96 | last);
+        let cases = [
+            (
+                64,
+                HostPolicy::Classic,
+                u64::MAX as u128,
+                1,
+                u64::MAX as u128,
+            ),
+            (
+                64,
+                HostPolicy::PointToPoint,
+                u64::MAX as u128,
+                1,
+                u64::MAX as u128,
+            ),
+            (
+                64,
+                HostPolicy::Usable,
+                u64::MAX as u128 - 128,
+                1,
+                u64::MAX as u128 - 128,
+            ),
+            (64, HostPolicy::All, 1 << 64, 0, u64::MAX as u128),
+            (120, HostPolicy::Usable, 127, 1, 127),
+            (121, HostPolicy::Usable, 127, 1, 127),
+            (126, HostPolicy::Classic, 3, 1, 3),
+            (127, HostPolicy::Classic, 1, 1, 1),
+            (127, HostPolicy::PointToPoint, 2, 0, 1),
+            (127, HostPolicy::Usable, 2, 0, 1),
+            (128, HostPolicy::PointToPoint, 1, 0, 0),
+            (128, HostPolicy::All, 1, 0, 0),
+        ];
+        for &(netmask, policy, count, first, last) in &cases {
+            let ip_network = Ipv6Network::new(address(0), netmask).unwrap();
+            let hosts = ip_network.hosts_with(policy);
+            assert_eq!(count, hosts.real_len(), "{:?} {}", policy, netmask);
+            assert_eq!(count, ip_network.host_count(policy));
+            assert_eq!(Some(address(first)), ip_network.hosts_with(policy).next());
+            if count < 1000 {
+                assert_eq!(Some(address(last)), ip_network.hosts_with(policy).last());
+            }
+        }
+
+        let ip_network = Ipv6Network::new(address(0), 128).unwrap();
+        assert_eq!(0, ip_network.hosts_with(HostPolicy::Classic).real_len());
+        assert_eq!(None, ip_network.hosts_with(HostPolicy::Classic).next());
+
+        let ip_network = Ipv6Network::new(Ipv6Addr::UNSPECIFIED, 0).unwrap();
+        assert_eq!(u128::MAX, ip_network.host_count(HostPolicy::All));
+        assert_eq!(u128::MAX, ip_network.host_count(HostPolicy::Classic));
+        assert_eq!(u128::MAX - 128, ip_network.host_count(HostPolicy::Usable));
+    }
+
     #[test]
     fn subnets() {
         let mut subnets = return_test_ipv6_network().subnets();
@@ -856,6 +1243,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn summarize_address_range() {
+        let networks = Ipv6Network::summarize_address_range(
+            Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1),
+            Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 4),
+        );
+        assert_eq!(
+            vec![
+                Ipv6Network::from_str("::1/128").unwrap(),
+                Ipv6Network::from_str("::2/127").unwrap(),
+                Ipv6Network::from_str("::4/128").unwrap(),
+            ],
+            networks
+        );
+
+        let networks = Ipv6Network::summarize_address_range(
+            Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0xffff),
+            Ipv6Addr::new(0, 0, 0, 0, 0, 0, 1, 0xffff),
+        );
+        assert_eq!(
+            vec![
+                Ipv6Network::from_str("::ffff/128").unwrap(),
+                Ipv6Network::from_str("::1:0/112").unwrap(),
+            ],
+            networks
+        );
+    }
+
+    #[test]
+    fn summarize_address_range_whole_range() {
+        let networks = Ipv6Network::summarize_address_range(
+            Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0),
+            Ipv6Addr::new(
+                0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff,
+            ),
+        );
+        assert_eq!(vec![Ipv6Network::DEFAULT_ROUTE], networks);
+    }
+
+    #[test]
+    fn summarize_address_range_first_is_bigger() {
+        let networks = Ipv6Network::summarize_address_range(
+            Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1),
+            Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0),
+        );
+        assert_eq!(0, networks.len());
+    }
+
     #[test]
     fn collapse_addresses() {
         let addresses = [
@@ -868,6 +1303,16 @@ mod tests {
         assert_eq!(Ipv6Network::from_str("2001::/96").unwrap(), collapsed[0]);
     }
 
+    #[test]
+    fn collapse_addresses_disjoint() {
+        let addresses = [
+            Ipv6Network::from_str("c000::/6").unwrap(),
+            Ipv6Network::from_str("c800::/5").unwrap(),
+        ];
+        let collapsed = Ipv6Network::collapse_addresses(&addresses);
+        assert_eq!(addresses.to_vec(), collapsed);
+    }
+
     #[test]
     fn parse() {
         let ip_network: Ipv6Network = "2001:db8::/32".parse().unwrap();
@@ -901,4 +1346,99 @@ mod tests {
 
         assert_ne!(hasher1.finish(), hasher2.finish());
     }
+
+    #[test]
+    fn address_arithmetic() {
+        let ip_network =
+            Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 64).unwrap();
+        assert_eq!(
+            Some(Ipv6Addr::new(
+                0x2001, 0xdb8, 0, 0, 0xffff, 0xffff, 0xffff, 0xffff
+            )),
+            ip_network.nth_address(u64::MAX.into())
+        );
+        assert_eq!(None, ip_network.nth_address(1 << 64));
+        assert_eq!(
+            Some(5),
+            ip_network.address_index(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 5))
+        );
+        assert_eq!(
+            None,
+            ip_network.address_index(Ipv6Addr::new(0x2001, 0xdb8, 0, 1, 0, 0, 0, 0))
+        );
+
+        let ip_network = Ipv6Network::new(Ipv6Addr::UNSPECIFIED, 0).unwrap();
+        assert_eq!(
+            Some(Ipv6Addr::from(u128::MAX)),
+            ip_network.nth_address(u128::MAX)
+        );
+        assert_eq!(
+            Some(u128::MAX),
+            ip_network.address_index(Ipv6Addr::from(u128::MAX))
+        );
+    }
+
+    #[test]
+    fn network_arithmetic() {
+        let ip_network =
+            Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32).unwrap();
+        let next = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb9, 0, 0, 0, 0, 0, 0), 32).unwrap();
+        assert_eq!(Some(next), ip_network.next());
+        assert_eq!(Some(ip_network), next.prev());
+        assert_eq!(ip_network.checked_add(3), next + 2);
+        assert_eq!(None, ip_network + u128::MAX);
+        assert_eq!(None, ip_network - u128::MAX);
+
+        let last = Ipv6Network::new(Ipv6Addr::from(u128::MAX), 128).unwrap();
+        assert_eq!(None, last.next());
+        assert_eq!(
+            Some(last),
+            Ipv6Network::new(Ipv6Addr::UNSPECIFIED, 128).unwrap() + u128::MAX
+        );
+        assert_eq!(
+            None,
+            Ipv6Network::new(Ipv6Addr::UNSPECIFIED, 128).unwrap().prev()
+        );
+
+        let ip_network = Ipv6Network::new(Ipv6Addr::UNSPECIFIED, 0).unwrap();
+        assert_eq!(Some(ip_network), ip_network + 0);
+        assert_eq!(Some(ip_network), ip_network - 0);
+        assert_eq!(None, ip_network.next());
+        assert_eq!(None, ip_network.prev());
+    }
+
+    #[test]
+    fn subnet_indexing() {
+        let ip_network =
+            Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 48).unwrap();
+        assert_eq!(1 << 16, ip_network.subnet_count(64));
+        assert_eq!(0, ip_network.subnet_count(47));
+        assert_eq!(0, ip_network.subnet_count(129));
+
+        let tenant = ip_network.subnet_at(64, 0xffff).unwrap();
+        assert_eq!(
+            Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0xffff, 0, 0, 0, 0), 64).unwrap(),
+            tenant
+        );
+        assert_eq!(Some(0xffff), ip_network.subnet_index(&tenant));
+        assert_eq!(None, ip_network.subnet_at(64, 1 << 16));
+        assert_eq!(None, tenant.subnet_index(&ip_network));
+        assert_eq!(
+            Some((0xffff << 64) | 1),
+            ip_network.subnet_index(
+                &Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0xffff, 0, 0, 0, 1), 128)
+                    .unwrap()
+            )
+        );
+
+        let ip_network = Ipv6Network::new(Ipv6Addr::UNSPECIFIED, 0).unwrap();
+        assert_eq!(u128::MAX, ip_network.subnet_count(128));
+        assert_eq!(1 << 127, ip_network.subnet_count(127));
+        assert_eq!(Some(ip_network), ip_network.subnet_at(0, 0));
+        assert_eq!(None, ip_network.subnet_at(0, 1));
+        let last = ip_network.subnet_at(128, u128::MAX).unwrap();
+        assert_eq!(Ipv6Network::from(Ipv6Addr::from(u128::MAX)), last);
+        assert_eq!(Some(u128::MAX), ip_network.subnet_index(&last));
+        assert_eq!(Some(0), ip_network.subnet_index(&ip_network));
+    }
 }
@@ -1,5 +1,6 @@
-use std::net::{Ipv4Addr, Ipv6Addr};
-use crate::{Ipv4Network, Ipv6Network};
+use std::convert::TryFrom;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::{HostPolicy, Ipv4Network, Ipv6Network};
 use crate::helpers;
 
 #[cfg(target_pointer_width = "16")]
@@ -8,8 +9,6 @@ const POINTER_WIDTH: u32 = 16;
 const POINTER_WIDTH: u32 = 32;
 #[cfg(target_pointer_width = "64")]
 const POINTER_WIDTH: u32 = 64;
-#[cfg(target_pointer_width = "128")]
-const POINTER_WIDTH: u32 = 128;
 
 /// IPv4 range iterator.
 pub struct Ipv4RangeIterator {
@@ -53,17 +52,26 @@ impl Ipv4RangeIterator {
     /// Constructs new `Ipv4RangeIterator` that iterates host (without network and broadcast address)
     /// IPs in Ipv4Network.
     pub fn hosts(network: Ipv4Network) -> Self {
-        if network.netmask() >= 31 {
+        Self::hosts_with(network, HostPolicy::Classic)
+    }
+
+    /// Constructs new `Ipv4RangeIterator` that iterates host IPs in Ipv4Network selected by `policy`.
+    pub fn hosts_with(network: Ipv4Network, policy: HostPolicy) -> Self {
+        let from = u32::from(network.network_address());
+        let to = u32::from(network.broadcast_address());
+        let point_to_point = network.netmask() >= Ipv4Network::LENGTH - 1;
+        match policy {
+            HostPolicy::All => Self::new(Ipv4Addr::from(from), Ipv4Addr::from(to)),
+            HostPolicy::PointToPoint | HostPolicy::Usable if point_to_point => {
+                Self::new(Ipv4Addr::from(from), Ipv4Addr::from(to))
+            }
             // Network doesn't contains any host IPs, create empty iterator.
-            Self {
+            HostPolicy::Classic if point_to_point => Self {
                 current: 0,
                 to: 0,
                 is_done: true,
-            }
-        } else {
-            let from = Ipv4Addr::from(u32::from(network.network_address()) + 1);
-            let to = Ipv4Addr::from(u32::from(network.broadcast_address()) - 1);
-            Self::new(from, to)
+            },
+            _ => Self::new(Ipv4Addr::from(from + 1), Ipv4Addr::from(to - 1)),
         }
     }
 }
@@ -87,11 +95,11 @@ impl Iterator for Ipv4RangeIterator {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        if self.is_done {
+        if self.is_done || self.current > self.to {
             return (0, Some(0));
         }
 
-        let remaining = (self.to - self.current + 1) as usize;
+        let remaining = (u64::from(self.to) - u64::from(self.current) + 1) as usize;
         (remaining, Some(remaining))
     }
 }
@@ -167,8 +175,24 @@ impl Iterator for Ipv4NetworkIterator {
         }
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // Empty iterator has netmask 0, so step would overflow.
         if self.is_done {
+            return None;
+        }
+        match u32::try_from(n)
+            .ok()
+            .and_then(|n| n.checked_mul(self.step()))
+            .and_then(|offset| self.current.checked_add(offset))
+        {
+            Some(x) if x <= self.to => self.current = x,
+            _ => self.is_done = true,
+        };
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.is_done || self.current > self.to {
             return (0, Some(0));
         }
 
@@ -227,7 +251,7 @@ impl Ipv6NetworkIterator {
     }
 
     pub fn real_len(&self) -> u128 {
-        if self.is_done {
+        if self.is_done || self.current > self.to {
             return 0;
         }
 
@@ -256,6 +280,21 @@ impl Iterator for Ipv6NetworkIterator {
         }
     }
 
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // Empty iterator has netmask 0, so step would overflow.
+        if self.is_done {
+            return None;
+        }
+        match (n as u128)
+            .checked_mul(self.step())
+            .and_then(|offset| self.current.checked_add(offset))
+        {
+            Some(x) if x <= self.to => self.current = x,
+            _ => self.is_done = true,
+        };
+        self.next()
+    }
+
     fn size_hint(&self) -> (usize, Option<usize>) {
         let remaining = self.real_len();
 
@@ -270,11 +309,156 @@ impl Iterator for Ipv6NetworkIterator {
 
 impl ExactSizeIterator for Ipv6NetworkIterator {}
 
+/// IPv6 range iterator.
+///
+/// Number of addresses can be bigger than `usize`, so iterator doesn't implement
+/// `ExactSizeIterator`, use [`real_len()`](Self::real_len) to get number of remaining addresses.
+pub struct Ipv6RangeIterator {
+    current: u128,
+    to: u128,
+    is_done: bool,
+}
+
+impl Ipv6RangeIterator {
+    /// Constructs new `Ipv6RangeIterator` for given range, both `from` and `to` address are inclusive.
+    ///
+    /// # Panics
+    ///
+    /// When `from` address is bigger than `to` address.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::net::Ipv6Addr;
+    /// use ip_network::iterator::Ipv6RangeIterator;
+    ///
+    /// let mut iterator = Ipv6RangeIterator::new(
+    ///     Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0),
+    ///     Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0xff),
+    /// );
+    /// assert_eq!(256, iterator.real_len());
+    /// assert_eq!(iterator.next().unwrap(), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0));
+    /// assert_eq!(iterator.last().unwrap(), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0xff));
+    /// ```
+    pub fn new(from: Ipv6Addr, to: Ipv6Addr) -> Self {
+        let current = u128::from(from);
+        let to = u128::from(to);
+        assert!(to >= current);
+        Self {
+            current,
+            to,
+            is_done: false,
+        }
+    }
+
+    /// Constructs new `Ipv6RangeIterator` that iterates host IPs in Ipv6Network selected by `policy`.
+    pub fn hosts_with(network: Ipv6Network, policy: HostPolicy) -> Self {
+        let from = u128::from(network.network_address());
+        let to = u128::from(network.last_address());
+        let point_to_point = network.netmask() >= Ipv6Network::LENGTH - 1;
+        let (from, to) = match policy {
+            HostPolicy::All => (from, to),
+            HostPolicy::PointToPoint | HostPolicy::Usable if point_to_point => (from, to),
+            HostPolicy::Classic if network.netmask() == Ipv6Network::LENGTH => {
+                return Self {
+                    current: 0,
+                    to: 0,
+                    is_done: true,
+                };
+            }
+            // Without RFC 2526 reserved subnet anycast addresses.
+            HostPolicy::Usable if network.netmask() <= 120 => (from + 1, to - 128),
+            _ => (from + 1, to),
+        };
+        Self::new(Ipv6Addr::from(from), Ipv6Addr::from(to))
+    }
+
+    /// Returns number of remaining addresses. When iterator contains all 2^128 addresses,
+    /// `u128::MAX` is returned.
+    pub fn real_len(&self) -> u128 {
+        if self.is_done {
+            return 0;
+        }
+
+        (self.to - self.current).saturating_add(1)
+    }
+}
+
+impl Iterator for Ipv6RangeIterator {
+    type Item = Ipv6Addr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current <= self.to && !self.is_done {
+            let output = self.current;
+
+            match self.current.checked_add(1) {
+                Some(x) if x <= self.to => self.current = x,
+                _ => self.is_done = true,
+            };
+
+            Some(Self::Item::from(output))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.real_len();
+
+        if 128 - remaining.leading_zeros() > POINTER_WIDTH {
+            (usize::MAX, None)
+        } else {
+            let remaining_u64 = remaining as u64;
+            (remaining_u64 as usize, Some(remaining_u64 as usize))
+        }
+    }
+}
+
+/// Iterator over addresses of IPv4 or IPv6 range.
+pub enum IpRangeIterator {
+    /// IPv4 range iterator.
+    V4(Ipv4RangeIterator),
+    /// IPv6 range iterator.
+    V6(Ipv6RangeIterator),
+}
+
+impl IpRangeIterator {
+    /// Returns number of remaining addresses, see [`Ipv6RangeIterator::real_len`].
+    pub fn real_len(&self) -> u128 {
+        match self {
+            IpRangeIterator::V4(iterator) if iterator.is_done || iterator.current > iterator.to => {
+                0
+            }
+            IpRangeIterator::V4(iterator) => u128::from(iterator.to - iterator.current) + 1,
+            IpRangeIterator::V6(iterator) => iterator.real_len(),
+        }
+    }
+}
+
+impl Iterator for IpRangeIterator {
+    type Item = IpAddr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            IpRangeIterator::V4(iterator) => iterator.next().map(IpAddr::V4),
+            IpRangeIterator::V6(iterator) => iterator.next().map(IpAddr::V6),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            IpRangeIterator::V4(iterator) => iterator.size_hint(),
+            IpRangeIterator::V6(iterator) => iterator.size_hint(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::{Ipv4Addr, Ipv6Addr};
-    use crate::{Ipv4Network, Ipv6Network};
-    use super::{Ipv4NetworkIterator, Ipv4RangeIterator, Ipv6NetworkIterator};
+    use crate::{HostPolicy, Ipv4Network, Ipv6Network};
+    use super::{IpRangeIterator, Ipv4NetworkIterator, Ipv4RangeIterator, Ipv6NetworkIterator};
+    use super::Ipv6RangeIterator;
 
     #[test]
     fn ipv4_range_iterator() {
@@ -309,6 +493,95 @@ mod tests {
         assert_eq!(iterator.next().unwrap(), Ipv4Addr::new(255, 255, 255, 255));
         assert!(iterator.next().is_none());
         assert_eq!(iterator.len(), 0);
+
+        let mut iterator =
+            Ipv4RangeIterator::new(Ipv4Addr::new(192, 168, 2, 1), Ipv4Addr::new(192, 168, 2, 1));
+        assert_eq!(iterator.next().unwrap(), Ipv4Addr::new(192, 168, 2, 1));
+        assert_eq!(iterator.len(), 0);
+    }
+
+    #[test]
+    fn ipv6_range_iterator() {
+        let mut iterator = Ipv6RangeIterator::new(
+            Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0),
+            Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0xff),
+        );
+        assert_eq!(iterator.real_len(), 256);
+        assert_eq!(
+            iterator.next().unwrap(),
+            Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0)
+        );
+        assert_eq!(iterator.real_len(), 255);
+        assert_eq!(
+            iterator.last().unwrap(),
+            Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0xff)
+        );
+
+        let iterator = Ipv6RangeIterator::new(Ipv6Addr::UNSPECIFIED, Ipv6Addr::from(u128::MAX));
+        assert_eq!(iterator.real_len(), u128::MAX);
+        assert_eq!(iterator.size_hint(), (usize::MAX, None));
+    }
+
+    #[test]
+    fn exhausted_iterators_length() {
+        let network = Ipv4Network::new(Ipv4Addr::new(192, 168, 2, 0), 30).unwrap();
+        let mut iterator =
+            IpRangeIterator::V4(Ipv4RangeIterator::hosts_with(network, HostPolicy::All));
+        assert_eq!(iterator.real_len(), 4);
+        assert_eq!(iterator.by_ref().count(), 4);
+        assert_eq!(iterator.real_len(), 0);
+        assert_eq!(iterator.size_hint(), (0, Some(0)));
+
+        let mut iterator = Ipv4NetworkIterator::new(network, 31);
+        assert_eq!(iterator.by_ref().count(), 2);
+        assert_eq!(iterator.len(), 0);
+
+        let network =
+            Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 126).unwrap();
+        let mut iterator = Ipv6NetworkIterator::new(network, 127);
+        assert_eq!(iterator.by_ref().count(), 2);
+        assert_eq!(iterator.real_len(), 0);
+    }
+
+    #[test]
+    fn network_iterator_nth() {
+        let network = Ipv4Network::new(Ipv4Addr::new(10, 0, 0, 0), 8).unwrap();
+        let mut iterator = Ipv4NetworkIterator::new(network, 24);
+        assert_eq!(iterator.nth(258), network.subnet_at(24, 258));
+        assert_eq!(iterator.next(), network.subnet_at(24, 259));
+        assert_eq!(iterator.len(), 65536 - 260);
+        assert_eq!(iterator.nth(65536 - 261), network.subnet_at(24, 65535));
+        assert_eq!(iterator.len(), 0);
+        assert!(iterator.next().is_none());
+
+        let mut iterator = Ipv4NetworkIterator::new(network, 24);
+        assert!(iterator.nth(65536).is_none());
+        assert!(iterator.next().is_none());
+
+        let network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 48).unwrap();
+        let mut iterator = Ipv6NetworkIterator::new(network, 64);
+        assert_eq!(iterator.nth(42), network.subnet_at(64, 42));
+        assert_eq!(iterator.real_len(), 65536 - 43);
+        assert_eq!(iterator.nth(65536 - 44), network.subnet_at(64, 65535));
+        assert_eq!(iterator.real_len(), 0);
+        assert!(iterator.nth(usize::MAX).is_none());
+    }
+
+    #[test]
+    fn empty_network_iterator_nth() {
+        let network = Ipv4Network::new(Ipv4Addr::new(10, 0, 0, 1), 32).unwrap();
+        assert!(network.subnets().nth(1).is_none());
+        assert!(Ipv4NetworkIterator::new(network, 32).nth(1).is_none());
+        let network = Ipv4Network::new(Ipv4Addr::new(10, 0, 0, 0), 8).unwrap();
+        assert!(network.subnets_with_prefix(8).nth(1).is_none());
+        assert_eq!(0, network.subnets_with_prefix(8).skip(1).count());
+
+        let network =
+            Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1), 128).unwrap();
+        assert!(network.subnets().nth(1).is_none());
+        let network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32).unwrap();
+        assert!(network.subnets_with_prefix(32).nth(1).is_none());
+        assert_eq!(0, network.subnets_with_prefix(32).skip(1).count());
     }
 
     #[test]
@@ -4,9 +4,38 @@
 //!
 //! When using this crate, you can choose to compile with these features:
 //!
-//! * `serde` – for serialization and deserialization by [Serde framework](https://serde.rs).
-//! * `diesel` – support for [PostgreSQL CIDR type] with [Diesel ORM](https://diesel.rs).
-//! * `postgres` – support for [PostgreSQL CIDR type] with [postgres crate](https://github.com/sfackler/rust-postgres).
+//! * `serde` – for serialization and deserialization by [Serde framework](https://serde.rs),
+//!   representation can be selected by modules in `ip_network::serde` module.
+//! * `diesel` – support for [PostgreSQL CIDR and INET types] with [Diesel ORM](https://diesel.rs).
+//! * `diesel2` – the same support as `diesel` feature for [Diesel ORM](https://diesel.rs) version 2,
+//!   can be enabled together with `diesel` feature.
+//! * `diesel2-mysql`, `diesel2-sqlite` – enables `diesel2` feature with MySQL or SQLite backend. Networks
+//!   can be stored as `Text` in CIDR notation or as `Binary` in order-preserving encoding, that supports
+//!   containment queries (also available for PostgreSQL with both `diesel` and `diesel2` features).
+//! * `postgres-types` – support for [PostgreSQL CIDR and INET types] with [postgres-types crate](https://crates.io/crates/postgres-types),
+//!   used by both [postgres](https://crates.io/crates/postgres) and [tokio-postgres](https://crates.io/crates/tokio-postgres) crates.
+//! * `postgres` – alias for `postgres-types` feature.
+//! * `sqlx` – support for [sqlx](https://crates.io/crates/sqlx), enables `sqlx-postgres`, `sqlx-mysql`
+//!   and `sqlx-sqlite` features. PostgreSQL uses native CIDR and INET types, MySQL and SQLite store
+//!   networks as text in CIDR notation.
+//! * `cbor` – encoding and decoding of networks and interfaces to CBOR according to
+//!   [RFC 9164](https://www.rfc-editor.org/rfc/rfc9164.html).
+//! * `ciborium` – enables `cbor` and `serde` features and serde adapter for [ciborium](https://crates.io/crates/ciborium).
+//! * `schemars` – implements [`JsonSchema`](https://docs.rs/schemars) for networks, schema describes
+//!   string in CIDR notation used by human-readable serde format.
+//! * `utoipa` – implements [`ToSchema`](https://docs.rs/utoipa) for networks with the same OpenAPI schema.
+//! * `arbitrary` – implements `Arbitrary` from [arbitrary](https://crates.io/crates/arbitrary) crate
+//!   for networks, that can be used for fuzzing.
+//! * `proptest` – strategies for property-based testing with [proptest](https://crates.io/crates/proptest)
+//!   in `ip_network::proptest` module.
+//! * `arc-swap` – prefix map in `concurrent` module, that can be updated while other threads
+//!   are doing lookups without locks, using [arc-swap](https://crates.io/crates/arc-swap) crate.
+//! * `memmap2` – opening of table files from `table_file` module mapped to memory with
+//!   [memmap2](https://crates.io/crates/memmap2) crate.
+//! * `serde_json` – loading of ROAs from JSON export in `rpki` module with
+//!   [serde_json](https://crates.io/crates/serde_json) crate, enables `serde` feature.
+//! * `rusqlite` – support for [rusqlite](https://crates.io/crates/rusqlite) and SQL functions for
+//!   network queries, see `rusqlite_support::register_functions`.
 //!
 //! ## Examples
 //!
@@ -22,73 +51,265 @@
 //! # Ok::<(), ip_network::IpNetworkError>(())
 //! ```
 //!
-//! [PostgreSQL CIDR type]: https://www.postgresql.org/docs/current/datatype-net-types.html#DATATYPE-CIDR
+//! [PostgreSQL CIDR and INET types]: https://www.postgresql.org/docs/current/datatype-net-types.html
 
 #[cfg(feature = "diesel")]
 #[macro_use]
 extern crate diesel;
 
+pub mod acl;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_support;
+#[cfg(any(feature = "diesel", feature = "diesel2"))]
+mod binary_common;
+pub mod bulk;
+#[cfg(feature = "cbor")]
+/// Encoding of networks to CBOR according to RFC 9164.
+pub mod cbor;
+#[cfg(feature = "arc-swap")]
+pub mod concurrent;
+#[cfg(feature = "diesel2")]
+/// Support for Diesel 2 PostgreSQL CIDR and INET types.
+pub mod diesel2_support;
+#[cfg(any(feature = "diesel", feature = "diesel2"))]
+mod diesel_common;
 #[cfg(feature = "diesel")]
-/// Support for Diesel PostgreSQL CIDR type.
+/// Support for Diesel PostgreSQL CIDR and INET types.
 pub mod diesel_support;
 mod helpers;
+mod ip_interface;
 mod ip_network;
 mod ipv4_network;
 mod ipv6_network;
 /// `Ipv4RangeIterator`, `Ipv4NetworkIterator`, and `Ipv6NetworkIterator`.
 pub mod iterator;
-#[cfg(any(feature = "diesel", feature = "postgres"))]
+pub mod lpm;
+#[cfg(any(
+    feature = "diesel",
+    feature = "diesel2",
+    feature = "postgres-types",
+    feature = "sqlx-postgres"
+))]
 mod postgres_common;
-#[cfg(feature = "postgres")]
+#[cfg(feature = "postgres-types")]
 mod postgres_support;
+pub mod prefix_list;
+pub mod prefix_range;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+pub mod rpki;
+#[cfg(feature = "rusqlite")]
+/// Support for SQLite with rusqlite crate.
+pub mod rusqlite_support;
+#[cfg(any(feature = "schemars", feature = "utoipa"))]
+mod schema_common;
+#[cfg(feature = "schemars")]
+mod schemars_support;
+#[cfg(feature = "serde")]
+pub mod serde;
 #[cfg(feature = "serde")]
 mod serde_support;
+#[cfg(any(
+    feature = "sqlx-postgres",
+    feature = "sqlx-mysql",
+    feature = "sqlx-sqlite"
+))]
+mod sqlx_support;
+pub mod table_file;
+#[cfg(feature = "utoipa")]
+mod utoipa_support;
+#[cfg(test)]
+mod xorshift;
 
 use std::error::Error;
 use std::fmt;
+use std::ops::Range;
 
+pub use self::ip_interface::{IpInterface, Ipv4Interface, Ipv6Interface};
 pub use self::ip_network::IpNetwork;
 pub use self::ipv4_network::Ipv4Network;
 pub use self::ipv6_network::{Ipv6MulticastScope, Ipv6Network};
+#[cfg(any(
+    feature = "diesel",
+    feature = "diesel2",
+    feature = "postgres-types",
+    feature = "sqlx-postgres"
+))]
+pub use self::postgres_common::{CidrDecodeError, Truncated};
+
+/// IP address family.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum IpFamily {
+    /// IP version 4.
+    V4,
+    /// IP version 6.
+    V6,
+}
+
+impl fmt::Display for IpFamily {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IpFamily::V4 => write!(fmt, "IPv4"),
+            IpFamily::V6 => write!(fmt, "IPv6"),
+        }
+    }
+}
+
+/// Selects addresses of network, that are considered as host addresses.
+///
+/// | Policy         | IPv4                                         | IPv6                                           |
+/// |----------------|----------------------------------------------|------------------------------------------------|
+/// | `Classic`      | without network and broadcast address        | without Subnet-Router anycast address          |
+/// | `PointToPoint` | as `Classic`, but /31 and /32 use all        | as `Classic`, but /127 and /128 use all        |
+/// | `All`          | all addresses                                | all addresses                                  |
+/// | `Usable`       | the same as `PointToPoint`                   | as `PointToPoint` and without RFC 2526 anycast |
+///
+/// # Examples
+///
+/// ```
+/// use std::net::Ipv4Addr;
+/// use ip_network::{HostPolicy, Ipv4Network};
+///
+/// let network = Ipv4Network::new(Ipv4Addr::new(192, 0, 2, 0), 31)?;
+/// assert_eq!(0, network.hosts_with(HostPolicy::Classic).len());
+/// assert_eq!(2, network.hosts_with(HostPolicy::PointToPoint).len());
+/// # Ok::<(), ip_network::IpNetworkError>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum HostPolicy {
+    /// Excludes network and broadcast address for IPv4 and Subnet-Router anycast address (the first
+    /// address, [RFC 4291](https://www.rfc-editor.org/rfc/rfc4291.html#section-2.6.1)) for IPv6.
+    /// IPv4 /31 and /32 and IPv6 /128 networks don't contain any host.
+    Classic,
+    /// The same as `Classic`, but IPv4 /31 ([RFC 3021](https://www.rfc-editor.org/rfc/rfc3021.html))
+    /// and IPv6 /127 ([RFC 6164](https://www.rfc-editor.org/rfc/rfc6164.html)) point-to-point
+    /// networks use both addresses and /32 and /128 networks contain single host.
+    PointToPoint,
+    /// All addresses of network.
+    All,
+    /// The same as `PointToPoint`, but IPv6 networks also exclude 128 highest addresses reserved
+    /// for subnet anycast addresses by [RFC 2526](https://www.rfc-editor.org/rfc/rfc2526.html).
+    /// Networks with netmask bigger than 120, where reserved range would cover the whole network,
+    /// exclude only Subnet-Router anycast address.
+    Usable,
+}
 
 /// Errors when creating new IPv4 or IPv6 networks.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum IpNetworkError {
     /// Network mask is bigger than possible for given IP version (32 for IPv4, 128 for IPv6).
-    NetmaskError(u8),
+    NetmaskError {
+        /// Given network mask.
+        netmask: u8,
+        /// Maximal network mask for given IP version.
+        max_netmask: u8,
+    },
     /// Host bits are set in given network IP address.
-    HostBitsSet,
+    HostBitsSet {
+        /// Network with host bits truncated, that would be accepted.
+        truncated: IpNetwork,
+    },
 }
 
 impl Error for IpNetworkError {}
 
 impl fmt::Display for IpNetworkError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let description = match *self {
-            IpNetworkError::NetmaskError(_) => "invalid netmask",
-            IpNetworkError::HostBitsSet => "IP network address has host bits set",
-        };
-        write!(fmt, "{}", description)
+        match *self {
+            IpNetworkError::NetmaskError {
+                netmask,
+                max_netmask,
+            } => write!(
+                fmt,
+                "invalid netmask {}, maximum is {}",
+                netmask, max_netmask
+            ),
+            IpNetworkError::HostBitsSet { ref truncated } => write!(
+                fmt,
+                "IP network address has host bits set, expected {}",
+                truncated
+            ),
+        }
     }
 }
 
 /// Errors from IPv4 or IPv6 network parsing.
-#[derive(Debug, PartialEq)]
+///
+/// Spans are byte ranges into the parsed input string.
+///
+/// # Examples
+///
+/// ```
+/// use ip_network::{IpFamily, Ipv4Network, IpNetworkParseError};
+///
+/// let error = "10.0.0.256/8".parse::<Ipv4Network>().unwrap_err();
+/// assert_eq!(
+///     error,
+///     IpNetworkParseError::AddrParseError { span: 0..10, family: Some(IpFamily::V4) }
+/// );
+/// assert_eq!(error.to_string(), "invalid IPv4 address syntax at position 0");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum IpNetworkParseError {
     /// Network mask is not valid integer between 0 and 255.
-    InvalidNetmaskFormat,
+    InvalidNetmaskFormat {
+        /// Span of network mask in input.
+        span: Range<usize>,
+    },
     /// Network address has invalid format (not X/Y).
-    InvalidFormatError,
+    InvalidFormatError {
+        /// Span of input where the format is broken.
+        span: Range<usize>,
+    },
     /// Invalid IP address syntax (IPv4 or IPv6).
-    AddrParseError,
+    AddrParseError {
+        /// Span of IP address in input.
+        span: Range<usize>,
+        /// Expected IP address family, `None` if both families are accepted.
+        family: Option<IpFamily>,
+    },
     /// Error when creating new IPv4 or IPv6 networks.
-    IpNetworkError(IpNetworkError),
+    IpNetworkError {
+        /// Error returned when creating network.
+        error: IpNetworkError,
+        /// Span of IP address in input when host bits are set, span of network mask otherwise.
+        span: Range<usize>,
+    },
+}
+
+impl IpNetworkParseError {
+    /// Returns span of input that caused the error, if it is known.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ip_network::Ipv4Network;
+    ///
+    /// let error = "10.0.0.0/a".parse::<Ipv4Network>().unwrap_err();
+    /// assert_eq!(error.span(), Some(9..10));
+    ///
+    /// let error = "10.0.0.0/33".parse::<Ipv4Network>().unwrap_err();
+    /// assert_eq!(error.span(), Some(9..11));
+    ///
+    /// let error = "10.0.0.1/8".parse::<Ipv4Network>().unwrap_err();
+    /// assert_eq!(error.span(), Some(0..8));
+    /// ```
+    pub fn span(&self) -> Option<Range<usize>> {
+        match *self {
+            IpNetworkParseError::InvalidNetmaskFormat { ref span }
+            | IpNetworkParseError::InvalidFormatError { ref span }
+            | IpNetworkParseError::AddrParseError { ref span, .. }
+            | IpNetworkParseError::IpNetworkError { ref span, .. } => Some(span.clone()),
+        }
+    }
 }
 
 impl Error for IpNetworkParseError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match *self {
-            IpNetworkParseError::IpNetworkError(ref ip_network_error) => Some(ip_network_error),
+            IpNetworkParseError::IpNetworkError { ref error, .. } => Some(error),
             _ => None,
         }
     }
@@ -97,12 +318,23 @@ impl Error for IpNetworkParseError {
 impl fmt::Display for IpNetworkParseError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            IpNetworkParseError::InvalidNetmaskFormat => write!(fmt, "invalid netmask format"),
-            IpNetworkParseError::InvalidFormatError => write!(fmt, "invalid format"),
-            IpNetworkParseError::AddrParseError => write!(fmt, "invalid IP address syntax"),
-            IpNetworkParseError::IpNetworkError(ref ip_network_error) => {
-                write!(fmt, "{}", ip_network_error)
+            IpNetworkParseError::InvalidNetmaskFormat { ref span } => {
+                write!(fmt, "invalid netmask format at position {}", span.start)
             }
+            IpNetworkParseError::InvalidFormatError { ref span } => write!(
+                fmt,
+                "invalid format at position {}, expected `address/netmask`",
+                span.start
+            ),
+            IpNetworkParseError::AddrParseError { ref span, family } => match family {
+                Some(family) => write!(
+                    fmt,
+                    "invalid {} address syntax at position {}",
+                    family, span.start
+                ),
+                None => write!(fmt, "invalid IP address syntax at position {}", span.start),
+            },
+            IpNetworkParseError::IpNetworkError { ref error, .. } => write!(fmt, "{}", error),
         }
     }
 }
@@ -0,0 +1,663 @@
+//! Compiled read-only table for fast longest prefix match lookups.
+//!
+//! [`LpmTableBuilder`] collects networks with values and compiles them to immutable [`LpmTable`].
+//! IPv4 networks are stored in DIR-24-8 structure, so every lookup needs at most two memory
+//! accesses. IPv6 networks are stored in Poptrie-style multibit trie with 16 bits wide root and
+//! nodes with 64 slots, where children and leaves of node are compressed using population count.
+//!
+//! IPv4 part of table allocates 64 MiB when table contains at least one IPv4 network, in exchange
+//! for lookup speed. For tables that change in time, build new table and replace the old one.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::net::Ipv4Addr;
+//! use ip_network::IpNetwork;
+//! use ip_network::lpm::LpmTableBuilder;
+//!
+//! let mut builder = LpmTableBuilder::new();
+//! builder.insert("10.0.0.0/8".parse::<IpNetwork>()?, "private");
+//! builder.insert("10.1.0.0/16".parse::<IpNetwork>()?, "office");
+//! let table = builder.build();
+//!
+//! let (network, value) = table.longest_match(Ipv4Addr::new(10, 1, 2, 3)).unwrap();
+//! assert_eq!("10.1.0.0/16", network.to_string());
+//! assert_eq!(&"office", value);
+//! assert_eq!(None, table.longest_match(Ipv4Addr::new(192, 168, 0, 1)));
+//! # Ok::<(), ip_network::IpNetworkParseError>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::iter::FromIterator;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+/// Table entry without value.
+const EMPTY: u32 = 0;
+/// Flag of table entry, that points to next level instead of value.
+const NEXT_LEVEL: u32 = 1 << 31;
+
+/// Number of bits resolved by IPv6 root array.
+const ROOT_BITS: u32 = 16;
+/// Number of bits resolved by IPv6 trie node.
+const STRIDE: u32 = 6;
+
+/// Builder of [`LpmTable`].
+///
+/// When the same network is inserted multiple times, the last value is used.
+#[derive(Clone, Debug)]
+pub struct LpmTableBuilder<V> {
+    ipv4: BTreeMap<Ipv4Network, V>,
+    ipv6: BTreeMap<Ipv6Network, V>,
+}
+
+impl<V> LpmTableBuilder<V> {
+    /// Creates empty builder.
+    pub fn new() -> Self {
+        Self {
+            ipv4: BTreeMap::new(),
+            ipv6: BTreeMap::new(),
+        }
+    }
+
+    /// Inserts network with value. If builder already contained this network, old value is
+    /// returned.
+    pub fn insert<N: Into<IpNetwork>>(&mut self, network: N, value: V) -> Option<V> {
+        match network.into() {
+            IpNetwork::V4(network) => self.ipv4.insert(network, value),
+            IpNetwork::V6(network) => self.ipv6.insert(network, value),
+        }
+    }
+
+    /// Returns number of networks in builder.
+    pub fn len(&self) -> usize {
+        self.ipv4.len() + self.ipv6.len()
+    }
+
+    /// Returns `true` if builder contains no networks.
+    pub fn is_empty(&self) -> bool {
+        self.ipv4.is_empty() && self.ipv6.is_empty()
+    }
+
+    /// Compiles networks to lookup table.
+    ///
+    /// # Panics
+    ///
+    /// If table would contain more than 2<sup>31</sup> networks or trie nodes of one address family.
+    pub fn build(self) -> LpmTable<V> {
+        LpmTable {
+            ipv4: Dir248::build(self.ipv4),
+            ipv6: Poptrie::build(self.ipv6),
+        }
+    }
+}
+
+impl<V> Default for LpmTableBuilder<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, N: Into<IpNetwork>> Extend<(N, V)> for LpmTableBuilder<V> {
+    fn extend<I: IntoIterator<Item = (N, V)>>(&mut self, iter: I) {
+        for (network, value) in iter {
+            self.insert(network, value);
+        }
+    }
+}
+
+impl<V, N: Into<IpNetwork>> FromIterator<(N, V)> for LpmTableBuilder<V> {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        let mut builder = Self::new();
+        builder.extend(iter);
+        builder
+    }
+}
+
+/// Immutable table for longest prefix match lookups, created by [`LpmTableBuilder`].
+#[derive(Clone)]
+pub struct LpmTable<V> {
+    ipv4: Dir248<V>,
+    ipv6: Poptrie<V>,
+}
+
+impl<V> LpmTable<V> {
+    /// Returns number of networks in table.
+    pub fn len(&self) -> usize {
+        self.ipv4.values.len() + self.ipv6.values.len()
+    }
+
+    /// Returns `true` if table contains no networks.
+    pub fn is_empty(&self) -> bool {
+        self.ipv4.values.is_empty() && self.ipv6.values.is_empty()
+    }
+
+    /// Returns the most specific network containing given address together with its value.
+    pub fn longest_match<A: Into<IpAddr>>(&self, address: A) -> Option<(IpNetwork, &V)> {
+        match address.into() {
+            IpAddr::V4(address) => self
+                .longest_match_ipv4(address)
+                .map(|(network, value)| (IpNetwork::V4(network), value)),
+            IpAddr::V6(address) => self
+                .longest_match_ipv6(address)
+                .map(|(network, value)| (IpNetwork::V6(network), value)),
+        }
+    }
+
+    /// Returns the most specific IPv4 network containing given address together with its value.
+    #[inline]
+    pub fn longest_match_ipv4(&self, address: Ipv4Addr) -> Option<(Ipv4Network, &V)> {
+        value(&self.ipv4.values, self.ipv4.lookup(address))
+    }
+
+    /// Returns the most specific IPv6 network containing given address together with its value.
+    #[inline]
+    pub fn longest_match_ipv6(&self, address: Ipv6Addr) -> Option<(Ipv6Network, &V)> {
+        value(&self.ipv6.values, self.ipv6.lookup(address))
+    }
+
+    /// Looks up all addresses and stores result for `addresses[i]` to `results[i]`.
+    ///
+    /// # Panics
+    ///
+    /// If `addresses` and `results` have different length.
+    pub fn longest_match_batch<'a>(
+        &'a self,
+        addresses: &[IpAddr],
+        results: &mut [Option<(IpNetwork, &'a V)>],
+    ) {
+        assert_eq!(addresses.len(), results.len(), "lengths are different");
+        for (address, result) in addresses.iter().zip(results.iter_mut()) {
+            *result = self.longest_match(*address);
+        }
+    }
+
+    /// Looks up all IPv4 addresses and stores result for `addresses[i]` to `results[i]`.
+    ///
+    /// Addresses are processed in chunks, where first level entries of all addresses in chunk
+    /// are loaded before second level, so memory accesses of independent lookups can overlap.
+    ///
+    /// # Panics
+    ///
+    /// If `addresses` and `results` have different length.
+    pub fn longest_match_ipv4_batch<'a>(
+        &'a self,
+        addresses: &[Ipv4Addr],
+        results: &mut [Option<(Ipv4Network, &'a V)>],
+    ) {
+        const CHUNK: usize = 16;
+
+        assert_eq!(addresses.len(), results.len(), "lengths are different");
+        if self.ipv4.tbl24.is_empty() {
+            results.iter_mut().for_each(|result| *result = None);
+            return;
+        }
+
+        let mut entries = [EMPTY; CHUNK];
+        for (addresses, results) in addresses.chunks(CHUNK).zip(results.chunks_mut(CHUNK)) {
+            for (address, entry) in addresses.iter().zip(entries.iter_mut()) {
+                *entry = self.ipv4.tbl24[(u32::from(*address) >> 8) as usize];
+            }
+            for ((address, entry), result) in addresses.iter().zip(&entries).zip(results) {
+                let entry = self.ipv4.resolve(*entry, *address);
+                *result = value(&self.ipv4.values, entry);
+            }
+        }
+    }
+
+    /// Looks up all IPv6 addresses and stores result for `addresses[i]` to `results[i]`.
+    ///
+    /// # Panics
+    ///
+    /// If `addresses` and `results` have different length.
+    pub fn longest_match_ipv6_batch<'a>(
+        &'a self,
+        addresses: &[Ipv6Addr],
+        results: &mut [Option<(Ipv6Network, &'a V)>],
+    ) {
+        assert_eq!(addresses.len(), results.len(), "lengths are different");
+        for (address, result) in addresses.iter().zip(results.iter_mut()) {
+            *result = self.longest_match_ipv6(*address);
+        }
+    }
+}
+
+impl<V> fmt::Debug for LpmTable<V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LpmTable")
+            .field("ipv4_networks", &self.ipv4.values.len())
+            .field("ipv6_networks", &self.ipv6.values.len())
+            .field("ipv6_nodes", &self.ipv6.nodes.len())
+            .finish()
+    }
+}
+
+impl<V, N: Into<IpNetwork>> FromIterator<(N, V)> for LpmTable<V> {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        LpmTableBuilder::from_iter(iter).build()
+    }
+}
+
+#[inline]
+fn value<N: Copy, V>(values: &[(N, V)], entry: u32) -> Option<(N, &V)> {
+    if entry == EMPTY {
+        None
+    } else {
+        let (network, value) = &values[entry as usize - 1];
+        Some((*network, value))
+    }
+}
+
+fn entry(index: usize) -> u32 {
+    assert!(index < NEXT_LEVEL as usize, "too many entries");
+    index as u32
+}
+
+/// Fills range of entries with value.
+fn fill(entries: &mut [u32], first: usize, count: usize, value: u32) {
+    for entry in &mut entries[first..first + count] {
+        *entry = value;
+    }
+}
+
+/// DIR-24-8 table for IPv4. First level is indexed by first 24 bits of address, entries for
+/// networks longer than 24 bits point to groups of 256 entries in second level.
+#[derive(Clone)]
+struct Dir248<V> {
+    tbl24: Vec<u32>,
+    tbl8: Vec<u32>,
+    values: Vec<(Ipv4Network, V)>,
+}
+
+impl<V> Dir248<V> {
+    fn build(networks: BTreeMap<Ipv4Network, V>) -> Self {
+        let mut values: Vec<_> = networks.into_iter().collect();
+        // Shorter networks first, so they are overwritten by more specific ones.
+        values.sort_by_key(|(network, _)| network.netmask());
+
+        let mut tbl24 = vec![];
+        let mut tbl8 = vec![];
+        if !values.is_empty() {
+            tbl24 = vec![EMPTY; 1 << 24];
+        }
+
+        for (i, (network, _)) in values.iter().enumerate() {
+            let value = entry(i + 1);
+            let address = u32::from(network.network_address());
+            let netmask = network.netmask();
+            let index = (address >> 8) as usize;
+
+            if netmask <= 24 {
+                fill(&mut tbl24, index, 1 << (24 - netmask), value);
+            } else {
+                let group = if tbl24[index] & NEXT_LEVEL != 0 {
+                    (tbl24[index] & !NEXT_LEVEL) as usize
+                } else {
+                    // New group inherits value of less specific network.
+                    let group = tbl8.len() / 256;
+                    tbl8.resize(tbl8.len() + 256, tbl24[index]);
+                    tbl24[index] = NEXT_LEVEL | entry(group);
+                    group
+                };
+                let first = group * 256 + (address & 0xff) as usize;
+                fill(&mut tbl8, first, 1 << (32 - netmask), value);
+            }
+        }
+
+        Self {
+            tbl24,
+            tbl8,
+            values,
+        }
+    }
+
+    #[inline]
+    fn lookup(&self, address: Ipv4Addr) -> u32 {
+        match self.tbl24.get((u32::from(address) >> 8) as usize) {
+            Some(entry) => self.resolve(*entry, address),
+            None => EMPTY,
+        }
+    }
+
+    #[inline]
+    fn resolve(&self, entry: u32, address: Ipv4Addr) -> u32 {
+        if entry & NEXT_LEVEL == 0 {
+            entry
+        } else {
+            let group = (entry & !NEXT_LEVEL) as usize;
+            self.tbl8[group * 256 + (u32::from(address) & 0xff) as usize]
+        }
+    }
+}
+
+/// Node of IPv6 trie, that resolves `STRIDE` bits of address.
+#[derive(Clone, Copy, Default)]
+struct Node {
+    /// Bit is set for slots, that have child node. Children are stored consecutively.
+    children: u64,
+    /// Bit is set for slots without child, where leaf value differs from value of previous
+    /// slot without child. Leaves are stored consecutively.
+    leaves: u64,
+    child_base: u32,
+    leaf_base: u32,
+}
+
+/// Prefix as address, netmask and table entry with value.
+type Prefix = (u128, u32, u32);
+
+/// Poptrie-style trie for IPv6. Root is array indexed by first `ROOT_BITS` bits of address.
+#[derive(Clone)]
+struct Poptrie<V> {
+    root: Vec<u32>,
+    nodes: Vec<Node>,
+    leaves: Vec<u32>,
+    values: Vec<(Ipv6Network, V)>,
+}
+
+impl<V> Poptrie<V> {
+    fn build(networks: BTreeMap<Ipv6Network, V>) -> Self {
+        let values: Vec<_> = networks.into_iter().collect();
+        let mut trie = Self {
+            root: vec![],
+            nodes: vec![],
+            leaves: vec![],
+            values: vec![],
+        };
+        if values.is_empty() {
+            return trie;
+        }
+
+        // Networks are sorted by address, so prefixes for the same child are next to each other.
+        let prefixes: Vec<Prefix> = values
+            .iter()
+            .enumerate()
+            .map(|(i, (network, _))| {
+                let address = u128::from(network.network_address());
+                (address, network.netmask().into(), entry(i + 1))
+            })
+            .collect();
+
+        let mut root = vec![EMPTY; 1 << ROOT_BITS];
+        let longer = fill_slots(&mut root, 0, ROOT_BITS, &prefixes);
+        for (slot, group) in group_by_slot(&longer, 0, ROOT_BITS) {
+            let index = trie.nodes.len();
+            trie.nodes.push(Node::default());
+            trie.build_node(index, ROOT_BITS, group, root[slot]);
+            root[slot] = NEXT_LEVEL | entry(index);
+        }
+
+        trie.root = root;
+        trie.values = values;
+        trie
+    }
+
+    fn build_node(&mut self, index: usize, depth: u32, prefixes: &[Prefix], default: u32) {
+        let mut slots = [default; 1 << STRIDE];
+        let longer = fill_slots(&mut slots, depth, STRIDE, prefixes);
+        let groups = group_by_slot(&longer, depth, STRIDE);
+
+        let mut children = 0;
+        for (slot, _) in &groups {
+            children |= 1 << slot;
+        }
+
+        let leaf_base = entry(self.leaves.len());
+        let mut leaves = 0;
+        let mut previous = None;
+        for (slot, value) in slots.iter().enumerate() {
+            if children & (1 << slot) == 0 && previous != Some(*value) {
+                leaves |= 1 << slot;
+                self.leaves.push(*value);
+                previous = Some(*value);
+            }
+        }
+
+        let child_base = self.nodes.len();
+        self.nodes
+            .resize(child_base + groups.len(), Node::default());
+        self.nodes[index] = Node {
+            children,
+            leaves,
+            child_base: entry(child_base),
+            leaf_base,
+        };
+
+        for (i, (slot, group)) in groups.into_iter().enumerate() {
+            self.build_node(child_base + i, depth + STRIDE, group, slots[slot]);
+        }
+    }
+
+    #[inline]
+    fn lookup(&self, address: Ipv6Addr) -> u32 {
+        let address = u128::from(address);
+        let entry = match self.root.get((address >> (128 - ROOT_BITS)) as usize) {
+            Some(entry) => *entry,
+            None => return EMPTY,
+        };
+        if entry & NEXT_LEVEL == 0 {
+            return entry;
+        }
+
+        let mut node = &self.nodes[(entry & !NEXT_LEVEL) as usize];
+        let mut depth = ROOT_BITS;
+        loop {
+            let slot = slot(address, depth, STRIDE);
+            let bit = 1 << slot;
+            if node.children & bit == 0 {
+                let leaves = (node.leaves & (u64::MAX >> (63 - slot))).count_ones();
+                return self.leaves[(node.leaf_base + leaves - 1) as usize];
+            }
+            let children = (node.children & (bit - 1)).count_ones();
+            node = &self.nodes[(node.child_base + children) as usize];
+            depth += STRIDE;
+        }
+    }
+}
+
+/// Returns `bits` wide slot of address starting at `depth`. Missing bits after the end of address
+/// are zero.
+#[inline]
+fn slot(address: u128, depth: u32, bits: u32) -> usize {
+    ((address << depth) >> (128 - bits)) as usize
+}
+
+/// Fills slots with values of prefixes, that end at level starting at `depth`. Returns longer
+/// prefixes in original order.
+fn fill_slots(slots: &mut [u32], depth: u32, bits: u32, prefixes: &[Prefix]) -> Vec<Prefix> {
+    let (mut ending, longer): (Vec<Prefix>, Vec<Prefix>) = prefixes
+        .iter()
+        .partition(|(_, netmask, _)| *netmask <= depth + bits);
+    // Shorter prefixes first, so they are overwritten by more specific ones.
+    ending.sort_by_key(|(_, netmask, _)| *netmask);
+    for (address, netmask, value) in ending {
+        let first = slot(address, depth, bits);
+        fill(slots, first, 1 << (depth + bits - netmask), value);
+    }
+    longer
+}
+
+/// Groups prefixes sorted by address by slot at level starting at `depth`.
+fn group_by_slot(prefixes: &[Prefix], depth: u32, bits: u32) -> Vec<(usize, &[Prefix])> {
+    let mut groups = vec![];
+    let mut rest = prefixes;
+    while let Some((address, _, _)) = rest.first() {
+        let current = slot(*address, depth, bits);
+        let length = rest
+            .iter()
+            .position(|(address, _, _)| slot(*address, depth, bits) != current)
+            .unwrap_or(rest.len());
+        let (group, tail) = rest.split_at(length);
+        groups.push((current, group));
+        rest = tail;
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::str::FromStr;
+    use super::{LpmTable, LpmTableBuilder};
+    use crate::xorshift::Random;
+    use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+    /// Returns the longest matching network, the last one for duplicate networks.
+    fn linear_match(
+        networks: &[(IpNetwork, usize)],
+        address: IpAddr,
+    ) -> Option<(IpNetwork, &usize)> {
+        networks
+            .iter()
+            .filter(|(network, _)| network.contains(address))
+            .max_by_key(|(network, _)| network.netmask())
+            .map(|(network, value)| (*network, value))
+    }
+
+    #[test]
+    fn empty() {
+        let table: LpmTable<()> = LpmTableBuilder::new().build();
+        assert!(table.is_empty());
+        assert_eq!(None, table.longest_match(Ipv4Addr::new(1, 2, 3, 4)));
+        assert_eq!(None, table.longest_match(Ipv6Addr::LOCALHOST));
+
+        let mut results = [Some((Ipv4Network::DEFAULT_ROUTE, &())); 2];
+        table.longest_match_ipv4_batch(&[Ipv4Addr::LOCALHOST, Ipv4Addr::BROADCAST], &mut results);
+        assert_eq!([None, None], results);
+    }
+
+    #[test]
+    fn ipv4() {
+        let table: LpmTable<_> = vec![
+            (IpNetwork::from_str("0.0.0.0/0").unwrap(), 0),
+            (IpNetwork::from_str("10.0.0.0/8").unwrap(), 1),
+            (IpNetwork::from_str("10.1.0.0/16").unwrap(), 2),
+            (IpNetwork::from_str("10.1.1.0/25").unwrap(), 3),
+            (IpNetwork::from_str("10.1.1.64/26").unwrap(), 4),
+            (IpNetwork::from_str("10.1.1.1/32").unwrap(), 5),
+            (IpNetwork::from_str("255.255.255.255/32").unwrap(), 6),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(7, table.len());
+
+        let lookup = |address: &str| {
+            let address: Ipv4Addr = address.parse().unwrap();
+            table
+                .longest_match(address)
+                .map(|(n, v)| (n.to_string(), *v))
+        };
+        assert_eq!(Some(("0.0.0.0/0".to_owned(), 0)), lookup("192.168.0.1"));
+        assert_eq!(Some(("10.0.0.0/8".to_owned(), 1)), lookup("10.2.0.1"));
+        assert_eq!(Some(("10.1.0.0/16".to_owned(), 2)), lookup("10.1.1.128"));
+        assert_eq!(Some(("10.1.1.0/25".to_owned(), 3)), lookup("10.1.1.0"));
+        assert_eq!(Some(("10.1.1.64/26".to_owned(), 4)), lookup("10.1.1.127"));
+        assert_eq!(Some(("10.1.1.1/32".to_owned(), 5)), lookup("10.1.1.1"));
+        assert_eq!(
+            Some(("255.255.255.255/32".to_owned(), 6)),
+            lookup("255.255.255.255")
+        );
+        assert_eq!(None, table.longest_match(Ipv6Addr::UNSPECIFIED));
+    }
+
+    #[test]
+    fn ipv6() {
+        let mut builder = LpmTableBuilder::new();
+        builder.insert(IpNetwork::from_str("2001:db8::/32").unwrap(), 1);
+        builder.insert(IpNetwork::from_str("2001:db8:1::/48").unwrap(), 2);
+        builder.insert(IpNetwork::from_str("2001:db8:1::1/128").unwrap(), 3);
+        builder.insert(IpNetwork::from_str("::/0").unwrap(), 4);
+        assert_eq!(
+            Some(4),
+            builder.insert(IpNetwork::from_str("::/0").unwrap(), 0)
+        );
+        let table = builder.build();
+
+        let lookup = |address: &str| {
+            let address: Ipv6Addr = address.parse().unwrap();
+            table
+                .longest_match(address)
+                .map(|(n, v)| (n.to_string(), *v))
+        };
+        assert_eq!(Some(("::/0".to_owned(), 0)), lookup("::1"));
+        assert_eq!(
+            Some(("2001:db8::/32".to_owned(), 1)),
+            lookup("2001:db8:2::1")
+        );
+        assert_eq!(
+            Some(("2001:db8:1::/48".to_owned(), 2)),
+            lookup("2001:db8:1::2")
+        );
+        assert_eq!(
+            Some(("2001:db8:1::1/128".to_owned(), 3)),
+            lookup("2001:db8:1::1")
+        );
+        assert_eq!(None, table.longest_match(Ipv4Addr::LOCALHOST));
+    }
+
+    #[test]
+    fn random_ipv4() {
+        let mut random = Random(0x1234_5678);
+        let networks: Vec<(IpNetwork, usize)> = (0..2000)
+            .map(|i| {
+                // Use only few first octets, so networks overlap.
+                let address = Ipv4Addr::from(random.next() as u32 & 0x0f0f_ffff);
+                let netmask = (random.next() % 33) as u8;
+                (
+                    Ipv4Network::new_truncate(address, netmask).unwrap().into(),
+                    i,
+                )
+            })
+            .collect();
+        let table: LpmTable<_> = networks.iter().cloned().collect();
+
+        let addresses: Vec<Ipv4Addr> = (0..5000)
+            .map(|_| Ipv4Addr::from(random.next() as u32 & 0x0f0f_ffff))
+            .collect();
+        let mut results = vec![None; addresses.len()];
+        table.longest_match_ipv4_batch(&addresses, &mut results);
+        for (address, result) in addresses.iter().zip(results) {
+            let expected = linear_match(&networks, IpAddr::V4(*address));
+            assert_eq!(expected, table.longest_match(*address));
+            assert_eq!(expected, result.map(|(n, v)| (n.into(), v)));
+        }
+    }
+
+    #[test]
+    fn random_ipv6() {
+        let mut random = Random(0x8765_4321);
+        let networks: Vec<(IpNetwork, usize)> = (0..2000)
+            .map(|i| {
+                let address = Ipv6Addr::from(random.next_u128() & !(0xfff0 << 112));
+                let netmask = (random.next() % 129) as u8;
+                (
+                    Ipv6Network::new_truncate(address, netmask).unwrap().into(),
+                    i,
+                )
+            })
+            .collect();
+        let table: LpmTable<_> = networks.iter().cloned().collect();
+
+        let addresses: Vec<IpAddr> = networks
+            .iter()
+            .flat_map(|(network, _)| {
+                // Addresses inside networks and random ones.
+                let address = u128::from(match network.network_address() {
+                    IpAddr::V6(address) => address,
+                    IpAddr::V4(_) => unreachable!(),
+                });
+                let host = random.next_u128() >> network.netmask().min(127);
+                vec![
+                    IpAddr::V6(Ipv6Addr::from(address)),
+                    IpAddr::V6(Ipv6Addr::from(address | host)),
+                    IpAddr::V6(Ipv6Addr::from(random.next_u128() & !(0xfff0 << 112))),
+                ]
+            })
+            .collect();
+        let mut results = vec![None; addresses.len()];
+        table.longest_match_batch(&addresses, &mut results);
+        for (address, result) in addresses.iter().zip(results) {
+            assert_eq!(linear_match(&networks, *address), result);
+        }
+    }
+}
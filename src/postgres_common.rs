@@ -1,76 +1,468 @@
 use std::error::Error;
+use std::fmt;
 use std::net::{Ipv4Addr, Ipv6Addr};
-use crate::{Ipv4Network, Ipv6Network};
+use crate::{IpInterface, IpNetwork, IpNetworkError, Ipv4Network, Ipv6Network};
+use crate::{Ipv4Interface, Ipv6Interface};
 
 // TODO: These constants are true for Linux, but we have to check it for Windows and other systems
 pub const IPV4_TYPE: u8 = 2;
 pub const IPV6_TYPE: u8 = 3;
 
-#[inline]
-pub fn from_sql_ipv4_network(raw: &[u8]) -> Result<Ipv4Network, Box<dyn Error + Sync + Send>> {
-    assert!(raw.len() >= 7);
+/// Length of header (family, netmask, is_cidr flag and address length) before address octets.
+const HEADER_LENGTH: usize = 4;
 
-    if raw[0] != IPV4_TYPE {
-        return Err("CIDR is not IP version 4".into());
-    }
+/// Errors when decoding binary representation of network, used for PostgreSQL CIDR and INET
+/// types and for binary encoding of networks.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum CidrDecodeError {
+    /// Address family byte is not IPv4 or IPv6 or not the expected one.
+    WrongFamily(u8),
+    /// Field has `is_cidr` flag unset, so it is probably INET type.
+    InetFlag,
+    /// Buffer length does not match length of encoded network.
+    BadLength {
+        /// Expected length of buffer in bytes.
+        expected: usize,
+        /// Actual length of buffer in bytes.
+        actual: usize,
+    },
+    /// Address length byte does not match address family.
+    BadAddressLength(u8),
+    /// Network prefix is bigger than possible for address family.
+    InvalidPrefix(u8),
+    /// Host bits are set in network address.
+    HostBitsSet,
+}
+
+impl Error for CidrDecodeError {}
 
-    if raw[2] != 1 {
-        return Err("This field is not CIDR type, probably INET type".into());
+impl fmt::Display for CidrDecodeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CidrDecodeError::WrongFamily(family) => {
+                write!(
+                    fmt,
+                    "Network value has unexpected address family '{}'",
+                    family
+                )
+            }
+            CidrDecodeError::InetFlag => {
+                write!(fmt, "This field is not CIDR type, probably INET type")
+            }
+            CidrDecodeError::BadLength { expected, actual } => write!(
+                fmt,
+                "Network value has bad length '{}', expected '{}'",
+                actual, expected
+            ),
+            CidrDecodeError::BadAddressLength(length) => {
+                write!(fmt, "Network value has bad address length '{}'", length)
+            }
+            CidrDecodeError::InvalidPrefix(prefix) => {
+                write!(fmt, "Network value has invalid prefix '{}'", prefix)
+            }
+            CidrDecodeError::HostBitsSet => write!(fmt, "Network value has host bits set"),
+        }
     }
+}
 
-    if raw[3] != Ipv4Network::LENGTH / 8 {
-        return Err(format!("CIDR is IP version 4, but have bad length '{}'", raw[3]).into());
+impl From<IpNetworkError> for CidrDecodeError {
+    fn from(error: IpNetworkError) -> Self {
+        match error {
+            IpNetworkError::NetmaskError { netmask, .. } => CidrDecodeError::InvalidPrefix(netmask),
+            IpNetworkError::HostBitsSet { .. } => CidrDecodeError::HostBitsSet,
+        }
     }
+}
 
-    let network_address = Ipv4Addr::new(raw[4], raw[5], raw[6], raw[7]);
-    let netmask = raw[1];
-    Ok(Ipv4Network::new(network_address, netmask)?)
+/// PostgreSQL network address type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SqlType {
+    Cidr,
+    Inet,
 }
 
-#[inline]
-pub fn from_sql_ipv6_network(raw: &[u8]) -> Result<Ipv6Network, Box<dyn Error + Sync + Send>> {
-    assert!(raw.len() >= 20);
+/// Wrapper for decoding PostgreSQL INET values into networks with truncating host bits, in the same
+/// way as [`Ipv4Network::new_truncate`] does. Without this wrapper, decoding INET value with host
+/// bits set into network returns error.
+///
+/// [`Ipv4Network::new_truncate`]: struct.Ipv4Network.html#method.new_truncate
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct Truncated<N>(pub N);
 
-    if raw[0] != IPV6_TYPE {
-        return Err("CIDR is not IP version 6".into());
+impl<N> Truncated<N> {
+    /// Returns wrapped network.
+    #[inline]
+    pub fn into_inner(self) -> N {
+        self.0
+    }
+}
+
+/// Checks header of CIDR or INET value and returns netmask, `is_cidr` flag and address octets.
+fn decode_header(
+    raw: &[u8],
+    family: u8,
+    address_length: u8,
+) -> Result<(u8, bool, &[u8]), CidrDecodeError> {
+    let expected = HEADER_LENGTH + address_length as usize;
+    if raw.len() < HEADER_LENGTH {
+        return Err(CidrDecodeError::BadLength {
+            expected,
+            actual: raw.len(),
+        });
     }
 
-    if raw[2] != 1 {
-        return Err("This field is not CIDR type, probably INET type".into());
+    if raw[0] != family {
+        return Err(CidrDecodeError::WrongFamily(raw[0]));
     }
 
-    if raw[3] != Ipv6Network::LENGTH / 8 {
-        return Err(format!("CIDR is IP version 6, but have bad length '{}'", raw[3]).into());
+    if raw[3] != address_length {
+        return Err(CidrDecodeError::BadAddressLength(raw[3]));
+    }
+
+    if raw.len() != expected {
+        return Err(CidrDecodeError::BadLength {
+            expected,
+            actual: raw.len(),
+        });
+    }
+
+    Ok((raw[1], raw[2] == 1, &raw[HEADER_LENGTH..]))
+}
+
+fn decode_ipv4(raw: &[u8], ty: SqlType) -> Result<(Ipv4Addr, u8), CidrDecodeError> {
+    let (netmask, is_cidr, address) = decode_header(raw, IPV4_TYPE, Ipv4Network::LENGTH / 8)?;
+    if ty == SqlType::Cidr && !is_cidr {
+        return Err(CidrDecodeError::InetFlag);
+    }
+
+    let address = Ipv4Addr::new(address[0], address[1], address[2], address[3]);
+    Ok((address, netmask))
+}
+
+fn decode_ipv6(raw: &[u8], ty: SqlType) -> Result<(Ipv6Addr, u8), CidrDecodeError> {
+    let (netmask, is_cidr, address) = decode_header(raw, IPV6_TYPE, Ipv6Network::LENGTH / 8)?;
+    if ty == SqlType::Cidr && !is_cidr {
+        return Err(CidrDecodeError::InetFlag);
     }
 
     let mut octets = [0; 16];
-    octets.copy_from_slice(&raw[4..]);
-    let network_address = Ipv6Addr::from(octets);
+    octets.copy_from_slice(address);
+    Ok((Ipv6Addr::from(octets), netmask))
+}
 
-    let netmask = raw[1];
-    Ok(Ipv6Network::new(network_address, netmask)?)
+fn family(raw: &[u8]) -> Result<u8, CidrDecodeError> {
+    match raw.first() {
+        Some(&family) if family == IPV4_TYPE || family == IPV6_TYPE => Ok(family),
+        Some(&family) => Err(CidrDecodeError::WrongFamily(family)),
+        None => Err(CidrDecodeError::BadLength {
+            expected: HEADER_LENGTH,
+            actual: 0,
+        }),
+    }
 }
 
 #[inline]
-pub fn to_sql_ipv4_network(network: &Ipv4Network) -> [u8; 8] {
-    let ip_octets = network.network_address().octets();
+pub fn from_sql_ipv4_network(
+    raw: &[u8],
+    ty: SqlType,
+    truncate: bool,
+) -> Result<Ipv4Network, CidrDecodeError> {
+    let (address, netmask) = decode_ipv4(raw, ty)?;
+    if truncate {
+        Ok(Ipv4Network::new_truncate(address, netmask)?)
+    } else {
+        Ok(Ipv4Network::new(address, netmask)?)
+    }
+}
+
+#[inline]
+pub fn from_sql_ipv6_network(
+    raw: &[u8],
+    ty: SqlType,
+    truncate: bool,
+) -> Result<Ipv6Network, CidrDecodeError> {
+    let (address, netmask) = decode_ipv6(raw, ty)?;
+    if truncate {
+        Ok(Ipv6Network::new_truncate(address, netmask)?)
+    } else {
+        Ok(Ipv6Network::new(address, netmask)?)
+    }
+}
+
+#[inline]
+pub fn from_sql_ip_network(
+    raw: &[u8],
+    ty: SqlType,
+    truncate: bool,
+) -> Result<IpNetwork, CidrDecodeError> {
+    if family(raw)? == IPV4_TYPE {
+        Ok(IpNetwork::V4(from_sql_ipv4_network(raw, ty, truncate)?))
+    } else {
+        Ok(IpNetwork::V6(from_sql_ipv6_network(raw, ty, truncate)?))
+    }
+}
+
+#[inline]
+pub fn from_sql_ipv4_interface(raw: &[u8]) -> Result<Ipv4Interface, CidrDecodeError> {
+    let (address, netmask) = decode_ipv4(raw, SqlType::Inet)?;
+    Ok(Ipv4Interface::new(address, netmask)?)
+}
+
+#[inline]
+pub fn from_sql_ipv6_interface(raw: &[u8]) -> Result<Ipv6Interface, CidrDecodeError> {
+    let (address, netmask) = decode_ipv6(raw, SqlType::Inet)?;
+    Ok(Ipv6Interface::new(address, netmask)?)
+}
+
+#[inline]
+pub fn from_sql_ip_interface(raw: &[u8]) -> Result<IpInterface, CidrDecodeError> {
+    if family(raw)? == IPV4_TYPE {
+        Ok(IpInterface::V4(from_sql_ipv4_interface(raw)?))
+    } else {
+        Ok(IpInterface::V6(from_sql_ipv6_interface(raw)?))
+    }
+}
+
+fn encode_ipv4(address: Ipv4Addr, netmask: u8, ty: SqlType) -> [u8; 8] {
     let mut bytes = [0; 8];
     bytes[0] = IPV4_TYPE;
-    bytes[1] = network.netmask();
-    bytes[2] = 1;
+    bytes[1] = netmask;
+    bytes[2] = (ty == SqlType::Cidr) as u8;
     bytes[3] = Ipv4Network::LENGTH / 8;
-    bytes[4..].copy_from_slice(&ip_octets);
+    bytes[4..].copy_from_slice(&address.octets());
     bytes
 }
 
-#[inline]
-pub fn to_sql_ipv6_network(network: &Ipv6Network) -> [u8; 20] {
-    let ip_octets = network.network_address().octets();
+fn encode_ipv6(address: Ipv6Addr, netmask: u8, ty: SqlType) -> [u8; 20] {
     let mut bytes = [0; 20];
     bytes[0] = IPV6_TYPE;
-    bytes[1] = network.netmask();
-    bytes[2] = 1;
+    bytes[1] = netmask;
+    bytes[2] = (ty == SqlType::Cidr) as u8;
     bytes[3] = Ipv6Network::LENGTH / 8;
-    bytes[4..].copy_from_slice(&ip_octets);
+    bytes[4..].copy_from_slice(&address.octets());
     bytes
 }
+
+#[inline]
+pub fn to_sql_ipv4_network(network: &Ipv4Network, ty: SqlType) -> [u8; 8] {
+    encode_ipv4(network.network_address(), network.netmask(), ty)
+}
+
+#[inline]
+pub fn to_sql_ipv6_network(network: &Ipv6Network, ty: SqlType) -> [u8; 20] {
+    encode_ipv6(network.network_address(), network.netmask(), ty)
+}
+
+#[inline]
+pub fn to_sql_ipv4_interface(interface: &Ipv4Interface) -> [u8; 8] {
+    encode_ipv4(interface.address(), interface.netmask(), SqlType::Inet)
+}
+
+#[inline]
+pub fn to_sql_ipv6_interface(interface: &Ipv6Interface) -> [u8; 20] {
+    encode_ipv6(interface.address(), interface.netmask(), SqlType::Inet)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use crate::{IpInterface, Ipv4Interface, Ipv4Network, Ipv6Network};
+    use super::{CidrDecodeError, SqlType, from_sql_ip_interface, to_sql_ipv4_interface};
+    use super::{to_sql_ipv4_network, to_sql_ipv6_network};
+
+    fn from_sql_ipv4_network(raw: &[u8]) -> Result<Ipv4Network, CidrDecodeError> {
+        super::from_sql_ipv4_network(raw, SqlType::Cidr, false)
+    }
+
+    fn from_sql_ipv6_network(raw: &[u8]) -> Result<Ipv6Network, CidrDecodeError> {
+        super::from_sql_ipv6_network(raw, SqlType::Cidr, false)
+    }
+
+    fn from_sql_ip_network(raw: &[u8]) -> Result<crate::IpNetwork, CidrDecodeError> {
+        super::from_sql_ip_network(raw, SqlType::Cidr, false)
+    }
+
+    fn ipv4_bytes() -> Vec<u8> {
+        let network = Ipv4Network::new(Ipv4Addr::new(192, 168, 0, 0), 16).unwrap();
+        to_sql_ipv4_network(&network, SqlType::Cidr).to_vec()
+    }
+
+    fn ipv6_bytes() -> Vec<u8> {
+        let network = Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32).unwrap();
+        to_sql_ipv6_network(&network, SqlType::Cidr).to_vec()
+    }
+
+    /// Builds corpus of malformed buffers from valid ones: every truncation, every extension
+    /// by one byte and every single byte mutation.
+    fn malformed_corpus() -> Vec<Vec<u8>> {
+        let mut corpus = vec![vec![]];
+        for valid in [ipv4_bytes(), ipv6_bytes()].iter() {
+            for length in 0..valid.len() {
+                corpus.push(valid[..length].to_vec());
+            }
+
+            let mut extended = valid.clone();
+            extended.push(0);
+            corpus.push(extended);
+
+            for position in 0..valid.len() {
+                for value in 0..=255 {
+                    let mut mutated = valid.clone();
+                    mutated[position] = value;
+                    corpus.push(mutated);
+                }
+            }
+        }
+
+        // Deterministic pseudo random buffers
+        let mut state: u32 = 0x1234_5678;
+        for length in 0..64 {
+            let buffer = (0..length)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    state as u8
+                })
+                .collect();
+            corpus.push(buffer);
+        }
+
+        corpus
+    }
+
+    #[test]
+    fn malformed_corpus_does_not_panic() {
+        for buffer in malformed_corpus() {
+            let _ = from_sql_ipv4_network(&buffer);
+            let _ = from_sql_ipv6_network(&buffer);
+            let _ = from_sql_ip_network(&buffer);
+            let _ = from_sql_ip_interface(&buffer);
+            let _ = super::from_sql_ip_network(&buffer, SqlType::Inet, true);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(
+            Err(CidrDecodeError::BadLength {
+                expected: 8,
+                actual: 0
+            }),
+            from_sql_ipv4_network(&[])
+        );
+        assert_eq!(
+            Err(CidrDecodeError::BadLength {
+                expected: 4,
+                actual: 0
+            }),
+            from_sql_ip_network(&[])
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = ipv4_bytes();
+        assert_eq!(
+            Err(CidrDecodeError::BadLength {
+                expected: 8,
+                actual: 7
+            }),
+            from_sql_ipv4_network(&bytes[..7])
+        );
+
+        let bytes = ipv6_bytes();
+        assert_eq!(
+            Err(CidrDecodeError::BadLength {
+                expected: 20,
+                actual: 19
+            }),
+            from_sql_ip_network(&bytes[..19])
+        );
+    }
+
+    #[test]
+    fn wrong_family() {
+        assert_eq!(
+            Err(CidrDecodeError::WrongFamily(3)),
+            from_sql_ipv4_network(&ipv6_bytes())
+        );
+
+        let mut bytes = ipv4_bytes();
+        bytes[0] = 10;
+        assert_eq!(
+            Err(CidrDecodeError::WrongFamily(10)),
+            from_sql_ip_network(&bytes)
+        );
+    }
+
+    #[test]
+    fn inet_flag() {
+        let mut bytes = ipv4_bytes();
+        bytes[2] = 0;
+        assert_eq!(Err(CidrDecodeError::InetFlag), from_sql_ip_network(&bytes));
+    }
+
+    #[test]
+    fn bad_address_length() {
+        let mut bytes = ipv6_bytes();
+        bytes[3] = 4;
+        assert_eq!(
+            Err(CidrDecodeError::BadAddressLength(4)),
+            from_sql_ip_network(&bytes)
+        );
+    }
+
+    #[test]
+    fn invalid_prefix() {
+        let mut bytes = ipv4_bytes();
+        bytes[1] = 33;
+        assert_eq!(
+            Err(CidrDecodeError::InvalidPrefix(33)),
+            from_sql_ip_network(&bytes)
+        );
+    }
+
+    #[test]
+    fn host_bits_set() {
+        let mut bytes = ipv6_bytes();
+        bytes[19] = 1;
+        assert_eq!(
+            Err(CidrDecodeError::HostBitsSet),
+            from_sql_ip_network(&bytes)
+        );
+    }
+
+    #[test]
+    fn inet_network() {
+        let interface = Ipv4Interface::new(Ipv4Addr::new(192, 168, 1, 10), 24).unwrap();
+        let bytes = to_sql_ipv4_interface(&interface);
+        assert_eq!(0, bytes[2]);
+
+        assert_eq!(
+            Ok(IpInterface::V4(interface)),
+            from_sql_ip_interface(&bytes)
+        );
+        assert_eq!(Err(CidrDecodeError::InetFlag), from_sql_ip_network(&bytes));
+        assert_eq!(
+            Err(CidrDecodeError::HostBitsSet),
+            super::from_sql_ip_network(&bytes, SqlType::Inet, false)
+        );
+        assert_eq!(
+            Ok(interface.network().into()),
+            super::from_sql_ip_network(&bytes, SqlType::Inet, true)
+        );
+    }
+
+    #[test]
+    fn inet_encode_network() {
+        let network = Ipv4Network::new(Ipv4Addr::new(192, 168, 1, 0), 24).unwrap();
+        let bytes = to_sql_ipv4_network(&network, SqlType::Inet);
+        assert_eq!(0, bytes[2]);
+        assert_eq!(
+            Ok(network),
+            super::from_sql_ipv4_network(&bytes, SqlType::Inet, false)
+        );
+    }
+}
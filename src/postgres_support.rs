@@ -1,60 +1,127 @@
 use std::error::Error;
-use postgres::types::{FromSql, IsNull, ToSql, Type, accepts, to_sql_checked};
-use crate::{IpNetwork, Ipv4Network, Ipv6Network};
-use crate::postgres_common;
-use postgres::types::private::BytesMut;
+use postgres_types::{FromSql, IsNull, ToSql, Type, accepts, to_sql_checked};
+use crate::{IpInterface, IpNetwork, Ipv4Interface, Ipv4Network, Ipv6Interface, Ipv6Network};
+use crate::postgres_common::{self, SqlType, Truncated};
+use postgres_types::private::BytesMut;
 
 type PostgresResult<T> = Result<T, Box<dyn Error + Sync + Send>>;
 
+fn sql_type(ty: &Type) -> SqlType {
+    if *ty == Type::INET {
+        SqlType::Inet
+    } else {
+        SqlType::Cidr
+    }
+}
+
 impl<'a> FromSql<'a> for Ipv4Network {
-    fn from_sql(_: &Type, raw: &'a [u8]) -> PostgresResult<Ipv4Network> {
-        postgres_common::from_sql_ipv4_network(raw)
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> PostgresResult<Ipv4Network> {
+        Ok(postgres_common::from_sql_ipv4_network(
+            raw,
+            sql_type(ty),
+            false,
+        )?)
     }
 
-    accepts!(CIDR);
+    accepts!(CIDR, INET);
 }
 
 impl<'a> FromSql<'a> for Ipv6Network {
-    fn from_sql(_: &Type, raw: &'a [u8]) -> PostgresResult<Ipv6Network> {
-        postgres_common::from_sql_ipv6_network(raw)
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> PostgresResult<Ipv6Network> {
+        Ok(postgres_common::from_sql_ipv6_network(
+            raw,
+            sql_type(ty),
+            false,
+        )?)
     }
 
-    accepts!(CIDR);
+    accepts!(CIDR, INET);
 }
 
 impl<'a> FromSql<'a> for IpNetwork {
-    fn from_sql(t: &Type, raw: &'a [u8]) -> PostgresResult<IpNetwork> {
-        match raw[0] {
-            postgres_common::IPV4_TYPE => Ok(IpNetwork::V4(Ipv4Network::from_sql(t, raw)?)),
-            postgres_common::IPV6_TYPE => Ok(IpNetwork::V6(Ipv6Network::from_sql(t, raw)?)),
-            _ => Err("CIDR is not IP version 4 or 6".into()),
-        }
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> PostgresResult<IpNetwork> {
+        Ok(postgres_common::from_sql_ip_network(
+            raw,
+            sql_type(ty),
+            false,
+        )?)
+    }
+
+    accepts!(CIDR, INET);
+}
+
+impl<'a> FromSql<'a> for Truncated<Ipv4Network> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> PostgresResult<Self> {
+        let network = postgres_common::from_sql_ipv4_network(raw, sql_type(ty), true)?;
+        Ok(Truncated(network))
+    }
+
+    accepts!(CIDR, INET);
+}
+
+impl<'a> FromSql<'a> for Truncated<Ipv6Network> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> PostgresResult<Self> {
+        let network = postgres_common::from_sql_ipv6_network(raw, sql_type(ty), true)?;
+        Ok(Truncated(network))
+    }
+
+    accepts!(CIDR, INET);
+}
+
+impl<'a> FromSql<'a> for Truncated<IpNetwork> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> PostgresResult<Self> {
+        let network = postgres_common::from_sql_ip_network(raw, sql_type(ty), true)?;
+        Ok(Truncated(network))
+    }
+
+    accepts!(CIDR, INET);
+}
+
+impl<'a> FromSql<'a> for Ipv4Interface {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> PostgresResult<Ipv4Interface> {
+        Ok(postgres_common::from_sql_ipv4_interface(raw)?)
+    }
+
+    accepts!(CIDR, INET);
+}
+
+impl<'a> FromSql<'a> for Ipv6Interface {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> PostgresResult<Ipv6Interface> {
+        Ok(postgres_common::from_sql_ipv6_interface(raw)?)
+    }
+
+    accepts!(CIDR, INET);
+}
+
+impl<'a> FromSql<'a> for IpInterface {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> PostgresResult<IpInterface> {
+        Ok(postgres_common::from_sql_ip_interface(raw)?)
     }
 
-    accepts!(CIDR);
+    accepts!(CIDR, INET);
 }
 
 impl ToSql for Ipv4Network {
-    fn to_sql(&self, _ty: &Type, w: &mut BytesMut) -> PostgresResult<IsNull> {
-        let bytes = postgres_common::to_sql_ipv4_network(self);
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> PostgresResult<IsNull> {
+        let bytes = postgres_common::to_sql_ipv4_network(self, sql_type(ty));
         w.extend_from_slice(&bytes);
 
         Ok(IsNull::No)
     }
 
-    accepts!(CIDR);
+    accepts!(CIDR, INET);
     to_sql_checked!();
 }
 
 impl ToSql for Ipv6Network {
-    fn to_sql(&self, _ty: &Type, w: &mut BytesMut) -> PostgresResult<IsNull> {
-        let bytes = postgres_common::to_sql_ipv6_network(self);
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> PostgresResult<IsNull> {
+        let bytes = postgres_common::to_sql_ipv6_network(self, sql_type(ty));
         w.extend_from_slice(&bytes);
 
         Ok(IsNull::No)
     }
 
-    accepts!(CIDR);
+    accepts!(CIDR, INET);
     to_sql_checked!();
 }
 
@@ -66,17 +133,53 @@ impl ToSql for IpNetwork {
         }
     }
 
-    accepts!(CIDR);
+    accepts!(CIDR, INET);
+    to_sql_checked!();
+}
+
+impl ToSql for Ipv4Interface {
+    fn to_sql(&self, _ty: &Type, w: &mut BytesMut) -> PostgresResult<IsNull> {
+        let bytes = postgres_common::to_sql_ipv4_interface(self);
+        w.extend_from_slice(&bytes);
+
+        Ok(IsNull::No)
+    }
+
+    accepts!(INET);
+    to_sql_checked!();
+}
+
+impl ToSql for Ipv6Interface {
+    fn to_sql(&self, _ty: &Type, w: &mut BytesMut) -> PostgresResult<IsNull> {
+        let bytes = postgres_common::to_sql_ipv6_interface(self);
+        w.extend_from_slice(&bytes);
+
+        Ok(IsNull::No)
+    }
+
+    accepts!(INET);
+    to_sql_checked!();
+}
+
+impl ToSql for IpInterface {
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> PostgresResult<IsNull> {
+        match *self {
+            IpInterface::V4(ref interface) => interface.to_sql(ty, w),
+            IpInterface::V6(ref interface) => interface.to_sql(ty, w),
+        }
+    }
+
+    accepts!(INET);
     to_sql_checked!();
 }
 
 #[cfg(test)]
 mod tests {
     use std::net::{Ipv4Addr, Ipv6Addr};
-    use postgres::types::{FromSql, ToSql};
-    use postgres::types::Type;
-    use crate::{IpNetwork, Ipv4Network, Ipv6Network};
-    use postgres::types::private::BytesMut;
+    use postgres_types::{FromSql, ToSql};
+    use postgres_types::Type;
+    use crate::{CidrDecodeError, IpInterface, IpNetwork, Ipv4Network, Ipv6Network, Truncated};
+    use postgres_types::private::BytesMut;
 
     fn return_test_ipv4_network() -> Ipv4Network {
         Ipv4Network::new(Ipv4Addr::new(192, 168, 0, 0), 16).unwrap()
@@ -147,6 +250,23 @@ mod tests {
         assert_eq!(ip_network, ip_network_converted);
     }
 
+    #[test]
+    fn ipnetwork_from_sql_truncated() {
+        let ip_network = IpNetwork::V4(return_test_ipv4_network());
+        let mut output = BytesMut::new();
+        ip_network.to_sql(&Type::CIDR, &mut output).unwrap();
+
+        let error = IpNetwork::from_sql(&Type::CIDR, &output[..6]).unwrap_err();
+        assert_eq!(
+            Some(&CidrDecodeError::BadLength {
+                expected: 8,
+                actual: 6
+            }),
+            error.downcast_ref::<CidrDecodeError>()
+        );
+        assert!(IpNetwork::from_sql(&Type::CIDR, &[]).is_err());
+    }
+
     #[test]
     fn ipnetwork_to_sql_v4() {
         let ip_network = IpNetwork::V4(return_test_ipv4_network());
@@ -160,4 +280,74 @@ mod tests {
         let mut output = BytesMut::new();
         assert!(ip_network.to_sql(&Type::CIDR, &mut output).is_ok());
     }
+
+    #[test]
+    fn inet_interface_both_direction() {
+        let interface: IpInterface = "192.168.1.10/24".parse().unwrap();
+        let mut output = BytesMut::new();
+        interface.to_sql(&Type::INET, &mut output).unwrap();
+        assert_eq!(0, output[2]);
+
+        let converted = IpInterface::from_sql(&Type::INET, &output).unwrap();
+        assert_eq!(interface, converted);
+    }
+
+    #[test]
+    fn inet_into_network() {
+        let interface: IpInterface = "192.168.1.10/24".parse().unwrap();
+        let mut output = BytesMut::new();
+        interface.to_sql(&Type::INET, &mut output).unwrap();
+
+        assert!(IpNetwork::from_sql(&Type::INET, &output).is_err());
+        let Truncated(network) = Truncated::<IpNetwork>::from_sql(&Type::INET, &output).unwrap();
+        assert_eq!(interface.network(), network);
+    }
+
+    #[test]
+    fn network_to_inet() {
+        let ip_network = IpNetwork::V6(return_test_ipv6_network());
+        let mut output = BytesMut::new();
+        ip_network.to_sql(&Type::INET, &mut output).unwrap();
+        assert_eq!(0, output[2]);
+        assert_eq!(
+            ip_network,
+            IpNetwork::from_sql(&Type::INET, &output).unwrap()
+        );
+        assert!(IpNetwork::from_sql(&Type::CIDR, &output).is_err());
+    }
+
+    #[test]
+    fn accepts_inet() {
+        assert!(<IpNetwork as ToSql>::accepts(&Type::INET));
+        assert!(<IpInterface as ToSql>::accepts(&Type::INET));
+        assert!(!<IpInterface as ToSql>::accepts(&Type::CIDR));
+        assert!(<Truncated<IpNetwork> as FromSql>::accepts(&Type::INET));
+    }
+
+    #[test]
+    fn array_both_direction() {
+        let networks = vec![
+            IpNetwork::V4(return_test_ipv4_network()),
+            IpNetwork::V6(return_test_ipv6_network()),
+        ];
+        let mut output = BytesMut::new();
+        networks.to_sql(&Type::CIDR_ARRAY, &mut output).unwrap();
+
+        let converted = Vec::<IpNetwork>::from_sql(&Type::CIDR_ARRAY, &output).unwrap();
+        assert_eq!(networks, converted);
+        assert!(<Vec<Ipv4Network> as FromSql>::accepts(&Type::INET_ARRAY));
+        assert!(<&[IpInterface] as ToSql>::accepts(&Type::INET_ARRAY));
+    }
+
+    #[test]
+    fn option_null() {
+        let converted = Option::<IpNetwork>::from_sql_null(&Type::CIDR).unwrap();
+        assert_eq!(None, converted);
+
+        let mut output = BytesMut::new();
+        let ip_network = Some(return_test_ipv4_network());
+        ip_network.to_sql(&Type::CIDR, &mut output).unwrap();
+        let converted = Option::<Ipv4Network>::from_sql_nullable(&Type::CIDR, Some(&output));
+        assert_eq!(ip_network, converted.unwrap());
+    }
 }
@@ -0,0 +1,603 @@
+//! Ordered prefix lists with permit and deny entries, like prefix lists in routers.
+//!
+//! Entries are evaluated by sequence number and the first entry matching network decides. When no
+//! entry matches, network is denied. Prefix lists can be parsed from Cisco `ip prefix-list` and
+//! `ipv6 prefix-list` commands by [`parse_cisco`] or from Juniper `route-filter` statements by
+//! [`parse_juniper`].
+//!
+//! # Examples
+//!
+//! ```
+//! use ip_network::IpNetwork;
+//! use ip_network::prefix_list::{parse_cisco, Action};
+//!
+//! let lists = parse_cisco("\
+//! ip prefix-list CUSTOMER seq 5 deny 10.0.0.0/8 le 32
+//! ip prefix-list CUSTOMER seq 10 permit 192.0.2.0/24 ge 25 le 26
+//! ")?;
+//! let list = &lists[0];
+//! assert_eq!("CUSTOMER", list.name());
+//!
+//! let evaluation = list.evaluate(&"192.0.2.128/25".parse::<IpNetwork>()?);
+//! assert_eq!(Action::Permit, evaluation.action);
+//! assert_eq!(10, evaluation.entry.unwrap().seq);
+//!
+//! let evaluation = list.evaluate(&"192.0.2.0/24".parse::<IpNetwork>()?);
+//! assert_eq!(Action::Deny, evaluation.action);
+//! assert_eq!("denied by implicit deny", evaluation.to_string());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use crate::IpNetwork;
+use crate::prefix_range::{PrefixRange, PrefixRangeError};
+
+/// Action of prefix list entry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Action {
+    /// Network is permitted.
+    Permit,
+    /// Network is denied.
+    Deny,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Action::Permit => write!(fmt, "permit"),
+            Action::Deny => write!(fmt, "deny"),
+        }
+    }
+}
+
+/// Entry of prefix list.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct PrefixListEntry {
+    /// Sequence number, entries are evaluated from the lowest.
+    pub seq: u32,
+    /// Action when entry matches network.
+    pub action: Action,
+    /// Networks matched by entry.
+    pub range: PrefixRange,
+}
+
+impl PrefixListEntry {
+    /// Creates entry from network with Cisco style `ge` and `le` values, see
+    /// [`PrefixRange::from_ge_le`].
+    pub fn new(
+        seq: u32,
+        action: Action,
+        network: IpNetwork,
+        ge: Option<u8>,
+        le: Option<u8>,
+    ) -> Result<Self, PrefixRangeError> {
+        Ok(Self {
+            seq,
+            action,
+            range: PrefixRange::from_ge_le(network, ge, le)?,
+        })
+    }
+
+    /// Returns `true` if entry matches `network`.
+    pub fn matches(&self, network: &IpNetwork) -> bool {
+        self.range.matches(network)
+    }
+}
+
+impl fmt::Display for PrefixListEntry {
+    /// Converts entry to Cisco format, for example `seq 5 permit 10.0.0.0/8 le 24`.
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "seq {} {} {}",
+            self.seq,
+            self.action,
+            self.range.to_ge_le()
+        )
+    }
+}
+
+/// Result of prefix list evaluation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Evaluation<'a> {
+    /// Resulting action.
+    pub action: Action,
+    /// Matched entry or `None`, when network was denied by implicit deny.
+    pub entry: Option<&'a PrefixListEntry>,
+}
+
+impl fmt::Display for Evaluation<'_> {
+    /// Explains result, for example `permitted by seq 5 permit 10.0.0.0/8 le 24`.
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let result = match self.action {
+            Action::Permit => "permitted",
+            Action::Deny => "denied",
+        };
+        match self.entry {
+            Some(entry) => write!(fmt, "{} by {}", result, entry),
+            None => write!(fmt, "{} by implicit deny", result),
+        }
+    }
+}
+
+/// Named list of entries ordered by sequence number.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PrefixList {
+    name: String,
+    entries: Vec<PrefixListEntry>,
+}
+
+impl PrefixList {
+    /// Creates empty prefix list, that denies everything.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            entries: vec![],
+        }
+    }
+
+    /// Returns name of list.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns entries ordered by sequence number.
+    pub fn entries(&self) -> &[PrefixListEntry] {
+        &self.entries
+    }
+
+    /// Inserts entry. If list already contains entry with the same sequence number, it is replaced
+    /// and returned.
+    pub fn insert(&mut self, entry: PrefixListEntry) -> Option<PrefixListEntry> {
+        match self.entries.binary_search_by_key(&entry.seq, |e| e.seq) {
+            Ok(index) => Some(std::mem::replace(&mut self.entries[index], entry)),
+            Err(index) => {
+                self.entries.insert(index, entry);
+                None
+            }
+        }
+    }
+
+    /// Removes entry with given sequence number.
+    pub fn remove(&mut self, seq: u32) -> Option<PrefixListEntry> {
+        let index = self.entries.binary_search_by_key(&seq, |e| e.seq).ok()?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Returns action of the first entry matching `network` or deny, when no entry matches.
+    pub fn evaluate(&self, network: &IpNetwork) -> Evaluation<'_> {
+        match self.entries.iter().find(|entry| entry.matches(network)) {
+            Some(entry) => Evaluation {
+                action: entry.action,
+                entry: Some(entry),
+            },
+            None => Evaluation {
+                action: Action::Deny,
+                entry: None,
+            },
+        }
+    }
+
+    /// Returns `true` if list permits `network`.
+    pub fn permits(&self, network: &IpNetwork) -> bool {
+        self.evaluate(network).action == Action::Permit
+    }
+}
+
+/// Parses Cisco `ip prefix-list` and `ipv6 prefix-list` commands, one command per line, and
+/// returns lists in order of first occurrence. Empty lines, lines starting with `!` and
+/// `description` commands are ignored. Entries without sequence number get number by 5 bigger
+/// than the last entry of list.
+pub fn parse_cisco(input: &str) -> Result<Vec<PrefixList>, PrefixListParseError> {
+    let mut lists: Vec<PrefixList> = vec![];
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let syntax = |reason| PrefixListParseError::Syntax {
+            line: line_number,
+            reason,
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('!') {
+            continue;
+        }
+
+        let mut tokens = line.split_whitespace().peekable();
+        let ipv6 = match tokens.next() {
+            Some("ip") => false,
+            Some("ipv6") => true,
+            _ => return Err(syntax("expected `ip prefix-list` or `ipv6 prefix-list`")),
+        };
+        if tokens.next() != Some("prefix-list") {
+            return Err(syntax("expected `ip prefix-list` or `ipv6 prefix-list`"));
+        }
+        let name = tokens.next().ok_or_else(|| syntax("missing list name"))?;
+        let position = match lists.iter().position(|list| list.name == name) {
+            Some(position) => position,
+            None => {
+                lists.push(PrefixList::new(name));
+                lists.len() - 1
+            }
+        };
+        let list = &mut lists[position];
+        if tokens.peek() == Some(&"description") {
+            continue;
+        }
+
+        let seq = if tokens.peek() == Some(&"seq") {
+            tokens.next();
+            let seq = tokens
+                .next()
+                .ok_or_else(|| syntax("missing sequence number"))?;
+            seq.parse().map_err(|_| syntax("invalid sequence number"))?
+        } else {
+            list.entries
+                .last()
+                .map_or(5, |entry| entry.seq.saturating_add(5))
+        };
+        let action = match tokens.next() {
+            Some("permit") => Action::Permit,
+            Some("deny") => Action::Deny,
+            _ => return Err(syntax("expected `permit` or `deny`")),
+        };
+        let network: IpNetwork = tokens
+            .next()
+            .ok_or_else(|| syntax("missing network"))?
+            .parse()
+            .map_err(|error| PrefixListParseError::Range {
+                line: line_number,
+                error: PrefixRangeError::InvalidNetwork(error),
+            })?;
+        if network.is_ipv6() != ipv6 {
+            return Err(syntax("network family doesn't match command"));
+        }
+
+        let (mut ge, mut le) = (None, None);
+        while let Some(keyword) = tokens.next() {
+            let value = match keyword {
+                "ge" => &mut ge,
+                "le" => &mut le,
+                _ => return Err(syntax("expected `ge` or `le`")),
+            };
+            if value.is_some() {
+                return Err(syntax("duplicate `ge` or `le`"));
+            }
+            let length = tokens
+                .next()
+                .ok_or_else(|| syntax("missing prefix length"))?;
+            *value = Some(
+                length
+                    .parse()
+                    .map_err(|_| syntax("invalid prefix length"))?,
+            );
+        }
+
+        let entry = PrefixListEntry::new(seq, action, network, ge, le).map_err(|error| {
+            PrefixListParseError::Range {
+                line: line_number,
+                error,
+            }
+        })?;
+        list.insert(entry);
+    }
+    Ok(lists)
+}
+
+/// Parses Juniper `route-filter` statements, one statement per line, for example
+/// `route-filter 10.0.0.0/8 upto /24 reject;`. Supported match types are `exact`, `orlonger`,
+/// `longer`, `upto` and `prefix-length-range`. Action `accept` or `reject` is optional, statements
+/// without action are permitted like in term with `then accept`. Empty lines and lines starting
+/// with `#` are ignored.
+///
+/// Entries get sequence numbers by order in input and are evaluated in this order, longest match
+/// selection of route filters done by Junos is not emulated.
+pub fn parse_juniper<S: Into<String>>(
+    name: S,
+    input: &str,
+) -> Result<PrefixList, PrefixListParseError> {
+    let mut list = PrefixList::new(name);
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let syntax = |reason| PrefixListParseError::Syntax {
+            line: line_number,
+            reason,
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line
+            .strip_suffix(';')
+            .ok_or_else(|| syntax("missing `;`"))?;
+
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("route-filter") {
+            return Err(syntax("expected `route-filter`"));
+        }
+        let network: IpNetwork = tokens
+            .next()
+            .ok_or_else(|| syntax("missing network"))?
+            .parse()
+            .map_err(|error| PrefixListParseError::Range {
+                line: line_number,
+                error: PrefixRangeError::InvalidNetwork(error),
+            })?;
+        let netmask = network.netmask();
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let length = |token: Option<&str>| -> Result<u8, PrefixListParseError> {
+            token
+                .and_then(|token| token.strip_prefix('/'))
+                .and_then(|length| length.parse().ok())
+                .ok_or_else(|| syntax("invalid prefix length"))
+        };
+        let (min_length, max_length) = match tokens.next() {
+            Some("exact") => (netmask, netmask),
+            Some("orlonger") => (netmask, max),
+            Some("longer") => (netmask.saturating_add(1), max),
+            Some("upto") => (netmask, length(tokens.next())?),
+            Some("prefix-length-range") => {
+                let range = tokens
+                    .next()
+                    .ok_or_else(|| syntax("missing prefix length range"))?;
+                let dash = range
+                    .find('-')
+                    .ok_or_else(|| syntax("invalid prefix length range"))?;
+                (
+                    length(Some(&range[..dash]))?,
+                    length(Some(&range[dash + 1..]))?,
+                )
+            }
+            _ => return Err(syntax("unsupported match type")),
+        };
+        let action = match tokens.next() {
+            None | Some("accept") => Action::Permit,
+            Some("reject") => Action::Deny,
+            Some(_) => return Err(syntax("expected `accept` or `reject`")),
+        };
+        if tokens.next().is_some() {
+            return Err(syntax("unexpected token after action"));
+        }
+
+        let range = PrefixRange::new(network, min_length, max_length).map_err(|error| {
+            PrefixListParseError::Range {
+                line: line_number,
+                error,
+            }
+        })?;
+        let seq = list.entries.len() as u32 + 1;
+        list.insert(PrefixListEntry { seq, action, range });
+    }
+    Ok(list)
+}
+
+/// Errors when parsing prefix lists.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum PrefixListParseError {
+    /// Line has invalid syntax.
+    Syntax {
+        /// Line number starting from 1.
+        line: usize,
+        /// Description of error.
+        reason: &'static str,
+    },
+    /// Line contains invalid network or prefix lengths.
+    Range {
+        /// Line number starting from 1.
+        line: usize,
+        /// Error when creating prefix range.
+        error: PrefixRangeError,
+    },
+}
+
+impl PrefixListParseError {
+    /// Returns number of line with error, starting from 1.
+    pub fn line(&self) -> usize {
+        match *self {
+            PrefixListParseError::Syntax { line, .. }
+            | PrefixListParseError::Range { line, .. } => line,
+        }
+    }
+}
+
+impl Error for PrefixListParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            PrefixListParseError::Range { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PrefixListParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PrefixListParseError::Syntax { line, reason } => {
+                write!(fmt, "line {}: {}", line, reason)
+            }
+            PrefixListParseError::Range { line, ref error } => {
+                write!(fmt, "line {}: {}", line, error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use super::{parse_cisco, parse_juniper, Action, PrefixList, PrefixListEntry, PrefixListParseError};
+    use crate::IpNetwork;
+
+    #[test]
+    fn evaluate() {
+        let mut list = PrefixList::new("test");
+        list.insert(
+            PrefixListEntry::new(
+                20,
+                Action::Permit,
+                IpNetwork::from_str("10.0.0.0/8").unwrap(),
+                None,
+                Some(24),
+            )
+            .unwrap(),
+        );
+        list.insert(
+            PrefixListEntry::new(
+                10,
+                Action::Deny,
+                IpNetwork::from_str("10.1.0.0/16").unwrap(),
+                Some(24),
+                None,
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            vec![10, 20],
+            list.entries().iter().map(|e| e.seq).collect::<Vec<_>>()
+        );
+
+        let evaluation = list.evaluate(&IpNetwork::from_str("10.1.2.0/24").unwrap());
+        assert_eq!(Action::Deny, evaluation.action);
+        assert_eq!(
+            "denied by seq 10 deny 10.1.0.0/16 ge 24 le 32",
+            evaluation.to_string()
+        );
+        assert!(list.permits(&IpNetwork::from_str("10.1.0.0/16").unwrap()));
+        assert!(list.permits(&IpNetwork::from_str("10.0.0.0/8").unwrap()));
+        assert!(!list.permits(&IpNetwork::from_str("10.0.0.0/25").unwrap()));
+        assert!(!list.permits(&IpNetwork::from_str("11.0.0.0/8").unwrap()));
+        assert_eq!(
+            None,
+            list.evaluate(&IpNetwork::from_str("::/0").unwrap()).entry
+        );
+
+        let replaced = list.insert(
+            PrefixListEntry::new(
+                10,
+                Action::Permit,
+                IpNetwork::from_str("::/0").unwrap(),
+                None,
+                None,
+            )
+            .unwrap(),
+        );
+        assert_eq!(Action::Deny, replaced.unwrap().action);
+        assert!(list.permits(&IpNetwork::from_str("::/0").unwrap()));
+        assert!(list.remove(10).is_some());
+        assert!(list.remove(10).is_none());
+    }
+
+    #[test]
+    fn cisco() {
+        let input = "\
+! comment
+ip prefix-list A description test list
+ip prefix-list A seq 10 permit 10.0.0.0/8 ge 16 le 24
+ip prefix-list A permit 192.0.2.0/24
+ipv6 prefix-list B seq 5 deny 2001:db8::/32 le 64
+ip prefix-list A seq 1 deny 10.1.0.0/16 ge 20
+";
+        let lists = parse_cisco(input).unwrap();
+        assert_eq!(2, lists.len());
+        let entries: Vec<String> = lists[0].entries().iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            vec![
+                "seq 1 deny 10.1.0.0/16 ge 20 le 32",
+                "seq 10 permit 10.0.0.0/8 ge 16 le 24",
+                "seq 15 permit 192.0.2.0/24",
+            ],
+            entries
+        );
+        assert_eq!("B", lists[1].name());
+        assert!(!lists[1].permits(&IpNetwork::from_str("2001:db8::/48").unwrap()));
+    }
+
+    #[test]
+    fn cisco_invalid() {
+        let cases = [
+            "ip route 0.0.0.0 0.0.0.0 10.0.0.1",
+            "ip prefix-list A seq x permit 10.0.0.0/8",
+            "ip prefix-list A seq 5 allow 10.0.0.0/8",
+            "ip prefix-list A seq 5 permit 10.0.0.0/8 ge",
+            "ip prefix-list A seq 5 permit 10.0.0.0/8 ge 16 ge 17",
+            "ip prefix-list A seq 5 permit 10.0.0.0/8 foo 16",
+            "ip prefix-list A seq 5 permit 2001:db8::/32",
+        ];
+        for input in &cases {
+            assert!(
+                matches!(
+                    parse_cisco(input),
+                    Err(PrefixListParseError::Syntax { line: 1, .. })
+                ),
+                "{}",
+                input
+            );
+        }
+        for input in &[
+            "ip prefix-list A seq 5 permit 10.0.0.1/8",
+            "ip prefix-list A seq 5 permit 10.0.0.0/8 ge 24 le 16",
+            "ip prefix-list A seq 5 permit 10.0.0.0/8 le 33",
+            "ip prefix-list A seq 5 permit 10.0.0.0/8 ge 8",
+            "ip prefix-list A seq 5 permit 10.0.0.0/8 ge 8 le 16",
+            "ip prefix-list A seq 5 permit 10.0.0.0/8 le 8",
+            "ip prefix-list A seq 5 permit 10.0.0.0/8 ge 4",
+        ] {
+            assert!(
+                matches!(
+                    parse_cisco(input),
+                    Err(PrefixListParseError::Range { line: 1, .. })
+                ),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn juniper() {
+        let input = "\
+# bogons
+route-filter 10.0.0.0/8 orlonger reject;
+route-filter 192.0.2.0/24 exact;
+route-filter 198.51.100.0/24 longer accept;
+route-filter 203.0.113.0/24 upto /26;
+route-filter 2001:db8::/32 prefix-length-range /40-/48 accept;
+";
+        let list = parse_juniper("import", input).unwrap();
+        assert_eq!(5, list.entries().len());
+        assert!(!list.permits(&IpNetwork::from_str("10.0.0.0/8").unwrap()));
+        assert!(list.permits(&IpNetwork::from_str("192.0.2.0/24").unwrap()));
+        assert!(!list.permits(&IpNetwork::from_str("192.0.2.0/25").unwrap()));
+        assert!(!list.permits(&IpNetwork::from_str("198.51.100.0/24").unwrap()));
+        assert!(list.permits(&IpNetwork::from_str("198.51.100.0/32").unwrap()));
+        assert!(list.permits(&IpNetwork::from_str("203.0.113.64/26").unwrap()));
+        assert!(!list.permits(&IpNetwork::from_str("203.0.113.0/27").unwrap()));
+        assert!(list.permits(&IpNetwork::from_str("2001:db8:100::/40").unwrap()));
+        assert_eq!(
+            5,
+            list.evaluate(&IpNetwork::from_str("2001:db8::/48").unwrap())
+                .entry
+                .unwrap()
+                .seq
+        );
+
+        for input in &[
+            "route-filter 10.0.0.0/8 exact",
+            "route-filter 10.0.0.0/8 through 10.0.0.0/16;",
+            "route-filter 10.0.0.0/8 upto 24;",
+            "route-filter 10.0.0.0/8 exact discard;",
+            "prefix-list-filter A exact;",
+        ] {
+            assert!(
+                matches!(
+                    parse_juniper("a", input),
+                    Err(PrefixListParseError::Syntax { .. })
+                ),
+                "{}",
+                input
+            );
+        }
+        let error = parse_juniper("a", "\nroute-filter 10.0.0.0/8 upto /4;").unwrap_err();
+        assert_eq!(2, error.line());
+    }
+}
@@ -0,0 +1,500 @@
+//! Prefix ranges with RPSL range operators according to
+//! [RFC 2622](https://www.rfc-editor.org/rfc/rfc2622.html#section-2).
+//!
+//! Range operator after prefix selects more specific prefixes of base prefix:
+//!
+//! * `^-` – exclusive more specifics, so all more specifics without base prefix,
+//! * `^+` – inclusive more specifics, so base prefix and all its more specifics,
+//! * `^n` – all more specifics with length `n`,
+//! * `^n-m` – all more specifics with length from `n` to `m`.
+//!
+//! # Examples
+//!
+//! ```
+//! use ip_network::IpNetwork;
+//! use ip_network::prefix_range::PrefixRange;
+//!
+//! let range: PrefixRange = "10.0.0.0/8^16-24".parse()?;
+//! assert!(range.matches(&"10.1.0.0/16".parse::<IpNetwork>()?));
+//! assert!(!range.matches(&"10.0.0.0/8".parse::<IpNetwork>()?));
+//! assert_eq!("10.0.0.0/8 ge 16 le 24", range.to_ge_le().to_string());
+//!
+//! let range: PrefixRange = "192.0.2.0/24^25".parse()?;
+//! assert_eq!(2, range.prefixes().count());
+//! # Ok::<(), ip_network::prefix_range::PrefixRangeError>(())
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
+use crate::{IpNetwork, IpNetworkParseError, Ipv4Network, Ipv6Network};
+
+/// Base network with range of prefix lengths, that matches more specific networks of base network.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct PrefixRange {
+    network: IpNetwork,
+    min_length: u8,
+    max_length: u8,
+}
+
+impl PrefixRange {
+    /// Creates new prefix range, that matches subnets of `network` with netmask from `min_length`
+    /// to `max_length`.
+    ///
+    /// # Errors
+    ///
+    /// If `min_length` is smaller than `network` netmask, `max_length` is bigger than address
+    /// length or `min_length` is bigger than `max_length`.
+    pub fn new(
+        network: IpNetwork,
+        min_length: u8,
+        max_length: u8,
+    ) -> Result<Self, PrefixRangeError> {
+        if min_length < network.netmask()
+            || max_length > address_length(network)
+            || min_length > max_length
+        {
+            return Err(PrefixRangeError::InvalidLength {
+                min_length,
+                max_length,
+            });
+        }
+        Ok(Self {
+            network,
+            min_length,
+            max_length,
+        })
+    }
+
+    /// Creates prefix range, that matches only `network`.
+    pub fn exact(network: IpNetwork) -> Self {
+        Self {
+            network,
+            min_length: network.netmask(),
+            max_length: network.netmask(),
+        }
+    }
+
+    /// Creates prefix range from Cisco style `ge` and `le` values. Without `ge` and `le` only
+    /// `network` is matched, without `ge` range starts at `network` netmask and without `le` range
+    /// ends at address length. Like in Cisco, values must satisfy
+    /// `netmask < ge <= le <= address length`.
+    pub fn from_ge_le(
+        network: IpNetwork,
+        ge: Option<u8>,
+        le: Option<u8>,
+    ) -> Result<Self, PrefixRangeError> {
+        let (min_length, max_length) = match (ge, le) {
+            (None, None) => (network.netmask(), network.netmask()),
+            (Some(ge), None) => (ge, address_length(network)),
+            (None, Some(le)) => (network.netmask(), le),
+            (Some(ge), Some(le)) => (ge, le),
+        };
+        let netmask = network.netmask();
+        if ge.map_or(false, |ge| ge <= netmask) || le.map_or(false, |le| le <= netmask) {
+            return Err(PrefixRangeError::InvalidLength {
+                min_length,
+                max_length,
+            });
+        }
+        Self::new(network, min_length, max_length)
+    }
+
+    /// Returns base network.
+    pub fn network(&self) -> IpNetwork {
+        self.network
+    }
+
+    /// Returns the shortest matched prefix length.
+    pub fn min_length(&self) -> u8 {
+        self.min_length
+    }
+
+    /// Returns the longest matched prefix length.
+    pub fn max_length(&self) -> u8 {
+        self.max_length
+    }
+
+    /// Returns range of matched prefix lengths.
+    pub fn lengths(&self) -> RangeInclusive<u8> {
+        self.min_length..=self.max_length
+    }
+
+    /// Returns `true` if `network` is subnet of base network with netmask in range.
+    pub fn matches(&self, network: &IpNetwork) -> bool {
+        self.lengths().contains(&network.netmask())
+            && self.network.contains(network.network_address())
+    }
+
+    /// Returns iterator over matched networks with given netmask, that is empty when netmask is
+    /// out of range.
+    pub fn prefixes_with_length(&self, length: u8) -> impl Iterator<Item = IpNetwork> {
+        let in_range = self.lengths().contains(&length);
+        let (ipv4, ipv6) = match self.network {
+            IpNetwork::V4(network) if in_range => (Some(network), None),
+            IpNetwork::V6(network) if in_range => (None, Some(network)),
+            _ => (None, None),
+        };
+        // Subnets iterator is empty for the same netmask, so base network is returned separately.
+        let exact = Some(self.network).filter(|network| network.netmask() == length);
+        let ipv4 = ipv4
+            .map(|network: Ipv4Network| network.subnets_with_prefix(length))
+            .into_iter()
+            .flatten()
+            .map(IpNetwork::V4);
+        let ipv6 = ipv6
+            .map(|network: Ipv6Network| network.subnets_with_prefix(length))
+            .into_iter()
+            .flatten()
+            .map(IpNetwork::V6);
+        exact
+            .filter(|_| in_range)
+            .into_iter()
+            .chain(ipv4)
+            .chain(ipv6)
+    }
+
+    /// Returns iterator over all matched networks from the shortest netmask. Number of matched
+    /// networks grows exponentially with netmask range, so consider limiting the iterator.
+    pub fn prefixes(&self) -> impl Iterator<Item = IpNetwork> {
+        let range = *self;
+        self.lengths()
+            .flat_map(move |length| range.prefixes_with_length(length))
+    }
+
+    /// Converts range to equivalent entry of Cisco style prefix list.
+    pub fn to_ge_le(&self) -> GeLe {
+        let netmask = self.network.netmask();
+        let (ge, le) = if self.min_length == netmask && self.max_length == netmask {
+            (None, None)
+        } else if self.min_length == netmask {
+            (None, Some(self.max_length))
+        } else {
+            (Some(self.min_length), Some(self.max_length))
+        };
+        GeLe {
+            network: self.network,
+            ge,
+            le,
+        }
+    }
+}
+
+fn address_length(network: IpNetwork) -> u8 {
+    match network {
+        IpNetwork::V4(_) => Ipv4Network::LENGTH,
+        IpNetwork::V6(_) => Ipv6Network::LENGTH,
+    }
+}
+
+impl From<IpNetwork> for PrefixRange {
+    /// Converts network to range, that matches only this network.
+    fn from(network: IpNetwork) -> Self {
+        Self::exact(network)
+    }
+}
+
+impl fmt::Display for PrefixRange {
+    /// Converts range to RPSL notation, the shortest range operator is used.
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let netmask = self.network.netmask();
+        let length = address_length(self.network);
+        write!(fmt, "{}", self.network)?;
+        if self.min_length == netmask && self.max_length == netmask {
+            Ok(())
+        } else if self.min_length == netmask && self.max_length == length {
+            write!(fmt, "^+")
+        } else if self.min_length == netmask + 1 && self.max_length == length {
+            write!(fmt, "^-")
+        } else if self.min_length == self.max_length {
+            write!(fmt, "^{}", self.min_length)
+        } else {
+            write!(fmt, "^{}-{}", self.min_length, self.max_length)
+        }
+    }
+}
+
+impl FromStr for PrefixRange {
+    type Err = PrefixRangeError;
+
+    /// Parses network in CIDR notation optionally followed by RPSL range operator.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (network, operator) = match s.find('^') {
+            Some(position) => (&s[..position], Some(position)),
+            None => (s, None),
+        };
+        let network: IpNetwork = network.parse()?;
+        let operator = match operator {
+            Some(position) => position,
+            None => return Ok(Self::exact(network)),
+        };
+
+        let span = operator..s.len();
+        let invalid = || PrefixRangeError::InvalidOperator { span: span.clone() };
+        let length = address_length(network);
+        let (min_length, max_length) = match &s[operator + 1..] {
+            "-" => (
+                network.netmask().checked_add(1).ok_or_else(invalid)?,
+                length,
+            ),
+            "+" => (network.netmask(), length),
+            range => {
+                let parse = |n: &str| {
+                    if n.bytes().all(|c| c.is_ascii_digit()) {
+                        n.parse::<u8>().map_err(|_| invalid())
+                    } else {
+                        Err(invalid())
+                    }
+                };
+                match range.find('-') {
+                    Some(dash) => (parse(&range[..dash])?, parse(&range[dash + 1..])?),
+                    None => {
+                        let n = parse(range)?;
+                        (n, n)
+                    }
+                }
+            }
+        };
+        Self::new(network, min_length, max_length)
+    }
+}
+
+/// Cisco style prefix list entry with optional `ge` and `le` values.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct GeLe {
+    /// Base network.
+    pub network: IpNetwork,
+    /// The shortest matched netmask, when bigger than network netmask.
+    pub ge: Option<u8>,
+    /// The longest matched netmask.
+    pub le: Option<u8>,
+}
+
+impl fmt::Display for GeLe {
+    /// Converts entry to format used by Cisco `ip prefix-list`, for example `10.0.0.0/8 ge 16 le 24`.
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.network)?;
+        if let Some(ge) = self.ge {
+            write!(fmt, " ge {}", ge)?;
+        }
+        if let Some(le) = self.le {
+            write!(fmt, " le {}", le)?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors when creating or parsing prefix range.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum PrefixRangeError {
+    /// Base network is not valid.
+    InvalidNetwork(IpNetworkParseError),
+    /// Range operator has invalid format.
+    InvalidOperator {
+        /// Span of range operator in input.
+        span: Range<usize>,
+    },
+    /// Prefix lengths are outside of base network netmask and address length or minimal length
+    /// is bigger than maximal length.
+    InvalidLength {
+        /// The shortest prefix length.
+        min_length: u8,
+        /// The longest prefix length.
+        max_length: u8,
+    },
+}
+
+impl Error for PrefixRangeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            PrefixRangeError::InvalidNetwork(ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PrefixRangeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PrefixRangeError::InvalidNetwork(ref error) => write!(fmt, "{}", error),
+            PrefixRangeError::InvalidOperator { ref span } => {
+                write!(fmt, "invalid range operator at position {}", span.start)
+            }
+            PrefixRangeError::InvalidLength {
+                min_length,
+                max_length,
+            } => write!(
+                fmt,
+                "invalid prefix length range {}-{}",
+                min_length, max_length
+            ),
+        }
+    }
+}
+
+impl From<IpNetworkParseError> for PrefixRangeError {
+    fn from(error: IpNetworkParseError) -> Self {
+        PrefixRangeError::InvalidNetwork(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use super::{GeLe, PrefixRange, PrefixRangeError};
+    use crate::IpNetwork;
+
+    fn range(s: &str) -> PrefixRange {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let cases = [
+            ("192.0.2.0/24", 24, 24),
+            ("192.0.2.0/24^-", 25, 32),
+            ("192.0.2.0/24^+", 24, 32),
+            ("192.0.2.0/24^26", 26, 26),
+            ("10.0.0.0/8^16-24", 16, 24),
+            ("10.0.0.0/8^8-16", 8, 16),
+            ("2001:db8::/32^+", 32, 128),
+            ("2001:db8::/32^48-64", 48, 64),
+        ];
+        for (input, min_length, max_length) in &cases {
+            let range = range(input);
+            assert_eq!(*min_length, range.min_length(), "{}", input);
+            assert_eq!(*max_length, range.max_length(), "{}", input);
+            assert_eq!(*input, range.to_string());
+        }
+        assert_eq!("10.0.0.0/8", range("10.0.0.0/8^8").to_string());
+        assert_eq!(
+            "10.0.0.0/8",
+            PrefixRange::exact(IpNetwork::from_str("10.0.0.0/8").unwrap()).to_string()
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(matches!(
+            "10.0.0.1/8^+".parse::<PrefixRange>(),
+            Err(PrefixRangeError::InvalidNetwork(_))
+        ));
+        assert_eq!(
+            Err(PrefixRangeError::InvalidOperator { span: 10..13 }),
+            "10.0.0.0/8^+x".parse::<PrefixRange>()
+        );
+        for input in &[
+            "10.0.0.0/8^",
+            "10.0.0.0/8^a",
+            "10.0.0.0/8^16-",
+            "10.0.0.0/8^+16",
+            "10.0.0.0/8^256",
+        ] {
+            assert!(
+                matches!(
+                    input.parse::<PrefixRange>(),
+                    Err(PrefixRangeError::InvalidOperator { .. })
+                ),
+                "{}",
+                input
+            );
+        }
+        for input in &[
+            "10.0.0.0/8^7",
+            "10.0.0.0/8^24-16",
+            "10.0.0.0/8^33",
+            "10.0.0.1/32^-",
+        ] {
+            assert!(
+                matches!(
+                    input.parse::<PrefixRange>(),
+                    Err(PrefixRangeError::InvalidLength { .. })
+                ),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn matches() {
+        let range = range("10.0.0.0/8^-");
+        assert!(!range.matches(&IpNetwork::from_str("10.0.0.0/8").unwrap()));
+        assert!(range.matches(&IpNetwork::from_str("10.0.0.0/9").unwrap()));
+        assert!(range.matches(&IpNetwork::from_str("10.255.255.255/32").unwrap()));
+        assert!(!range.matches(&IpNetwork::from_str("11.0.0.0/16").unwrap()));
+        assert!(!range.matches(&IpNetwork::from_str("::/128").unwrap()));
+    }
+
+    #[test]
+    fn prefixes() {
+        let prefixes: Vec<_> = range("192.0.2.0/24^+").prefixes().take(4).collect();
+        assert_eq!(
+            vec![
+                IpNetwork::from_str("192.0.2.0/24").unwrap(),
+                IpNetwork::from_str("192.0.2.0/25").unwrap(),
+                IpNetwork::from_str("192.0.2.128/25").unwrap(),
+                IpNetwork::from_str("192.0.2.0/26").unwrap()
+            ],
+            prefixes
+        );
+        assert_eq!(511, range("192.0.2.0/24^+").prefixes().count());
+        assert_eq!(
+            4,
+            range("2001:db8::/32^34").prefixes_with_length(34).count()
+        );
+        assert_eq!(
+            0,
+            range("2001:db8::/32^34").prefixes_with_length(33).count()
+        );
+        assert_eq!(
+            vec![IpNetwork::from_str("::1/128").unwrap()],
+            range("::1/128").prefixes().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn ge_le() {
+        let cases = [
+            ("10.0.0.0/8", None, None),
+            ("10.0.0.0/8^+", None, Some(32)),
+            ("10.0.0.0/8^-", Some(9), Some(32)),
+            ("10.0.0.0/8^8-16", None, Some(16)),
+            ("10.0.0.0/8^16", Some(16), Some(16)),
+        ];
+        for (input, ge, le) in &cases {
+            let range = range(input);
+            let ge_le = range.to_ge_le();
+            assert_eq!(
+                GeLe {
+                    network: IpNetwork::from_str("10.0.0.0/8").unwrap(),
+                    ge: *ge,
+                    le: *le
+                },
+                ge_le
+            );
+            assert_eq!(Ok(range), PrefixRange::from_ge_le(ge_le.network, *ge, *le));
+        }
+        assert_eq!(
+            range("10.0.0.0/8^16-32"),
+            PrefixRange::from_ge_le(IpNetwork::from_str("10.0.0.0/8").unwrap(), Some(16), None)
+                .unwrap()
+        );
+        for (ge, le) in &[
+            (Some(8), None),
+            (Some(8), Some(16)),
+            (None, Some(8)),
+            (Some(4), None),
+        ] {
+            assert_eq!(
+                Err(PrefixRangeError::InvalidLength {
+                    min_length: ge.unwrap_or(8),
+                    max_length: le.unwrap_or(32),
+                }),
+                PrefixRange::from_ge_le(IpNetwork::from_str("10.0.0.0/8").unwrap(), *ge, *le)
+            );
+        }
+    }
+}
@@ -0,0 +1,302 @@
+//! Strategies for property-based testing with [proptest](https://crates.io/crates/proptest).
+//!
+//! All strategies produce only valid networks, so network address never has host bits set.
+//!
+//! # Examples
+//!
+//! ```
+//! use proptest::strategy::{Strategy, ValueTree};
+//! use proptest::test_runner::TestRunner;
+//! use ip_network::proptest::ipv4_subnet;
+//!
+//! let mut runner = TestRunner::default();
+//! let subnet = ipv4_subnet("10.0.0.0/8".parse()?)
+//!     .new_tree(&mut runner)
+//!     .unwrap()
+//!     .current();
+//! assert!(subnet.netmask() >= 8);
+//! assert_eq!(10, subnet.network_address().octets()[0]);
+//! # Ok::<(), ip_network::IpNetworkParseError>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ops::RangeInclusive;
+use proptest::collection::{btree_map, SizeRange};
+use proptest::prelude::*;
+use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+/// Strategy for `Ipv4Network` with any netmask.
+pub fn ipv4_network() -> impl Strategy<Value = Ipv4Network> {
+    ipv4_network_with_netmask(0..=Ipv4Network::LENGTH)
+}
+
+/// Strategy for `Ipv6Network` with any netmask.
+pub fn ipv6_network() -> impl Strategy<Value = Ipv6Network> {
+    ipv6_network_with_netmask(0..=Ipv6Network::LENGTH)
+}
+
+/// Strategy for `IpNetwork`, that is IPv4 or IPv6 network with the same probability.
+pub fn ip_network() -> impl Strategy<Value = IpNetwork> {
+    prop_oneof![
+        ipv4_network().prop_map(IpNetwork::V4),
+        ipv6_network().prop_map(IpNetwork::V6),
+    ]
+}
+
+/// Strategy for `Ipv4Network` with netmask from given range.
+///
+/// # Panics
+///
+/// If range ends after 32.
+pub fn ipv4_network_with_netmask(
+    netmask: RangeInclusive<u8>,
+) -> impl Strategy<Value = Ipv4Network> {
+    assert!(
+        *netmask.end() <= Ipv4Network::LENGTH,
+        "IPv4 netmask range ends after 32"
+    );
+    (any::<u32>(), netmask).prop_map(|(network_address, netmask)| {
+        Ipv4Network::new_truncate(Ipv4Addr::from(network_address), netmask).unwrap()
+    })
+}
+
+/// Strategy for `Ipv6Network` with netmask from given range.
+///
+/// # Panics
+///
+/// If range ends after 128.
+pub fn ipv6_network_with_netmask(
+    netmask: RangeInclusive<u8>,
+) -> impl Strategy<Value = Ipv6Network> {
+    assert!(
+        *netmask.end() <= Ipv6Network::LENGTH,
+        "IPv6 netmask range ends after 128"
+    );
+    (any::<u128>(), netmask).prop_map(|(network_address, netmask)| {
+        Ipv6Network::new_truncate(Ipv6Addr::from(network_address), netmask).unwrap()
+    })
+}
+
+/// Strategy for subnets of `parent` network, including `parent` itself.
+pub fn ipv4_subnet(parent: Ipv4Network) -> impl Strategy<Value = Ipv4Network> {
+    let network_address = u32::from(parent.network_address());
+    let host_mask = u32::MAX.checked_shr(parent.netmask().into()).unwrap_or(0);
+    (any::<u32>(), parent.netmask()..=Ipv4Network::LENGTH).prop_map(move |(host, netmask)| {
+        let address = Ipv4Addr::from(network_address | (host & host_mask));
+        Ipv4Network::new_truncate(address, netmask).unwrap()
+    })
+}
+
+/// Strategy for subnets of `parent` network, including `parent` itself.
+pub fn ipv6_subnet(parent: Ipv6Network) -> impl Strategy<Value = Ipv6Network> {
+    let network_address = u128::from(parent.network_address());
+    let host_mask = u128::MAX.checked_shr(parent.netmask().into()).unwrap_or(0);
+    (any::<u128>(), parent.netmask()..=Ipv6Network::LENGTH).prop_map(move |(host, netmask)| {
+        let address = Ipv6Addr::from(network_address | (host & host_mask));
+        Ipv6Network::new_truncate(address, netmask).unwrap()
+    })
+}
+
+/// Strategy for sorted list of networks, where no two networks overlap. Number of networks is
+/// selected from `size`.
+pub fn ipv4_disjoint_networks(
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = Vec<Ipv4Network>> {
+    btree_map(any::<u32>(), any::<u8>(), size).prop_map(|points| {
+        let points = points.into_iter().map(|(a, s)| (a.into(), s)).collect();
+        disjoint_networks(&points, Ipv4Network::LENGTH)
+            .map(|(address, netmask)| {
+                Ipv4Network::new_truncate(Ipv4Addr::from(address as u32), netmask).unwrap()
+            })
+            .collect()
+    })
+}
+
+/// Strategy for sorted list of networks, where no two networks overlap. Number of networks is
+/// selected from `size`.
+pub fn ipv6_disjoint_networks(
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = Vec<Ipv6Network>> {
+    btree_map(any::<u128>(), any::<u8>(), size).prop_map(|points| {
+        disjoint_networks(&points, Ipv6Network::LENGTH)
+            .map(|(address, netmask)| {
+                Ipv6Network::new_truncate(Ipv6Addr::from(address), netmask).unwrap()
+            })
+            .collect()
+    })
+}
+
+/// For every address selects network, that contains this address but none of neighbouring
+/// addresses. Two such networks can't overlap, because overlapping networks are nested and the
+/// bigger one would contain address of neighbour. Seed selects netmask from allowed netmasks.
+fn disjoint_networks(
+    points: &BTreeMap<u128, u8>,
+    length: u8,
+) -> impl Iterator<Item = (u128, u8)> + '_ {
+    let addresses: Vec<u128> = points.keys().copied().collect();
+    points
+        .iter()
+        .enumerate()
+        .map(move |(i, (&address, &seed))| {
+            let neighbours = [
+                i.checked_sub(1).map(|i| addresses[i]),
+                addresses.get(i + 1).copied(),
+            ];
+            let min_netmask = neighbours
+                .iter()
+                .flatten()
+                .map(|neighbour| {
+                    // Length of common prefix plus one.
+                    ((address ^ neighbour).leading_zeros() - (128 - u32::from(length)) + 1) as u8
+                })
+                .max()
+                .unwrap_or(0);
+            let netmask = min_netmask + seed % (length - min_netmask + 1);
+            (address, netmask)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use super::*;
+
+    fn ipv4_overlaps(a: &Ipv4Network, b: &Ipv4Network) -> bool {
+        a.contains(b.network_address()) || b.contains(a.network_address())
+    }
+
+    fn ipv4_contains(a: &Ipv4Network, b: &Ipv4Network) -> bool {
+        a.netmask() <= b.netmask() && a.contains(b.network_address())
+    }
+
+    /// Number of addresses covered by networks.
+    fn ipv4_union_size(networks: &[Ipv4Network]) -> u64 {
+        let mut ranges: Vec<(u64, u64)> = networks
+            .iter()
+            .map(|n| {
+                (
+                    u32::from(n.network_address()).into(),
+                    u32::from(n.broadcast_address()).into(),
+                )
+            })
+            .collect();
+        ranges.sort_unstable();
+        let mut size = 0;
+        let mut next = 0;
+        for (first, last) in ranges {
+            let first = first.max(next);
+            if last >= first {
+                size += last - first + 1;
+                next = last + 1;
+            }
+        }
+        size
+    }
+
+    proptest! {
+        #[test]
+        fn ipv4_subnet_is_contained(
+            (parent, subnet) in ipv4_network().prop_flat_map(|p| (Just(p), ipv4_subnet(p)))
+        ) {
+            prop_assert!(ipv4_contains(&parent, &subnet));
+        }
+
+        #[test]
+        fn ipv6_subnet_is_contained(
+            (parent, subnet) in ipv6_network().prop_flat_map(|p| (Just(p), ipv6_subnet(p)))
+        ) {
+            prop_assert!(parent.netmask() <= subnet.netmask());
+            prop_assert!(parent.contains(subnet.network_address()));
+        }
+
+        #[test]
+        fn netmask_range(network in ipv6_network_with_netmask(48..=64)) {
+            prop_assert!((48..=64).contains(&network.netmask()));
+        }
+
+        #[test]
+        fn ipv4_disjoint(networks in ipv4_disjoint_networks(0..16)) {
+            prop_assert!(networks.len() < 16);
+            for (i, a) in networks.iter().enumerate() {
+                for b in &networks[i + 1..] {
+                    prop_assert!(a < b);
+                    prop_assert!(!ipv4_overlaps(a, b));
+                }
+            }
+        }
+
+        #[test]
+        fn ipv6_disjoint(networks in ipv6_disjoint_networks(1..16)) {
+            for pair in networks.windows(2) {
+                prop_assert!(pair[0].last_address() < pair[1].network_address());
+            }
+        }
+
+        #[test]
+        fn ipv4_collapse_addresses(
+            networks in vec(ipv4_subnet("10.0.0.0/16".parse().unwrap()), 0..32)
+        ) {
+            let collapsed = Ipv4Network::collapse_addresses(&networks);
+            for pair in collapsed.windows(2) {
+                prop_assert!(pair[0].broadcast_address() < pair[1].network_address());
+                // Sibling networks should be merged to supernet.
+                prop_assert!(pair[0].netmask() != pair[1].netmask() || pair[0].supernet() != pair[1].supernet());
+            }
+            for network in &networks {
+                prop_assert!(collapsed.iter().any(|c| ipv4_contains(c, network)));
+            }
+            prop_assert_eq!(ipv4_union_size(&networks), ipv4_union_size(&collapsed));
+        }
+
+        #[test]
+        fn ipv4_collapse_disjoint(networks in ipv4_disjoint_networks(0..32)) {
+            let collapsed = Ipv4Network::collapse_addresses(&networks);
+            prop_assert!(collapsed.len() <= networks.len());
+            prop_assert_eq!(ipv4_union_size(&networks), ipv4_union_size(&collapsed));
+        }
+
+        #[test]
+        fn ip_collapse_addresses_is_idempotent(networks in vec(ip_network(), 0..32)) {
+            let collapsed = IpNetwork::collapse_addresses(&networks);
+            prop_assert_eq!(&collapsed, &IpNetwork::collapse_addresses(&collapsed));
+            for network in &networks {
+                let covered = collapsed.iter().any(|c| {
+                    c.netmask() <= network.netmask() && c.contains(network.network_address())
+                });
+                prop_assert!(covered);
+            }
+        }
+
+        #[test]
+        fn ipv4_summarize_address_range(a in any::<u32>(), b in any::<u32>()) {
+            let (first, last) = (Ipv4Addr::from(a.min(b)), Ipv4Addr::from(a.max(b)));
+            let networks = Ipv4Network::summarize_address_range(first, last);
+            prop_assert_eq!(first, networks[0].network_address());
+            prop_assert_eq!(last, networks[networks.len() - 1].broadcast_address());
+            for pair in networks.windows(2) {
+                prop_assert_eq!(
+                    u64::from(u32::from(pair[0].broadcast_address())) + 1,
+                    u64::from(u32::from(pair[1].network_address()))
+                );
+            }
+            prop_assert_eq!(&networks, &Ipv4Network::collapse_addresses(&networks));
+        }
+
+        #[test]
+        fn ipv4_parse_display(network in ipv4_network()) {
+            prop_assert_eq!(Ok(network), network.to_string().parse());
+        }
+
+        #[test]
+        fn ipv6_parse_display(network in ipv6_network()) {
+            prop_assert_eq!(Ok(network), network.to_string().parse());
+        }
+
+        #[test]
+        fn ip_parse_display(network in ip_network()) {
+            prop_assert_eq!(Ok(network), network.to_string().parse());
+        }
+    }
+}
@@ -0,0 +1,494 @@
+//! Route origin validation with ROAs (Route Origin Authorizations) according to
+//! [RFC 6811](https://www.rfc-editor.org/rfc/rfc6811.html).
+//!
+//! ROAs can be loaded from CSV export of validators like rpki-client or Routinator by
+//! [`parse_csv`] or from JSON export by `parse_json`, when `serde_json` feature is enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use ip_network::IpNetwork;
+//! use ip_network::rpki::{parse_csv, RoaTable, Validity};
+//!
+//! let csv = "\
+//! ASN,IP Prefix,Max Length,Trust Anchor
+//! AS13335,1.0.0.0/24,24,apnic
+//! AS64496,192.0.2.0/24,26,ripe
+//! ";
+//! let table: RoaTable = parse_csv(csv.as_bytes())?.into_iter().collect();
+//!
+//! let route = "192.0.2.0/25".parse::<IpNetwork>()?;
+//! assert_eq!(Validity::Valid, table.validate(route, 64496).validity);
+//! assert_eq!(Validity::Invalid, table.validate(route, 64497).validity);
+//! assert_eq!(Validity::NotFound, table.validate("198.51.100.0/24".parse()?, 64496).validity);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::iter::FromIterator;
+use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+/// Route Origin Authorization, that allows AS to originate prefix and its subnets up to maximum
+/// length.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct Roa {
+    /// Authorized prefix.
+    pub prefix: IpNetwork,
+    /// Maximum length of announced prefix.
+    pub max_length: u8,
+    /// Authorized origin AS. AS 0 means, that prefix should not be announced at all.
+    pub asn: u32,
+}
+
+impl Roa {
+    /// Creates new ROA.
+    ///
+    /// # Errors
+    ///
+    /// If `max_length` is smaller than prefix netmask or bigger than address length.
+    pub fn new(prefix: IpNetwork, max_length: u8, asn: u32) -> Result<Self, RoaError> {
+        let length = match prefix {
+            IpNetwork::V4(_) => Ipv4Network::LENGTH,
+            IpNetwork::V6(_) => Ipv6Network::LENGTH,
+        };
+        if max_length < prefix.netmask() || max_length > length {
+            return Err(RoaError::InvalidMaxLength { prefix, max_length });
+        }
+        Ok(Self {
+            prefix,
+            max_length,
+            asn,
+        })
+    }
+
+    /// Returns `true` if ROA prefix covers `route`, so route is the same as prefix or its subnet.
+    pub fn covers(&self, route: IpNetwork) -> bool {
+        route.netmask() >= self.prefix.netmask() && self.prefix.contains(route.network_address())
+    }
+
+    /// Returns `true` if ROA covers `route`, route isn't longer than maximum length and origin
+    /// matches. ROA with AS 0 never matches.
+    pub fn matches(&self, route: IpNetwork, origin: u32) -> bool {
+        self.asn != 0
+            && self.asn == origin
+            && route.netmask() <= self.max_length
+            && self.covers(route)
+    }
+}
+
+impl fmt::Display for Roa {
+    /// Converts ROA to string in format `prefix-max_length AS`, for example
+    /// `192.0.2.0/24-26 AS64496`.
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}-{} AS{}", self.prefix, self.max_length, self.asn)
+    }
+}
+
+/// Validation state of route.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Validity {
+    /// At least one ROA matches route.
+    Valid,
+    /// Route is covered by some ROA, but none of them matches.
+    Invalid,
+    /// No ROA covers route.
+    NotFound,
+}
+
+/// Result of route validation with ROAs used for decision.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Validation<'a> {
+    /// Validation state.
+    pub validity: Validity,
+    /// ROAs matching route.
+    pub matching: Vec<&'a Roa>,
+    /// All ROAs covering route including matching ones, from the least specific.
+    pub covering: Vec<&'a Roa>,
+}
+
+/// Set of ROAs for route origin validation.
+#[derive(Clone, Debug, Default)]
+pub struct RoaTable {
+    roas: BTreeMap<IpNetwork, Vec<Roa>>,
+    len: usize,
+}
+
+impl RoaTable {
+    /// Creates empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts ROA, duplicate ROAs are ignored.
+    pub fn insert(&mut self, roa: Roa) {
+        let roas = self.roas.entry(roa.prefix).or_default();
+        if !roas.contains(&roa) {
+            roas.push(roa);
+            self.len += 1;
+        }
+    }
+
+    /// Returns number of ROAs in table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if table contains no ROAs.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns iterator over all ROAs sorted by prefix.
+    pub fn iter(&self) -> impl Iterator<Item = &Roa> {
+        self.roas.values().flatten()
+    }
+
+    /// Returns all ROAs covering `route`, from the least specific.
+    pub fn covering(&self, route: IpNetwork) -> Vec<&Roa> {
+        (0..=route.netmask())
+            .filter_map(|netmask| {
+                let prefix = IpNetwork::new_truncate(route.network_address(), netmask).unwrap();
+                self.roas.get(&prefix)
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Validates route announced by `origin` AS. Use origin 0 for routes, that don't have single
+    /// origin AS (AS_SET as the last segment of AS path), these routes can't be valid.
+    pub fn validate(&self, route: IpNetwork, origin: u32) -> Validation<'_> {
+        let covering = self.covering(route);
+        let matching: Vec<&Roa> = covering
+            .iter()
+            .copied()
+            .filter(|roa| roa.matches(route, origin))
+            .collect();
+        let validity = if !matching.is_empty() {
+            Validity::Valid
+        } else if !covering.is_empty() {
+            Validity::Invalid
+        } else {
+            Validity::NotFound
+        };
+        Validation {
+            validity,
+            matching,
+            covering,
+        }
+    }
+}
+
+impl Extend<Roa> for RoaTable {
+    fn extend<I: IntoIterator<Item = Roa>>(&mut self, iter: I) {
+        for roa in iter {
+            self.insert(roa);
+        }
+    }
+}
+
+impl FromIterator<Roa> for RoaTable {
+    fn from_iter<I: IntoIterator<Item = Roa>>(iter: I) -> Self {
+        let mut table = Self::new();
+        table.extend(iter);
+        table
+    }
+}
+
+/// Parses ROAs from CSV export with columns ASN, prefix and maximum length, other columns are
+/// ignored. Header line and empty lines are skipped. ASN can be prefixed with `AS`.
+pub fn parse_csv<R: BufRead>(reader: R) -> Result<Vec<Roa>, RoaError> {
+    let mut roas = vec![];
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || (index == 0 && line.to_ascii_uppercase().starts_with("ASN")) {
+            continue;
+        }
+        let invalid = |reason| RoaError::InvalidRecord {
+            record: index + 1,
+            reason,
+        };
+        let mut fields = line.split(',').map(str::trim);
+        let mut field = |name| fields.next().ok_or_else(|| invalid(name));
+        let asn = parse_asn(field("missing ASN")?).ok_or_else(|| invalid("invalid ASN"))?;
+        let prefix = field("missing prefix")?
+            .parse()
+            .map_err(|_| invalid("invalid prefix"))?;
+        let max_length = field("missing max length")?
+            .parse()
+            .map_err(|_| invalid("invalid max length"))?;
+        roas.push(Roa::new(prefix, max_length, asn)?);
+    }
+    Ok(roas)
+}
+
+/// Parses ROAs from JSON export in format `{"roas": [{"asn": "AS64496", "prefix":
+/// "192.0.2.0/24", "maxLength": 24}, ...]}`, that is used by rpki-client and Routinator. ASN can
+/// be number or string prefixed with `AS`, other fields are ignored.
+#[cfg(feature = "serde_json")]
+pub fn parse_json<R: io::Read>(reader: R) -> Result<Vec<Roa>, RoaError> {
+    use std::convert::TryInto;
+    use serde_json::Value;
+
+    let document: Value = serde_json::from_reader(reader)?;
+    let records =
+        document
+            .get("roas")
+            .and_then(Value::as_array)
+            .ok_or(RoaError::InvalidRecord {
+                record: 0,
+                reason: "missing roas array",
+            })?;
+    records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            let invalid = |reason| RoaError::InvalidRecord {
+                record: index + 1,
+                reason,
+            };
+            let asn = match record.get("asn") {
+                Some(Value::Number(number)) => number.as_u64().and_then(|n| n.try_into().ok()),
+                Some(Value::String(asn)) => parse_asn(asn),
+                _ => None,
+            }
+            .ok_or_else(|| invalid("invalid ASN"))?;
+            let prefix = record
+                .get("prefix")
+                .and_then(Value::as_str)
+                .and_then(|prefix| prefix.parse().ok())
+                .ok_or_else(|| invalid("invalid prefix"))?;
+            let max_length = record
+                .get("maxLength")
+                .and_then(Value::as_u64)
+                .and_then(|max_length| max_length.try_into().ok())
+                .ok_or_else(|| invalid("invalid max length"))?;
+            Roa::new(prefix, max_length, asn)
+        })
+        .collect()
+}
+
+fn parse_asn(asn: &str) -> Option<u32> {
+    let number = match asn.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("AS") => &asn[2..],
+        _ => asn,
+    };
+    number.parse().ok()
+}
+
+/// Errors when creating or loading ROAs.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RoaError {
+    /// Maximum length is smaller than prefix netmask or bigger than address length.
+    InvalidMaxLength {
+        /// ROA prefix.
+        prefix: IpNetwork,
+        /// Invalid maximum length.
+        max_length: u8,
+    },
+    /// Record in export is invalid.
+    InvalidRecord {
+        /// Line number in CSV or index of ROA in JSON, starting from 1.
+        record: usize,
+        /// Description of error.
+        reason: &'static str,
+    },
+    /// Reading failed.
+    Io(io::Error),
+    /// JSON is not valid.
+    #[cfg(feature = "serde_json")]
+    Json(serde_json::Error),
+}
+
+impl Error for RoaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            RoaError::Io(ref error) => Some(error),
+            #[cfg(feature = "serde_json")]
+            RoaError::Json(ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RoaError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RoaError::InvalidMaxLength { prefix, max_length } => write!(
+                fmt,
+                "invalid max length {} for ROA prefix {}",
+                max_length, prefix
+            ),
+            RoaError::InvalidRecord { record, reason } => {
+                write!(fmt, "invalid ROA record {}: {}", record, reason)
+            }
+            RoaError::Io(ref error) => write!(fmt, "reading ROAs failed: {}", error),
+            #[cfg(feature = "serde_json")]
+            RoaError::Json(ref error) => write!(fmt, "invalid JSON: {}", error),
+        }
+    }
+}
+
+impl From<io::Error> for RoaError {
+    fn from(error: io::Error) -> Self {
+        RoaError::Io(error)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl From<serde_json::Error> for RoaError {
+    fn from(error: serde_json::Error) -> Self {
+        RoaError::Json(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use super::{parse_csv, Roa, RoaError, RoaTable, Validity};
+    use crate::IpNetwork;
+
+    fn roa(prefix: &str, max_length: u8, asn: u32) -> Roa {
+        Roa::new(IpNetwork::from_str(prefix).unwrap(), max_length, asn).unwrap()
+    }
+
+    #[test]
+    fn invalid_max_length() {
+        assert!(matches!(
+            Roa::new(IpNetwork::from_str("10.0.0.0/8").unwrap(), 7, 1),
+            Err(RoaError::InvalidMaxLength { max_length: 7, .. })
+        ));
+        assert!(Roa::new(IpNetwork::from_str("10.0.0.0/8").unwrap(), 33, 1).is_err());
+        assert!(Roa::new(IpNetwork::from_str("2001:db8::/32").unwrap(), 128, 1).is_ok());
+    }
+
+    #[test]
+    fn validate() {
+        let table: RoaTable = vec![
+            roa("10.0.0.0/8", 16, 64496),
+            roa("10.0.0.0/16", 24, 64497),
+            roa("10.0.0.0/16", 24, 64497),
+            roa("192.0.2.0/24", 24, 0),
+            roa("2001:db8::/32", 48, 64498),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(4, table.len());
+
+        let result = table.validate(IpNetwork::from_str("10.0.0.0/16").unwrap(), 64496);
+        assert_eq!(Validity::Valid, result.validity);
+        assert_eq!(vec![&roa("10.0.0.0/8", 16, 64496)], result.matching);
+        assert_eq!(2, result.covering.len());
+
+        // Too long for the first ROA.
+        let result = table.validate(IpNetwork::from_str("10.0.0.0/24").unwrap(), 64496);
+        assert_eq!(Validity::Invalid, result.validity);
+        assert!(result.matching.is_empty());
+        assert_eq!(
+            vec![
+                &roa("10.0.0.0/8", 16, 64496),
+                &roa("10.0.0.0/16", 24, 64497)
+            ],
+            result.covering
+        );
+        assert_eq!(
+            Validity::Valid,
+            table
+                .validate(IpNetwork::from_str("10.0.0.0/24").unwrap(), 64497)
+                .validity
+        );
+        assert_eq!(
+            Validity::Invalid,
+            table
+                .validate(IpNetwork::from_str("10.1.0.0/25").unwrap(), 64496)
+                .validity
+        );
+        // Less specific than any ROA.
+        assert_eq!(
+            Validity::NotFound,
+            table
+                .validate(IpNetwork::from_str("10.0.0.0/7").unwrap(), 64496)
+                .validity
+        );
+        // AS 0 never matches.
+        assert_eq!(
+            Validity::Invalid,
+            table
+                .validate(IpNetwork::from_str("192.0.2.0/24").unwrap(), 0)
+                .validity
+        );
+        assert_eq!(
+            Validity::Valid,
+            table
+                .validate(IpNetwork::from_str("2001:db8:1::/48").unwrap(), 64498)
+                .validity
+        );
+        assert_eq!(
+            Validity::NotFound,
+            table
+                .validate(IpNetwork::from_str("2001:db9::/48").unwrap(), 64498)
+                .validity
+        );
+    }
+
+    #[test]
+    fn csv() {
+        let input = "\
+ASN,IP Prefix,Max Length,Trust Anchor,Expires
+AS13335,1.0.0.0/24,24,apnic,1700000000
+
+64496, 2001:db8::/32 ,48
+";
+        let roas = parse_csv(input.as_bytes()).unwrap();
+        assert_eq!(
+            vec![
+                roa("1.0.0.0/24", 24, 13335),
+                roa("2001:db8::/32", 48, 64496)
+            ],
+            roas
+        );
+
+        let error = parse_csv("AS1,10.0.0.0/8\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            "invalid ROA record 1: missing max length",
+            error.to_string()
+        );
+        assert!(parse_csv("ASX,10.0.0.0/8,8\n".as_bytes()).is_err());
+        assert!(parse_csv("AS1,10.0.0.1/8,8\n".as_bytes()).is_err());
+        assert!(parse_csv("AS1,10.0.0.0/8,4\n".as_bytes()).is_err());
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn json() {
+        use super::parse_json;
+
+        let input = r#"{
+            "metadata": {"buildtime": "2024-01-01T00:00:00Z"},
+            "roas": [
+                {"asn": 13335, "prefix": "1.0.0.0/24", "maxLength": 24, "ta": "apnic"},
+                {"asn": "AS64496", "prefix": "2001:db8::/32", "maxLength": 48, "ta": "ripe"}
+            ]
+        }"#;
+        let roas = parse_json(input.as_bytes()).unwrap();
+        assert_eq!(
+            vec![
+                roa("1.0.0.0/24", 24, 13335),
+                roa("2001:db8::/32", 48, 64496)
+            ],
+            roas
+        );
+
+        let input = r#"{"roas": [{"asn": 1, "prefix": "10.0.0.0/8"}]}"#;
+        assert!(matches!(
+            parse_json(input.as_bytes()),
+            Err(RoaError::InvalidRecord { record: 1, .. })
+        ));
+        assert!(matches!(parse_json("{".as_bytes()), Err(RoaError::Json(_))));
+        assert!(parse_json("{}".as_bytes()).is_err());
+    }
+}
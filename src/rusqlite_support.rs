@@ -0,0 +1,327 @@
+//! Networks and interfaces are stored as text in CIDR notation.
+
+use std::error::Error;
+use std::net::IpAddr;
+use rusqlite::functions::{Aggregate, Context, FunctionFlags};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::Connection;
+use crate::{IpInterface, IpNetwork, Ipv4Interface, Ipv4Network, Ipv6Interface, Ipv6Network};
+
+macro_rules! to_sql_impl {
+    ($t:ty) => {
+        impl ToSql for $t {
+            fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+                Ok(ToSqlOutput::from(self.to_string()))
+            }
+        }
+    };
+}
+
+macro_rules! from_sql_impl {
+    ($t:ty) => {
+        impl FromSql for $t {
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                parse_text(value.as_bytes()?)
+            }
+        }
+    };
+}
+
+to_sql_impl!(Ipv4Network);
+to_sql_impl!(Ipv6Network);
+to_sql_impl!(IpNetwork);
+to_sql_impl!(Ipv4Interface);
+to_sql_impl!(Ipv6Interface);
+to_sql_impl!(IpInterface);
+
+from_sql_impl!(Ipv4Network);
+from_sql_impl!(Ipv6Network);
+from_sql_impl!(IpNetwork);
+from_sql_impl!(Ipv4Interface);
+from_sql_impl!(Ipv6Interface);
+from_sql_impl!(IpInterface);
+
+fn other_error<E: Error + Send + Sync + 'static>(error: E) -> FromSqlError {
+    FromSqlError::Other(Box::new(error))
+}
+
+fn parse_text<T>(text: &[u8]) -> FromSqlResult<T>
+where
+    T: std::str::FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    std::str::from_utf8(text)
+        .map_err(other_error)?
+        .parse()
+        .map_err(other_error)
+}
+
+/// Registers scalar and aggregate SQL functions for network queries on given connection.
+///
+/// Networks are accepted as text in CIDR notation, addresses as text. When any argument is `NULL`, scalar functions return `NULL`.
+///
+/// | Function                 | Returns                                                           |
+/// |--------------------------|-------------------------------------------------------------------|
+/// | `net_contains(net, addr)`| `1` if network contains address (or network with longer netmask)  |
+/// | `net_overlaps(net, net)` | `1` if networks have at least one address in common               |
+/// | `net_masklen(net)`       | network mask length                                               |
+/// | `net_family(net)`        | `4` for IPv4 network, `6` for IPv6 network                        |
+/// | `net_network(net)`       | network address as text                                           |
+/// | `net_broadcast(net)`     | broadcast (last) address as text                                  |
+/// | `net_collapse(net)`      | aggregate, JSON array of collapsed networks                       |
+///
+/// # Examples
+///
+/// ```
+/// use rusqlite::Connection;
+///
+/// let connection = Connection::open_in_memory()?;
+/// ip_network::rusqlite_support::register_functions(&connection)?;
+///
+/// let contains: bool = connection.query_row(
+///     "SELECT net_contains('192.168.0.0/16', '192.168.1.1')",
+///     [],
+///     |row| row.get(0),
+/// )?;
+/// assert!(contains);
+///
+/// let collapsed: String = connection.query_row(
+///     "SELECT net_collapse(net) FROM (SELECT '10.0.0.0/24' AS net UNION SELECT '10.0.1.0/24')",
+///     [],
+///     |row| row.get(0),
+/// )?;
+/// assert_eq!(collapsed, r#"["10.0.0.0/23"]"#);
+/// # Ok::<(), rusqlite::Error>(())
+/// ```
+pub fn register_functions(connection: &Connection) -> rusqlite::Result<()> {
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+
+    connection.create_scalar_function("net_contains", 2, flags, |ctx| {
+        Ok(
+            match (network_arg(ctx, 0)?, network_or_address_arg(ctx, 1)?) {
+                (Some(network), Some(other)) => Some(contains(&network, &other)),
+                _ => None,
+            },
+        )
+    })?;
+    connection.create_scalar_function("net_overlaps", 2, flags, |ctx| {
+        Ok(match (network_arg(ctx, 0)?, network_arg(ctx, 1)?) {
+            (Some(a), Some(b)) => Some(contains(&a, &b) || contains(&b, &a)),
+            _ => None,
+        })
+    })?;
+    connection.create_scalar_function("net_masklen", 1, flags, |ctx| {
+        Ok(network_arg(ctx, 0)?.map(|network| network.netmask()))
+    })?;
+    connection.create_scalar_function("net_family", 1, flags, |ctx| {
+        Ok(network_arg(ctx, 0)?.map(|network| if network.is_ipv4() { 4 } else { 6 }))
+    })?;
+    connection.create_scalar_function("net_network", 1, flags, |ctx| {
+        Ok(network_arg(ctx, 0)?.map(|network| network.network_address().to_string()))
+    })?;
+    connection.create_scalar_function("net_broadcast", 1, flags, |ctx| {
+        Ok(network_arg(ctx, 0)?.map(|network| match network {
+            IpNetwork::V4(network) => network.broadcast_address().to_string(),
+            IpNetwork::V6(network) => network.last_address().to_string(),
+        }))
+    })?;
+    connection.create_aggregate_function("net_collapse", 1, flags, Collapse)?;
+    Ok(())
+}
+
+fn user_function_error<E: Error + Send + Sync + 'static>(error: E) -> rusqlite::Error {
+    rusqlite::Error::UserFunctionError(Box::new(error))
+}
+
+fn network_arg(ctx: &Context<'_>, index: usize) -> rusqlite::Result<Option<IpNetwork>> {
+    match ctx.get_raw(index) {
+        ValueRef::Null => Ok(None),
+        value => IpNetwork::column_result(value)
+            .map(Some)
+            .map_err(user_function_error),
+    }
+}
+
+/// Accepts network or single address, that is converted to network with maximal netmask.
+fn network_or_address_arg(ctx: &Context<'_>, index: usize) -> rusqlite::Result<Option<IpNetwork>> {
+    if let ValueRef::Text(text) = ctx.get_raw(index) {
+        if !text.contains(&b'/') {
+            let address: IpAddr = parse_text(text).map_err(user_function_error)?;
+            return Ok(Some(IpNetwork::from(address)));
+        }
+    }
+    network_arg(ctx, index)
+}
+
+/// Returns true if network `a` contains network `b`.
+fn contains(a: &IpNetwork, b: &IpNetwork) -> bool {
+    a.is_ipv4() == b.is_ipv4() && a.netmask() <= b.netmask() && a.contains(b.network_address())
+}
+
+struct Collapse;
+
+impl Aggregate<Vec<IpNetwork>, Option<String>> for Collapse {
+    fn init(&self, _: &mut Context<'_>) -> rusqlite::Result<Vec<IpNetwork>> {
+        Ok(Vec::new())
+    }
+
+    fn step(&self, ctx: &mut Context<'_>, networks: &mut Vec<IpNetwork>) -> rusqlite::Result<()> {
+        if let Some(network) = network_arg(ctx, 0)? {
+            networks.push(network);
+        }
+        Ok(())
+    }
+
+    fn finalize(
+        &self,
+        _: &mut Context<'_>,
+        networks: Option<Vec<IpNetwork>>,
+    ) -> rusqlite::Result<Option<String>> {
+        Ok(networks.map(|networks| {
+            let collapsed: Vec<_> = IpNetwork::collapse_addresses(&networks)
+                .iter()
+                .map(|network| format!("\"{}\"", network))
+                .collect();
+            format!("[{}]", collapsed.join(","))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::{params, Connection};
+    use rusqlite::types::Value;
+    use super::register_functions;
+    use crate::{IpInterface, IpNetwork, Ipv4Network, Ipv6Network};
+
+    fn connection() -> Connection {
+        let connection = Connection::open_in_memory().unwrap();
+        register_functions(&connection).unwrap();
+        connection
+    }
+
+    fn query<T: rusqlite::types::FromSql>(connection: &Connection, sql: &str) -> T {
+        connection.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn to_sql_from_sql() {
+        let connection = connection();
+        connection
+            .execute("CREATE TABLE test (network TEXT, interface TEXT)", [])
+            .unwrap();
+
+        let network: Ipv6Network = "2001:db8::/32".parse().unwrap();
+        let interface: IpInterface = "192.168.1.10/24".parse().unwrap();
+        connection
+            .execute(
+                "INSERT INTO test VALUES (?1, ?2)",
+                params![network, interface],
+            )
+            .unwrap();
+
+        let (raw, decoded, decoded_interface): (String, IpNetwork, IpInterface) = connection
+            .query_row("SELECT network, network, interface FROM test", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!("2001:db8::/32", raw);
+        assert_eq!(IpNetwork::V6(network), decoded);
+        assert_eq!(interface, decoded_interface);
+
+        let result = connection.query_row("SELECT network FROM test", [], |row| {
+            row.get::<_, Ipv4Network>(0)
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn from_sql_invalid_type() {
+        let connection = connection();
+        let result = connection.query_row("SELECT 1", [], |row| row.get::<_, IpNetwork>(0));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn scalar_functions() {
+        let connection = connection();
+        assert!(query::<bool>(
+            &connection,
+            "SELECT net_contains('10.0.0.0/8', '10.1.2.3')"
+        ));
+        assert!(query::<bool>(
+            &connection,
+            "SELECT net_contains('10.0.0.0/8', '10.1.0.0/16')"
+        ));
+        assert!(!query::<bool>(
+            &connection,
+            "SELECT net_contains('10.0.0.0/16', '10.0.0.0/8')"
+        ));
+        assert!(!query::<bool>(
+            &connection,
+            "SELECT net_contains('::/0', '10.0.0.1')"
+        ));
+        assert!(query::<bool>(
+            &connection,
+            "SELECT net_overlaps('10.0.0.0/16', '10.0.0.0/8')"
+        ));
+        assert!(!query::<bool>(
+            &connection,
+            "SELECT net_overlaps('10.0.0.0/16', '10.1.0.0/16')"
+        ));
+        assert_eq!(
+            16,
+            query::<i64>(&connection, "SELECT net_masklen('10.0.0.0/16')")
+        );
+        assert_eq!(
+            6,
+            query::<i64>(&connection, "SELECT net_family('2001:db8::/32')")
+        );
+        assert_eq!(
+            "10.0.0.0",
+            query::<String>(&connection, "SELECT net_network('10.0.0.0/16')")
+        );
+        assert_eq!(
+            "10.0.255.255",
+            query::<String>(&connection, "SELECT net_broadcast('10.0.0.0/16')")
+        );
+        assert_eq!(
+            "2001:db8:ffff:ffff:ffff:ffff:ffff:ffff",
+            query::<String>(&connection, "SELECT net_broadcast('2001:db8::/32')")
+        );
+        assert_eq!(
+            Value::Null,
+            query::<Value>(&connection, "SELECT net_contains(NULL, '10.0.0.1')")
+        );
+
+        let result = connection.query_row("SELECT net_masklen('10.0.0.1/8')", [], |row| {
+            row.get::<_, i64>(0)
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn collapse() {
+        let connection = connection();
+        connection
+            .execute("CREATE TABLE test (network)", [])
+            .unwrap();
+        assert_eq!(
+            Value::Null,
+            query::<Value>(&connection, "SELECT net_collapse(network) FROM test")
+        );
+
+        for network in &["10.0.0.0/24", "10.0.1.0/24", "10.0.0.0/25", "2001:db8::/32"] {
+            connection
+                .execute("INSERT INTO test VALUES (?1)", [network])
+                .unwrap();
+        }
+        connection
+            .execute("INSERT INTO test VALUES (NULL)", [])
+            .unwrap();
+        assert_eq!(
+            r#"["10.0.0.0/23","2001:db8::/32"]"#,
+            query::<String>(&connection, "SELECT net_collapse(network) FROM test")
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Common parts of JSON Schema and OpenAPI schemas, that describe human-readable serde format.
+
+/// Pattern for IPv4 network in CIDR notation.
+pub const IPV4_NETWORK_PATTERN: &str = r"^(25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])(\.(25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])){3}/(3[0-2]|[12]?[0-9])$";
+
+/// Pattern for IPv6 network in CIDR notation. Address part is checked only for allowed characters.
+pub const IPV6_NETWORK_PATTERN: &str =
+    r"^[0-9A-Fa-f:.]*:[0-9A-Fa-f:.]*/(12[0-8]|1[01][0-9]|[1-9]?[0-9])$";
+
+/// Pattern for IPv4 or IPv6 network in CIDR notation.
+pub const IP_NETWORK_PATTERN: &str = r"^((25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])(\.(25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])){3}/(3[0-2]|[12]?[0-9])|[0-9A-Fa-f:.]*:[0-9A-Fa-f:.]*/(12[0-8]|1[01][0-9]|[1-9]?[0-9]))$";
+
+pub const IPV4_NETWORK_FORMAT: &str = "ipv4-network";
+pub const IPV6_NETWORK_FORMAT: &str = "ipv6-network";
+pub const IP_NETWORK_FORMAT: &str = "ip-network";
+
+pub const IPV4_NETWORK_EXAMPLE: &str = "192.168.1.0/24";
+pub const IPV6_NETWORK_EXAMPLE: &str = "2001:db8::/32";
+
+pub const IPV4_NETWORK_DESCRIPTION: &str =
+    "IPv4 network in CIDR notation, host bits must not be set.";
+pub const IPV6_NETWORK_DESCRIPTION: &str =
+    "IPv6 network in CIDR notation, host bits must not be set.";
+pub const IP_NETWORK_DESCRIPTION: &str =
+    "IPv4 or IPv6 network in CIDR notation, host bits must not be set.";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+    #[test]
+    fn examples_are_valid() {
+        assert!(IPV4_NETWORK_EXAMPLE.parse::<Ipv4Network>().is_ok());
+        assert!(IPV6_NETWORK_EXAMPLE.parse::<Ipv6Network>().is_ok());
+        assert!(IPV4_NETWORK_EXAMPLE.parse::<IpNetwork>().is_ok());
+    }
+
+    fn matches(pattern: &str, input: &str) -> bool {
+        regex::Regex::new(pattern).unwrap().is_match(input)
+    }
+
+    #[test]
+    fn ipv4_network_pattern() {
+        for input in &[
+            "0.0.0.0/0",
+            "10.0.0.0/8",
+            "192.168.1.0/24",
+            "255.255.255.255/32",
+        ] {
+            assert!(matches(IPV4_NETWORK_PATTERN, input), "{}", input);
+            assert!(matches(IP_NETWORK_PATTERN, input), "{}", input);
+        }
+        for input in &[
+            "10.0.0.0/33",
+            "10.0.0.0",
+            "10.0.0/8",
+            "256.0.0.0/8",
+            "10.0.0.01/32",
+            "::/0",
+        ] {
+            assert!(!matches(IPV4_NETWORK_PATTERN, input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn ipv6_network_pattern() {
+        for input in &["::/0", "2001:db8::/32", "::ffff:10.0.0.0/104", "::1/128"] {
+            assert!(matches(IPV6_NETWORK_PATTERN, input), "{}", input);
+            assert!(matches(IP_NETWORK_PATTERN, input), "{}", input);
+        }
+        for input in &["::/129", "2001:db8::", "2001:db8::g/32", "10.0.0.0/8"] {
+            assert!(!matches(IPV6_NETWORK_PATTERN, input), "{}", input);
+        }
+        for input in &["10.0.0.0/33", "::/129", "2001:db8::g/32", "a/8"] {
+            assert!(!matches(IP_NETWORK_PATTERN, input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn host_bits_set() {
+        // Host bits cannot be checked by pattern, so they are rejected by parsing, that is
+        // described by format and description.
+        assert!(matches(IPV4_NETWORK_PATTERN, "10.0.0.1/8"));
+        assert!("10.0.0.1/8".parse::<Ipv4Network>().is_err());
+        assert!(matches(IPV6_NETWORK_PATTERN, "2001:db8::1/32"));
+        assert!("2001:db8::1/32".parse::<Ipv6Network>().is_err());
+        assert!(matches(IP_NETWORK_PATTERN, "2001:db8::1/32"));
+        assert!("2001:db8::1/32".parse::<IpNetwork>().is_err());
+    }
+}
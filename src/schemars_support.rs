@@ -0,0 +1,89 @@
+use std::borrow::Cow;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use crate::schema_common::*;
+use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+macro_rules! json_schema_impl {
+    ($t:ident, $format:expr, $pattern:expr, $description:expr, $examples:expr) => {
+        impl JsonSchema for $t {
+            fn schema_name() -> Cow<'static, str> {
+                stringify!($t).into()
+            }
+
+            fn schema_id() -> Cow<'static, str> {
+                concat!("ip_network::", stringify!($t)).into()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                json_schema!({
+                    "type": "string",
+                    "format": $format,
+                    "pattern": $pattern,
+                    "description": $description,
+                    "examples": $examples,
+                })
+            }
+        }
+    };
+}
+
+json_schema_impl!(
+    Ipv4Network,
+    IPV4_NETWORK_FORMAT,
+    IPV4_NETWORK_PATTERN,
+    IPV4_NETWORK_DESCRIPTION,
+    [IPV4_NETWORK_EXAMPLE]
+);
+json_schema_impl!(
+    Ipv6Network,
+    IPV6_NETWORK_FORMAT,
+    IPV6_NETWORK_PATTERN,
+    IPV6_NETWORK_DESCRIPTION,
+    [IPV6_NETWORK_EXAMPLE]
+);
+json_schema_impl!(
+    IpNetwork,
+    IP_NETWORK_FORMAT,
+    IP_NETWORK_PATTERN,
+    IP_NETWORK_DESCRIPTION,
+    [IPV4_NETWORK_EXAMPLE, IPV6_NETWORK_EXAMPLE]
+);
+
+#[cfg(test)]
+mod tests {
+    use schemars::{schema_for, JsonSchema};
+    use serde_json::json;
+    use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+    #[test]
+    fn ipv4_network() {
+        let schema = schema_for!(Ipv4Network);
+        assert_eq!(Some(&json!("string")), schema.get("type"));
+        assert_eq!(Some(&json!("ipv4-network")), schema.get("format"));
+        assert_eq!(Some(&json!(["192.168.1.0/24"])), schema.get("examples"));
+    }
+
+    #[test]
+    fn ip_network_in_struct() {
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Route {
+            destination: IpNetwork,
+            source: Option<Ipv6Network>,
+        }
+
+        let schema = serde_json::to_value(schema_for!(Route)).unwrap();
+        assert_eq!(
+            json!({"$ref": "#/$defs/IpNetwork"}),
+            schema["properties"]["destination"]
+        );
+        assert_eq!(
+            json!(["192.168.1.0/24", "2001:db8::/32"]),
+            schema["$defs"]["IpNetwork"]["examples"]
+        );
+        assert_eq!(
+            json!("ipv6-network"),
+            schema["$defs"]["Ipv6Network"]["format"]
+        );
+    }
+}
@@ -0,0 +1,898 @@
+//! Selectable Serde representations of networks for `#[serde(with = "...")]` attribute.
+//!
+//! Default `Serialize` and `Deserialize` implementations use a string for human-readable formats
+//! and a compact form otherwise, so the same value looks different in JSON and in bincode.
+//! Modules in this module use the same representation for all formats:
+//!
+//! * [`as_str`] – string in CIDR notation, for example `"192.168.1.0/24"`,
+//! * [`as_tuple`] – tuple `(address, prefix)`,
+//! * [`as_struct`] – struct `{ address, prefix }`,
+//! * [`as_bytes`] – bytes with family (`4` or `6`), network address octets and prefix,
+//! * [`as_range`] – tuple `(first_address, last_address)`.
+//!
+//! Every module works with `Ipv4Network`, `Ipv6Network` and `IpNetwork` and contains `option`,
+//! `vec` and `hash_set` submodules for `Option<N>`, `Vec<N>` and `HashSet<N>` of networks.
+//!
+//! For configuration files written by hand, [`lenient`] module accepts also bare addresses, networks
+//! with host bits set and address ranges.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::collections::HashSet;
+//! use serde::{Deserialize, Serialize};
+//! use ip_network::{IpNetwork, Ipv4Network};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Route {
+//!     #[serde(with = "ip_network::serde::as_struct")]
+//!     destination: Ipv4Network,
+//!     #[serde(with = "ip_network::serde::as_str::option")]
+//!     source: Option<IpNetwork>,
+//!     #[serde(with = "ip_network::serde::as_bytes::hash_set")]
+//!     excluded: HashSet<IpNetwork>,
+//! }
+//!
+//! let route = Route {
+//!     destination: "10.0.0.0/8".parse()?,
+//!     source: None,
+//!     excluded: HashSet::new(),
+//! };
+//! assert_eq!(
+//!     serde_json::to_string(&route)?,
+//!     r#"{"destination":{"address":"10.0.0.0","prefix":8},"source":null,"excluded":[]}"#
+//! );
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use serde::de::{self, DeserializeOwned, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::{IpNetwork, IpNetworkParseError, Ipv4Network, Ipv6Network};
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for crate::Ipv4Network {}
+    impl Sealed for crate::Ipv6Network {}
+    impl Sealed for crate::IpNetwork {}
+}
+
+/// Network types supported by representation modules, implemented for `Ipv4Network`,
+/// `Ipv6Network` and `IpNetwork`.
+pub trait Network:
+    private::Sealed + Sized + fmt::Display + FromStr<Err = IpNetworkParseError>
+{
+    #[doc(hidden)]
+    type Address: Serialize + DeserializeOwned + Copy + Into<IpAddr>;
+
+    #[doc(hidden)]
+    fn address(&self) -> Self::Address;
+
+    #[doc(hidden)]
+    fn prefix(&self) -> u8;
+
+    #[doc(hidden)]
+    fn last_address(&self) -> Self::Address;
+
+    #[doc(hidden)]
+    fn from_parts<E: de::Error>(address: IpAddr, prefix: u8) -> Result<Self, E>;
+
+    #[doc(hidden)]
+    fn from_ip_network<E: de::Error>(network: IpNetwork) -> Result<Self, E>;
+
+    #[doc(hidden)]
+    fn collapse(networks: &[Self]) -> Vec<Self>;
+}
+
+impl Network for Ipv4Network {
+    type Address = Ipv4Addr;
+
+    fn address(&self) -> Ipv4Addr {
+        self.network_address()
+    }
+
+    fn prefix(&self) -> u8 {
+        self.netmask()
+    }
+
+    fn last_address(&self) -> Ipv4Addr {
+        self.broadcast_address()
+    }
+
+    fn from_parts<E: de::Error>(address: IpAddr, prefix: u8) -> Result<Self, E> {
+        match address {
+            IpAddr::V4(address) => Self::new(address, prefix).map_err(E::custom),
+            IpAddr::V6(_) => Err(E::custom("expected IPv4 address")),
+        }
+    }
+
+    fn from_ip_network<E: de::Error>(network: IpNetwork) -> Result<Self, E> {
+        match network {
+            IpNetwork::V4(network) => Ok(network),
+            IpNetwork::V6(_) => Err(E::custom("expected IPv4 network")),
+        }
+    }
+
+    fn collapse(networks: &[Self]) -> Vec<Self> {
+        Self::collapse_addresses(networks)
+    }
+}
+
+impl Network for Ipv6Network {
+    type Address = Ipv6Addr;
+
+    fn address(&self) -> Ipv6Addr {
+        self.network_address()
+    }
+
+    fn prefix(&self) -> u8 {
+        self.netmask()
+    }
+
+    fn last_address(&self) -> Ipv6Addr {
+        Ipv6Network::last_address(self)
+    }
+
+    fn from_parts<E: de::Error>(address: IpAddr, prefix: u8) -> Result<Self, E> {
+        match address {
+            IpAddr::V6(address) => Self::new(address, prefix).map_err(E::custom),
+            IpAddr::V4(_) => Err(E::custom("expected IPv6 address")),
+        }
+    }
+
+    fn from_ip_network<E: de::Error>(network: IpNetwork) -> Result<Self, E> {
+        match network {
+            IpNetwork::V6(network) => Ok(network),
+            IpNetwork::V4(_) => Err(E::custom("expected IPv6 network")),
+        }
+    }
+
+    fn collapse(networks: &[Self]) -> Vec<Self> {
+        Self::collapse_addresses(networks)
+    }
+}
+
+impl Network for IpNetwork {
+    type Address = IpAddr;
+
+    fn address(&self) -> IpAddr {
+        self.network_address()
+    }
+
+    fn prefix(&self) -> u8 {
+        self.netmask()
+    }
+
+    fn last_address(&self) -> IpAddr {
+        match self {
+            IpNetwork::V4(network) => network.broadcast_address().into(),
+            IpNetwork::V6(network) => network.last_address().into(),
+        }
+    }
+
+    fn from_parts<E: de::Error>(address: IpAddr, prefix: u8) -> Result<Self, E> {
+        Self::new(address, prefix).map_err(E::custom)
+    }
+
+    fn from_ip_network<E: de::Error>(network: IpNetwork) -> Result<Self, E> {
+        Ok(network)
+    }
+
+    fn collapse(networks: &[Self]) -> Vec<Self> {
+        Self::collapse_addresses(networks)
+    }
+}
+
+trait Representation {
+    fn serialize<N: Network, S: Serializer>(network: &N, serializer: S) -> Result<S::Ok, S::Error>;
+
+    fn deserialize<'de, N: Network, D: Deserializer<'de>>(deserializer: D) -> Result<N, D::Error>;
+}
+
+struct Ser<'a, R, N>(&'a N, PhantomData<R>);
+
+impl<R: Representation, N: Network> Serialize for Ser<'_, R, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        R::serialize(self.0, serializer)
+    }
+}
+
+struct De<R, N>(N, PhantomData<R>);
+
+impl<'de, R: Representation, N: Network> Deserialize<'de> for De<R, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        R::deserialize(deserializer).map(|network| De(network, PhantomData))
+    }
+}
+
+fn serialize_option<R, N, S>(network: &Option<N>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    R: Representation,
+    N: Network,
+    S: Serializer,
+{
+    network
+        .as_ref()
+        .map(|network| Ser::<R, N>(network, PhantomData))
+        .serialize(serializer)
+}
+
+fn deserialize_option<'de, R, N, D>(deserializer: D) -> Result<Option<N>, D::Error>
+where
+    R: Representation,
+    N: Network,
+    D: Deserializer<'de>,
+{
+    Ok(Option::<De<R, N>>::deserialize(deserializer)?.map(|network| network.0))
+}
+
+fn serialize_seq<'a, R, N, I, S>(networks: I, serializer: S) -> Result<S::Ok, S::Error>
+where
+    R: Representation,
+    N: Network + 'a,
+    I: IntoIterator<Item = &'a N>,
+    S: Serializer,
+{
+    serializer.collect_seq(
+        networks
+            .into_iter()
+            .map(|network| Ser::<R, N>(network, PhantomData)),
+    )
+}
+
+fn deserialize_seq<'de, R, N, C, D>(deserializer: D) -> Result<C, D::Error>
+where
+    R: Representation,
+    N: Network,
+    C: FromIterator<N>,
+    D: Deserializer<'de>,
+{
+    Ok(Vec::<De<R, N>>::deserialize(deserializer)?
+        .into_iter()
+        .map(|network| network.0)
+        .collect())
+}
+
+macro_rules! representation_module {
+    ($repr:ident) => {
+        use serde::{Deserializer, Serializer};
+        use crate::serde::{$repr, Network, Representation};
+
+        /// Serializes network.
+        pub fn serialize<N, S>(network: &N, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            N: Network,
+            S: Serializer,
+        {
+            $repr::serialize(network, serializer)
+        }
+
+        /// Deserializes network.
+        pub fn deserialize<'de, N, D>(deserializer: D) -> Result<N, D::Error>
+        where
+            N: Network,
+            D: Deserializer<'de>,
+        {
+            $repr::deserialize(deserializer)
+        }
+
+        /// The same representation for `Option` of network.
+        pub mod option {
+            use serde::{Deserializer, Serializer};
+            use crate::serde::{$repr, Network};
+
+            /// Serializes optional network.
+            pub fn serialize<N, S>(network: &Option<N>, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                N: Network,
+                S: Serializer,
+            {
+                crate::serde::serialize_option::<$repr, _, _>(network, serializer)
+            }
+
+            /// Deserializes optional network.
+            pub fn deserialize<'de, N, D>(deserializer: D) -> Result<Option<N>, D::Error>
+            where
+                N: Network,
+                D: Deserializer<'de>,
+            {
+                crate::serde::deserialize_option::<$repr, _, _>(deserializer)
+            }
+        }
+
+        /// The same representation for `Vec` of networks.
+        pub mod vec {
+            use serde::{Deserializer, Serializer};
+            use crate::serde::{$repr, Network};
+
+            /// Serializes networks.
+            pub fn serialize<N, S>(networks: &[N], serializer: S) -> Result<S::Ok, S::Error>
+            where
+                N: Network,
+                S: Serializer,
+            {
+                crate::serde::serialize_seq::<$repr, _, _, _>(networks, serializer)
+            }
+
+            /// Deserializes networks.
+            pub fn deserialize<'de, N, D>(deserializer: D) -> Result<Vec<N>, D::Error>
+            where
+                N: Network,
+                D: Deserializer<'de>,
+            {
+                crate::serde::deserialize_seq::<$repr, _, _, _>(deserializer)
+            }
+        }
+
+        /// The same representation for `HashSet` of networks.
+        pub mod hash_set {
+            use std::collections::HashSet;
+            use std::hash::{BuildHasher, Hash};
+            use serde::{Deserializer, Serializer};
+            use crate::serde::{$repr, Network};
+
+            /// Serializes set of networks.
+            pub fn serialize<N, H, S>(
+                networks: &HashSet<N, H>,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error>
+            where
+                N: Network,
+                S: Serializer,
+            {
+                crate::serde::serialize_seq::<$repr, _, _, _>(networks, serializer)
+            }
+
+            /// Deserializes set of networks.
+            pub fn deserialize<'de, N, H, D>(deserializer: D) -> Result<HashSet<N, H>, D::Error>
+            where
+                N: Network + Eq + Hash,
+                H: BuildHasher + Default,
+                D: Deserializer<'de>,
+            {
+                crate::serde::deserialize_seq::<$repr, _, _, _>(deserializer)
+            }
+        }
+    };
+}
+
+struct AsStr;
+
+impl Representation for AsStr {
+    fn serialize<N: Network, S: Serializer>(network: &N, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(network)
+    }
+
+    fn deserialize<'de, N: Network, D: Deserializer<'de>>(deserializer: D) -> Result<N, D::Error> {
+        struct StrVisitor<N>(PhantomData<N>);
+
+        impl<'de, N: Network> Visitor<'de> for StrVisitor<N> {
+            type Value = N;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("IP network in CIDR notation")
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+                s.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(StrVisitor(PhantomData))
+    }
+}
+
+struct AsTuple;
+
+impl Representation for AsTuple {
+    fn serialize<N: Network, S: Serializer>(network: &N, serializer: S) -> Result<S::Ok, S::Error> {
+        (network.address(), network.prefix()).serialize(serializer)
+    }
+
+    fn deserialize<'de, N: Network, D: Deserializer<'de>>(deserializer: D) -> Result<N, D::Error> {
+        let (address, prefix) = <(N::Address, u8)>::deserialize(deserializer)?;
+        N::from_parts(address.into(), prefix)
+    }
+}
+
+struct AsStruct;
+
+const STRUCT_NAME: &str = "Network";
+const STRUCT_FIELDS: &[&str] = &["address", "prefix"];
+
+enum Field {
+    Address,
+    Prefix,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl<'de> Visitor<'de> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("`address` or `prefix`")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                match value {
+                    "address" => Ok(Field::Address),
+                    "prefix" => Ok(Field::Prefix),
+                    _ => Err(E::unknown_field(value, STRUCT_FIELDS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+impl Representation for AsStruct {
+    fn serialize<N: Network, S: Serializer>(network: &N, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct(STRUCT_NAME, 2)?;
+        state.serialize_field("address", &network.address())?;
+        state.serialize_field("prefix", &network.prefix())?;
+        state.end()
+    }
+
+    fn deserialize<'de, N: Network, D: Deserializer<'de>>(deserializer: D) -> Result<N, D::Error> {
+        struct StructVisitor<N>(PhantomData<N>);
+
+        impl<'de, N: Network> Visitor<'de> for StructVisitor<N> {
+            type Value = N;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("struct Network")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let address: N::Address = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let prefix: u8 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                N::from_parts(address.into(), prefix)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut address: Option<N::Address> = None;
+                let mut prefix = None;
+                while let Some(key) = map.next_key()? {
+                    match key {
+                        Field::Address => {
+                            if address.is_some() {
+                                return Err(de::Error::duplicate_field("address"));
+                            }
+                            address = Some(map.next_value()?);
+                        }
+                        Field::Prefix => {
+                            if prefix.is_some() {
+                                return Err(de::Error::duplicate_field("prefix"));
+                            }
+                            prefix = Some(map.next_value()?);
+                        }
+                    }
+                }
+                let address = address.ok_or_else(|| de::Error::missing_field("address"))?;
+                let prefix = prefix.ok_or_else(|| de::Error::missing_field("prefix"))?;
+                N::from_parts(address.into(), prefix)
+            }
+        }
+
+        deserializer.deserialize_struct(STRUCT_NAME, STRUCT_FIELDS, StructVisitor(PhantomData))
+    }
+}
+
+struct AsBytes;
+
+const IPV4_BYTES_LENGTH: usize = 6;
+const IPV6_BYTES_LENGTH: usize = 18;
+
+impl Representation for AsBytes {
+    fn serialize<N: Network, S: Serializer>(network: &N, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = [0; IPV6_BYTES_LENGTH];
+        let length = match network.address().into() {
+            IpAddr::V4(address) => {
+                bytes[0] = 4;
+                bytes[1..5].copy_from_slice(&address.octets());
+                IPV4_BYTES_LENGTH
+            }
+            IpAddr::V6(address) => {
+                bytes[0] = 6;
+                bytes[1..17].copy_from_slice(&address.octets());
+                IPV6_BYTES_LENGTH
+            }
+        };
+        bytes[length - 1] = network.prefix();
+        serializer.serialize_bytes(&bytes[..length])
+    }
+
+    fn deserialize<'de, N: Network, D: Deserializer<'de>>(deserializer: D) -> Result<N, D::Error> {
+        struct BytesVisitor<N>(PhantomData<N>);
+
+        impl<N: Network> BytesVisitor<N> {
+            fn decode<E: de::Error>(&self, bytes: &[u8]) -> Result<N, E> {
+                let address = match (bytes.first(), bytes.len()) {
+                    (Some(4), IPV4_BYTES_LENGTH) => {
+                        IpAddr::V4(Ipv4Addr::new(bytes[1], bytes[2], bytes[3], bytes[4]))
+                    }
+                    (Some(6), IPV6_BYTES_LENGTH) => {
+                        let mut octets = [0; 16];
+                        octets.copy_from_slice(&bytes[1..17]);
+                        IpAddr::V6(Ipv6Addr::from(octets))
+                    }
+                    (Some(4), length) | (Some(6), length) => {
+                        return Err(E::invalid_length(length, self))
+                    }
+                    _ => return Err(E::invalid_value(Unexpected::Bytes(bytes), self)),
+                };
+                N::from_parts(address, bytes[bytes.len() - 1])
+            }
+        }
+
+        impl<'de, N: Network> Visitor<'de> for BytesVisitor<N> {
+            type Value = N;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("IP network as family, address octets and prefix bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+                self.decode(bytes)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bytes = [0; IPV6_BYTES_LENGTH];
+                let mut length = 0;
+                while let Some(byte) = seq.next_element()? {
+                    if length == IPV6_BYTES_LENGTH {
+                        return Err(de::Error::invalid_length(length + 1, &self));
+                    }
+                    bytes[length] = byte;
+                    length += 1;
+                }
+                self.decode(&bytes[..length])
+            }
+        }
+
+        deserializer.deserialize_bytes(BytesVisitor(PhantomData))
+    }
+}
+
+struct AsRange;
+
+/// Returns prefix of network, that contains exactly addresses from `first` to `last`.
+fn range_prefix(first: IpAddr, last: IpAddr) -> Option<u8> {
+    let (first, last, length) = match (first, last) {
+        (IpAddr::V4(first), IpAddr::V4(last)) => (
+            u128::from(u32::from(first)),
+            u128::from(u32::from(last)),
+            Ipv4Network::LENGTH,
+        ),
+        (IpAddr::V6(first), IpAddr::V6(last)) => {
+            (u128::from(first), u128::from(last), Ipv6Network::LENGTH)
+        }
+        _ => return None,
+    };
+    let host_bits = first ^ last;
+    if host_bits & host_bits.wrapping_add(1) != 0 || first & host_bits != 0 {
+        return None;
+    }
+    Some(length - host_bits.count_ones() as u8)
+}
+
+impl Representation for AsRange {
+    fn serialize<N: Network, S: Serializer>(network: &N, serializer: S) -> Result<S::Ok, S::Error> {
+        (network.address(), network.last_address()).serialize(serializer)
+    }
+
+    fn deserialize<'de, N: Network, D: Deserializer<'de>>(deserializer: D) -> Result<N, D::Error> {
+        let (first, last) = <(N::Address, N::Address)>::deserialize(deserializer)?;
+        let (first, last) = (first.into(), last.into());
+        match range_prefix(first, last) {
+            Some(prefix) => N::from_parts(first, prefix),
+            None => Err(de::Error::invalid_value(
+                Unexpected::Other("address range"),
+                &"range of all addresses of a single network",
+            )),
+        }
+    }
+}
+
+pub mod lenient;
+
+/// Network as string in CIDR notation, for example `"192.168.1.0/24"`.
+pub mod as_str {
+    representation_module!(AsStr);
+}
+
+/// Network as tuple `(address, prefix)`.
+pub mod as_tuple {
+    representation_module!(AsTuple);
+}
+
+/// Network as struct `{ address, prefix }`.
+pub mod as_struct {
+    representation_module!(AsStruct);
+}
+
+/// Network as bytes: family (`4` or `6`), network address octets and prefix.
+pub mod as_bytes {
+    representation_module!(AsBytes);
+}
+
+/// Network as tuple `(first_address, last_address)`. Deserialization fails when the range
+/// doesn't cover exactly one network.
+pub mod as_range {
+    representation_module!(AsRange);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use serde::{Deserialize, Serialize};
+    use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Readable, Token};
+    use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Networks {
+        #[serde(with = "super::as_str")]
+        string: Ipv4Network,
+        #[serde(with = "super::as_tuple")]
+        tuple: IpNetwork,
+        #[serde(with = "super::as_struct")]
+        structure: Ipv6Network,
+        #[serde(with = "super::as_bytes")]
+        bytes: IpNetwork,
+        #[serde(with = "super::as_range")]
+        range: Ipv4Network,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Collections {
+        #[serde(with = "super::as_str::option")]
+        option: Option<IpNetwork>,
+        #[serde(with = "super::as_bytes::vec")]
+        vec: Vec<Ipv4Network>,
+        #[serde(with = "super::as_str::hash_set")]
+        hash_set: HashSet<IpNetwork>,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Range(#[serde(with = "super::as_range")] IpNetwork);
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Bytes(#[serde(with = "super::as_bytes")] Ipv4Network);
+
+    fn networks() -> Networks {
+        Networks {
+            string: "10.0.0.0/8".parse().unwrap(),
+            tuple: "10.0.0.0/8".parse().unwrap(),
+            structure: "2001:db8::/32".parse().unwrap(),
+            bytes: "10.0.0.0/8".parse().unwrap(),
+            range: "10.0.0.0/8".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn representations() {
+        let tokens = [
+            Token::Struct {
+                name: "Networks",
+                len: 5,
+            },
+            Token::Str("string"),
+            Token::Str("10.0.0.0/8"),
+            Token::Str("tuple"),
+            Token::Tuple { len: 2 },
+            Token::Str("10.0.0.0"),
+            Token::U8(8),
+            Token::TupleEnd,
+            Token::Str("structure"),
+            Token::Struct {
+                name: "Network",
+                len: 2,
+            },
+            Token::Str("address"),
+            Token::Str("2001:db8::"),
+            Token::Str("prefix"),
+            Token::U8(32),
+            Token::StructEnd,
+            Token::Str("bytes"),
+            Token::Bytes(&[4, 10, 0, 0, 0, 8]),
+            Token::Str("range"),
+            Token::Tuple { len: 2 },
+            Token::Str("10.0.0.0"),
+            Token::Str("10.255.255.255"),
+            Token::TupleEnd,
+            Token::StructEnd,
+        ];
+        assert_tokens(&networks().readable(), &tokens);
+    }
+
+    #[test]
+    fn representations_compact() {
+        // String and bytes representations don't depend on format.
+        let tokens = [
+            Token::Struct {
+                name: "Networks",
+                len: 5,
+            },
+            Token::Str("string"),
+            Token::Str("10.0.0.0/8"),
+            Token::Str("tuple"),
+            Token::Tuple { len: 2 },
+            Token::NewtypeVariant {
+                name: "IpAddr",
+                variant: "V4",
+            },
+            Token::Tuple { len: 4 },
+            Token::U8(10),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::TupleEnd,
+            Token::U8(8),
+            Token::TupleEnd,
+            Token::Str("structure"),
+            Token::Struct {
+                name: "Network",
+                len: 2,
+            },
+            Token::Str("address"),
+            Token::Tuple { len: 16 },
+            Token::U8(0x20),
+            Token::U8(0x01),
+            Token::U8(0x0d),
+            Token::U8(0xb8),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::TupleEnd,
+            Token::Str("prefix"),
+            Token::U8(32),
+            Token::StructEnd,
+            Token::Str("bytes"),
+            Token::Bytes(&[4, 10, 0, 0, 0, 8]),
+            Token::Str("range"),
+            Token::Tuple { len: 2 },
+            Token::Tuple { len: 4 },
+            Token::U8(10),
+            Token::U8(0),
+            Token::U8(0),
+            Token::U8(0),
+            Token::TupleEnd,
+            Token::Tuple { len: 4 },
+            Token::U8(10),
+            Token::U8(255),
+            Token::U8(255),
+            Token::U8(255),
+            Token::TupleEnd,
+            Token::TupleEnd,
+            Token::StructEnd,
+        ];
+        assert_tokens(&networks().compact(), &tokens);
+    }
+
+    #[test]
+    fn collections() {
+        let collections = Collections {
+            option: None,
+            vec: vec!["10.0.0.0/8".parse().unwrap(), "0.0.0.0/0".parse().unwrap()],
+            hash_set: vec!["2001:db8::/32".parse().unwrap()].into_iter().collect(),
+        };
+        assert_tokens(
+            &collections,
+            &[
+                Token::Struct {
+                    name: "Collections",
+                    len: 3,
+                },
+                Token::Str("option"),
+                Token::None,
+                Token::Str("vec"),
+                Token::Seq { len: Some(2) },
+                Token::Bytes(&[4, 10, 0, 0, 0, 8]),
+                Token::Bytes(&[4, 0, 0, 0, 0, 0]),
+                Token::SeqEnd,
+                Token::Str("hash_set"),
+                Token::Seq { len: Some(1) },
+                Token::Str("2001:db8::/32"),
+                Token::SeqEnd,
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn range_errors() {
+        assert_de_tokens_error::<Readable<Range>>(
+            &[
+                Token::NewtypeStruct { name: "Range" },
+                Token::Tuple { len: 2 },
+                Token::Str("10.0.0.0"),
+                Token::Str("10.0.0.2"),
+                Token::TupleEnd,
+            ],
+            "invalid value: address range, expected range of all addresses of a single network",
+        );
+        assert_de_tokens_error::<Readable<Range>>(
+            &[
+                Token::NewtypeStruct { name: "Range" },
+                Token::Tuple { len: 2 },
+                Token::Str("10.0.0.1"),
+                Token::Str("10.0.0.2"),
+                Token::TupleEnd,
+            ],
+            "invalid value: address range, expected range of all addresses of a single network",
+        );
+        assert_de_tokens_error::<Readable<Range>>(
+            &[
+                Token::NewtypeStruct { name: "Range" },
+                Token::Tuple { len: 2 },
+                Token::Str("10.0.0.0"),
+                Token::Str("::"),
+                Token::TupleEnd,
+            ],
+            "invalid value: address range, expected range of all addresses of a single network",
+        );
+    }
+
+    #[test]
+    fn range_prefix() {
+        let prefix = |first: &str, last: &str| {
+            super::range_prefix(first.parse().unwrap(), last.parse().unwrap())
+        };
+        assert_eq!(Some(0), prefix("0.0.0.0", "255.255.255.255"));
+        assert_eq!(Some(32), prefix("10.0.0.1", "10.0.0.1"));
+        assert_eq!(
+            Some(0),
+            prefix("::", "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff")
+        );
+        assert_eq!(Some(128), prefix("::1", "::1"));
+        assert_eq!(None, prefix("10.0.0.2", "10.0.0.1"));
+    }
+
+    #[test]
+    fn bytes_errors() {
+        assert_de_tokens_error::<Bytes>(
+            &[
+                Token::NewtypeStruct { name: "Bytes" },
+                Token::Bytes(&[4, 10, 0, 0, 0]),
+            ],
+            "invalid length 5, expected IP network as family, address octets and prefix bytes",
+        );
+        assert_de_tokens_error::<Bytes>(
+            &[
+                Token::NewtypeStruct { name: "Bytes" },
+                Token::Bytes(&[6; 18]),
+            ],
+            "expected IPv4 address",
+        );
+        assert_de_tokens_error::<Bytes>(
+            &[
+                Token::NewtypeStruct { name: "Bytes" },
+                Token::Bytes(&[4, 10, 0, 0, 0, 33]),
+            ],
+            "invalid netmask 33, maximum is 32",
+        );
+    }
+}
@@ -0,0 +1,587 @@
+//! Forgiving deserialization of networks for configuration files written by hand.
+//!
+//! Besides network in CIDR notation, deserializers in this module accept:
+//!
+//! * bare address, for example `"10.0.0.1"`, that is converted to network with maximal prefix,
+//! * network with host bits set, for example `"10.0.0.1/24"`, that is truncated to `10.0.0.0/24`
+//!   and reported as [`LenientWarning`] when deserialized through [`Lenient`],
+//! * address range, for example `"10.0.0.0-10.0.0.255"`. Single network must be covered exactly
+//!   by the range, lists accept any range.
+//!
+//! Lists deserialized by [`vec`](mod@vec) module are collapsed, so overlapping and adjacent networks
+//! are merged. Networks are always serialized as strings in CIDR notation.
+//!
+//! Functions in this module are intended for `#[serde(with = "...")]` attribute and silently
+//! discard warnings. Wrap field in [`Lenient`] to get warnings together with the value.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde::Deserialize;
+//! use ip_network::{IpNetwork, Ipv4Network};
+//! use ip_network::serde::lenient::{Lenient, LenientWarning};
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     #[serde(with = "ip_network::serde::lenient")]
+//!     gateway: Ipv4Network,
+//!     allowed: Lenient<Vec<IpNetwork>>,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{
+//!     "gateway": "10.0.0.1",
+//!     "allowed": ["10.0.0.1/24", "10.0.1.0-10.0.1.255", "::1"]
+//! }"#)?;
+//! assert_eq!(config.gateway, "10.0.0.1/32".parse::<Ipv4Network>()?);
+//! assert_eq!(
+//!     config.allowed.value,
+//!     vec!["10.0.0.0/23".parse::<IpNetwork>()?, "::1/128".parse()?]
+//! );
+//! assert_eq!(
+//!     config.allowed.warnings,
+//!     vec![LenientWarning::HostBitsTruncated {
+//!         input: "10.0.0.1/24".to_owned(),
+//!         truncated: "10.0.0.0/24".parse()?,
+//!     }]
+//! );
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::net::IpAddr;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::{IpNetwork, IpNetworkError, IpNetworkParseError, Ipv4Network, Ipv6Network};
+use super::{private, range_prefix, Network};
+
+/// Warning recorded when lenient deserialization changed the input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum LenientWarning {
+    /// Network address had host bits set, that were truncated.
+    HostBitsTruncated {
+        /// Original input.
+        input: String,
+        /// Network with truncated host bits.
+        truncated: IpNetwork,
+    },
+}
+
+impl fmt::Display for LenientWarning {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LenientWarning::HostBitsTruncated {
+                ref input,
+                ref truncated,
+            } => write!(
+                fmt,
+                "network `{}` has host bits set, truncated to `{}`",
+                input, truncated
+            ),
+        }
+    }
+}
+
+/// Value deserialized leniently together with warnings about changed input.
+///
+/// Implemented for network types, `Option` of network and `Vec` of networks, that behave like
+/// functions from this module, [`option`](mod@option) and [`vec`](mod@vec) module. Value is
+/// serialized as strings in CIDR notation, warnings are not serialized.
+///
+/// # Examples
+///
+/// ```
+/// use ip_network::Ipv4Network;
+/// use ip_network::serde::lenient::Lenient;
+///
+/// let network: Lenient<Ipv4Network> = serde_json::from_str(r#""10.0.0.1/24""#)?;
+/// assert_eq!(network.value, "10.0.0.0/24".parse::<Ipv4Network>()?);
+/// assert_eq!(network.warnings.len(), 1);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lenient<T> {
+    /// Deserialized value.
+    pub value: T,
+    /// Warnings produced when deserializing value.
+    pub warnings: Vec<LenientWarning>,
+}
+
+/// Types supported by [`Lenient`], implemented for network types, `Option` of network and `Vec`
+/// of networks.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait LenientValue: private::Sealed + Sized {
+    #[doc(hidden)]
+    fn deserialize_lenient<'de, D: Deserializer<'de>>(
+        deserializer: D,
+        warnings: &mut Vec<LenientWarning>,
+    ) -> Result<Self, D::Error>;
+
+    #[doc(hidden)]
+    fn serialize_lenient<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+impl<N: Network> LenientValue for N {
+    fn deserialize_lenient<'de, D: Deserializer<'de>>(
+        deserializer: D,
+        warnings: &mut Vec<LenientWarning>,
+    ) -> Result<Self, D::Error> {
+        deserialize_network(deserializer, warnings)
+    }
+
+    fn serialize_lenient<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<N: Network> private::Sealed for Option<N> {}
+
+impl<N: Network> LenientValue for Option<N> {
+    fn deserialize_lenient<'de, D: Deserializer<'de>>(
+        deserializer: D,
+        warnings: &mut Vec<LenientWarning>,
+    ) -> Result<Self, D::Error> {
+        option::deserialize_option(deserializer, warnings)
+    }
+
+    fn serialize_lenient<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        option::serialize(self, serializer)
+    }
+}
+
+impl<N: Network> private::Sealed for Vec<N> {}
+
+impl<N: Network> LenientValue for Vec<N> {
+    fn deserialize_lenient<'de, D: Deserializer<'de>>(
+        deserializer: D,
+        warnings: &mut Vec<LenientWarning>,
+    ) -> Result<Self, D::Error> {
+        vec::deserialize_vec(deserializer, warnings)
+    }
+
+    fn serialize_lenient<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        vec::serialize(self, serializer)
+    }
+}
+
+impl<'de, T: LenientValue> Deserialize<'de> for Lenient<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut warnings = vec![];
+        let value = T::deserialize_lenient(deserializer, &mut warnings)?;
+        Ok(Lenient { value, warnings })
+    }
+}
+
+impl<T: LenientValue> Serialize for Lenient<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize_lenient(serializer)
+    }
+}
+
+/// Parsed input, range is kept as it can cover multiple networks.
+enum Entry {
+    Network(IpNetwork),
+    Range(IpAddr, IpAddr),
+}
+
+fn parse_entry<E: de::Error>(input: &str, warnings: &mut Vec<LenientWarning>) -> Result<Entry, E> {
+    let input = input.trim();
+    if let Some(position) = input.find('-') {
+        let first: IpAddr = input[..position].trim().parse().map_err(E::custom)?;
+        let last: IpAddr = input[position + 1..].trim().parse().map_err(E::custom)?;
+        if first.is_ipv4() != last.is_ipv4() || first > last {
+            return Err(E::invalid_value(
+                de::Unexpected::Str(input),
+                &"valid address range",
+            ));
+        }
+        Ok(Entry::Range(first, last))
+    } else if input.contains('/') {
+        match input.parse::<IpNetwork>() {
+            Ok(network) => Ok(Entry::Network(network)),
+            Err(IpNetworkParseError::IpNetworkError {
+                error: IpNetworkError::HostBitsSet { .. },
+                ..
+            }) => {
+                let truncated = IpNetwork::from_str_truncate(input).map_err(E::custom)?;
+                warnings.push(LenientWarning::HostBitsTruncated {
+                    input: input.to_owned(),
+                    truncated,
+                });
+                Ok(Entry::Network(truncated))
+            }
+            Err(error) => Err(E::custom(error)),
+        }
+    } else {
+        let address: IpAddr = input.parse().map_err(E::custom)?;
+        Ok(Entry::Network(IpNetwork::from(address)))
+    }
+}
+
+fn parse_network<N: Network, E: de::Error>(
+    input: &str,
+    warnings: &mut Vec<LenientWarning>,
+) -> Result<N, E> {
+    match parse_entry(input, warnings)? {
+        Entry::Network(network) => N::from_ip_network(network),
+        Entry::Range(first, last) => match range_prefix(first, last) {
+            Some(prefix) => N::from_parts(first, prefix),
+            None => Err(E::invalid_value(
+                de::Unexpected::Str(input),
+                &"range of all addresses of a single network",
+            )),
+        },
+    }
+}
+
+fn push_entry<N: Network, E: de::Error>(
+    networks: &mut Vec<N>,
+    input: &str,
+    warnings: &mut Vec<LenientWarning>,
+) -> Result<(), E> {
+    match parse_entry(input, warnings)? {
+        Entry::Network(network) => networks.push(N::from_ip_network(network)?),
+        Entry::Range(IpAddr::V4(first), IpAddr::V4(last)) => {
+            for network in Ipv4Network::summarize_address_range(first, last) {
+                networks.push(N::from_ip_network(network.into())?);
+            }
+        }
+        Entry::Range(IpAddr::V6(first), IpAddr::V6(last)) => {
+            for network in Ipv6Network::summarize_address_range(first, last) {
+                networks.push(N::from_ip_network(network.into())?);
+            }
+        }
+        Entry::Range(..) => unreachable!("families of range are checked when parsing"),
+    }
+    Ok(())
+}
+
+struct NetworkVisitor<'a, N>(&'a mut Vec<LenientWarning>, PhantomData<N>);
+
+impl<'de, N: Network> Visitor<'de> for NetworkVisitor<'_, N> {
+    type Value = N;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("IP network, address or address range")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+        parse_network(s, self.0)
+    }
+}
+
+/// Serializes network as string in CIDR notation.
+pub fn serialize<N, S>(network: &N, serializer: S) -> Result<S::Ok, S::Error>
+where
+    N: Network,
+    S: Serializer,
+{
+    serializer.collect_str(network)
+}
+
+/// Deserializes network from network, address or address range.
+pub fn deserialize<'de, N, D>(deserializer: D) -> Result<N, D::Error>
+where
+    N: Network,
+    D: Deserializer<'de>,
+{
+    deserialize_network(deserializer, &mut vec![])
+}
+
+fn deserialize_network<'de, N, D>(
+    deserializer: D,
+    warnings: &mut Vec<LenientWarning>,
+) -> Result<N, D::Error>
+where
+    N: Network,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(NetworkVisitor(warnings, PhantomData))
+}
+
+/// Lenient deserialization of `Option` of network.
+pub mod option {
+    use std::fmt;
+    use std::marker::PhantomData;
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+    use crate::serde::Network;
+    use super::LenientWarning;
+
+    /// Serializes optional network as string in CIDR notation.
+    pub fn serialize<N, S>(network: &Option<N>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        N: Network,
+        S: Serializer,
+    {
+        match network {
+            Some(network) => serializer.collect_str(network),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserializes optional network from network, address or address range.
+    pub fn deserialize<'de, N, D>(deserializer: D) -> Result<Option<N>, D::Error>
+    where
+        N: Network,
+        D: Deserializer<'de>,
+    {
+        deserialize_option(deserializer, &mut vec![])
+    }
+
+    pub(super) fn deserialize_option<'de, N, D>(
+        deserializer: D,
+        warnings: &mut Vec<LenientWarning>,
+    ) -> Result<Option<N>, D::Error>
+    where
+        N: Network,
+        D: Deserializer<'de>,
+    {
+        struct OptionVisitor<'a, N>(&'a mut Vec<LenientWarning>, PhantomData<N>);
+
+        impl<'de, N: Network> Visitor<'de> for OptionVisitor<'_, N> {
+            type Value = Option<N>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("optional IP network, address or address range")
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(None)
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(None)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                super::deserialize_network(deserializer, self.0).map(Some)
+            }
+        }
+
+        deserializer.deserialize_option(OptionVisitor(warnings, PhantomData))
+    }
+}
+
+/// Lenient deserialization of list of networks, that is collapsed on load.
+pub mod vec {
+    use std::fmt;
+    use std::marker::PhantomData;
+    use serde::de::{SeqAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+    use crate::serde::Network;
+    use super::LenientWarning;
+
+    /// Serializes networks as strings in CIDR notation.
+    pub fn serialize<N, S>(networks: &[N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        N: Network,
+        S: Serializer,
+    {
+        serializer.collect_seq(networks.iter().map(|network| network.to_string()))
+    }
+
+    /// Deserializes list of networks, addresses or address ranges and collapses them.
+    pub fn deserialize<'de, N, D>(deserializer: D) -> Result<Vec<N>, D::Error>
+    where
+        N: Network,
+        D: Deserializer<'de>,
+    {
+        deserialize_vec(deserializer, &mut vec![])
+    }
+
+    pub(super) fn deserialize_vec<'de, N, D>(
+        deserializer: D,
+        warnings: &mut Vec<LenientWarning>,
+    ) -> Result<Vec<N>, D::Error>
+    where
+        N: Network,
+        D: Deserializer<'de>,
+    {
+        struct SeqVisitor<'a, N>(&'a mut Vec<LenientWarning>, PhantomData<N>);
+
+        impl<'de, N: Network> Visitor<'de> for SeqVisitor<'_, N> {
+            type Value = Vec<N>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("list of IP networks, addresses or address ranges")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                super::visit_seq(seq, self.0)
+            }
+        }
+
+        deserializer.deserialize_seq(SeqVisitor(warnings, PhantomData))
+    }
+}
+
+fn visit_seq<'de, N: Network, A: SeqAccess<'de>>(
+    mut seq: A,
+    warnings: &mut Vec<LenientWarning>,
+) -> Result<Vec<N>, A::Error> {
+    struct Item(String);
+
+    impl<'de> serde::Deserialize<'de> for Item {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct ItemVisitor;
+
+            impl<'de> Visitor<'de> for ItemVisitor {
+                type Value = Item;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("IP network, address or address range")
+                }
+
+                fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+                    Ok(Item(s.to_owned()))
+                }
+            }
+
+            deserializer.deserialize_str(ItemVisitor)
+        }
+    }
+
+    let mut networks = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+    while let Some(Item(input)) = seq.next_element()? {
+        push_entry(&mut networks, &input, warnings)?;
+    }
+    Ok(N::collapse(&networks))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::value::{self, StrDeserializer};
+    use serde::de::IntoDeserializer;
+    use serde::Deserialize;
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, Token};
+    use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+    use super::{Lenient, LenientWarning};
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Single(#[serde(with = "super")] Ipv4Network);
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Optional(#[serde(with = "super::option")] Option<Ipv6Network>);
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct List(#[serde(with = "super::vec")] Vec<IpNetwork>);
+
+    fn single(input: &'static str) -> [Token; 2] {
+        [Token::NewtypeStruct { name: "Single" }, Token::Str(input)]
+    }
+
+    #[test]
+    fn single_network() {
+        let expected = Single("10.0.0.0/24".parse().unwrap());
+        assert_de_tokens(&expected, &single("10.0.0.0/24"));
+        assert_de_tokens(&expected, &single(" 10.0.0.0 - 10.0.0.255 "));
+        assert_de_tokens(&Single("10.0.0.1/32".parse().unwrap()), &single("10.0.0.1"));
+        assert_de_tokens(&expected, &single("10.0.0.1/24"));
+        assert_eq!(
+            "network `10.0.0.1/24` has host bits set, truncated to `10.0.0.0/24`",
+            LenientWarning::HostBitsTruncated {
+                input: "10.0.0.1/24".to_owned(),
+                truncated: "10.0.0.0/24".parse().unwrap(),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn lenient_warnings() {
+        let input: StrDeserializer<value::Error> = "10.0.0.1/24".into_deserializer();
+        assert_eq!(
+            Lenient {
+                value: "10.0.0.0/24".parse::<Ipv4Network>().unwrap(),
+                warnings: vec![LenientWarning::HostBitsTruncated {
+                    input: "10.0.0.1/24".to_owned(),
+                    truncated: "10.0.0.0/24".parse().unwrap(),
+                }],
+            },
+            Lenient::deserialize(input).unwrap()
+        );
+
+        let input: StrDeserializer<value::Error> = "10.0.0.1".into_deserializer();
+        let network: Lenient<Ipv4Network> = Lenient::deserialize(input).unwrap();
+        assert!(network.warnings.is_empty());
+
+        let network: Lenient<Option<Ipv6Network>> = Lenient {
+            value: Some("2001:db8::/32".parse().unwrap()),
+            warnings: vec![],
+        };
+        assert_de_tokens(&network, &[Token::Some, Token::Str("2001:db8::/32")]);
+        assert_ser_tokens(&network, &[Token::Str("2001:db8::/32")]);
+    }
+
+    #[test]
+    fn single_network_errors() {
+        assert_de_tokens_error::<Single>(
+            &single("10.0.0.0-10.0.0.2"),
+            "invalid value: string \"10.0.0.0-10.0.0.2\", expected range of all addresses of a single network",
+        );
+        assert_de_tokens_error::<Single>(
+            &single("10.0.0.2-10.0.0.1"),
+            "invalid value: string \"10.0.0.2-10.0.0.1\", expected valid address range",
+        );
+        assert_de_tokens_error::<Single>(&single("::1"), "expected IPv4 network");
+        assert_de_tokens_error::<Single>(
+            &single("10.0.0.0/33"),
+            "invalid netmask 33, maximum is 32",
+        );
+        assert_de_tokens_error::<Single>(&single("a"), "invalid IP address syntax");
+    }
+
+    #[test]
+    fn optional_network() {
+        assert_de_tokens(
+            &Optional(None),
+            &[Token::NewtypeStruct { name: "Optional" }, Token::None],
+        );
+        assert_de_tokens(
+            &Optional(Some("2001:db8::/32".parse().unwrap())),
+            &[
+                Token::NewtypeStruct { name: "Optional" },
+                Token::Some,
+                Token::Str("2001:db8:1::/32"),
+            ],
+        );
+    }
+
+    #[test]
+    fn list_is_collapsed() {
+        assert_de_tokens(
+            &List(vec![
+                "10.0.0.0/23".parse().unwrap(),
+                "10.0.2.1/32".parse().unwrap(),
+                "2001:db8::/127".parse().unwrap(),
+                "2001:db8::2/128".parse().unwrap(),
+            ]),
+            &[
+                Token::NewtypeStruct { name: "List" },
+                Token::Seq { len: Some(5) },
+                Token::Str("10.0.1.0/24"),
+                Token::Str("10.0.0.0-10.0.0.255"),
+                Token::Str("10.0.2.1"),
+                Token::Str("10.0.0.5/30"),
+                Token::Str("2001:db8::-2001:db8::2"),
+                Token::SeqEnd,
+            ],
+        );
+
+        let list: Lenient<Vec<Ipv4Network>> = serde_json::from_str(
+            r#"["10.0.1.0/24", "10.0.0.5/30", "10.0.0.1/24", "10.0.0.0-10.0.0.255"]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["10.0.0.0/23".parse::<Ipv4Network>().unwrap()],
+            list.value
+        );
+        assert_eq!(2, list.warnings.len());
+    }
+}
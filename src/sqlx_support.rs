@@ -0,0 +1,349 @@
+#[cfg(feature = "sqlx-postgres")]
+mod postgres {
+    use std::str::FromStr;
+    use sqlx::decode::Decode;
+    use sqlx::encode::{Encode, IsNull};
+    use sqlx::error::BoxDynError;
+    use sqlx::postgres::{
+        PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres,
+    };
+    use sqlx::{Type, ValueRef};
+    use crate::{
+        IpInterface, IpNetwork, IpNetworkParseError, Ipv4Interface, Ipv4Network, Ipv6Interface,
+        Ipv6Network,
+    };
+    use crate::postgres_common::{self, SqlType};
+
+    const CIDR: &str = "cidr";
+    const INET: &str = "inet";
+
+    fn sql_type(value: &PgValueRef<'_>) -> SqlType {
+        if *value.type_info() == PgTypeInfo::with_name(INET) {
+            SqlType::Inet
+        } else {
+            SqlType::Cidr
+        }
+    }
+
+    /// Parses value in text format. INET values are printed without netmask when it is a single host.
+    pub(super) fn parse_text<N>(s: &str) -> Result<N, BoxDynError>
+    where
+        N: FromStr<Err = IpNetworkParseError>,
+    {
+        if s.contains('/') {
+            Ok(s.parse()?)
+        } else if s.contains(':') {
+            Ok(format!("{}/{}", s, Ipv6Network::LENGTH).parse()?)
+        } else {
+            Ok(format!("{}/{}", s, Ipv4Network::LENGTH).parse()?)
+        }
+    }
+
+    macro_rules! pg_impl {
+        ($t:ty, $type_name:expr, $from_sql:expr) => {
+            /// Values are bound as parameters of PostgreSQL type named by `type_info` in binary
+            /// format and can be decoded from both `cidr` and `inet` columns.
+            impl Type<Postgres> for $t {
+                fn type_info() -> PgTypeInfo {
+                    PgTypeInfo::with_name($type_name)
+                }
+
+                fn compatible(ty: &PgTypeInfo) -> bool {
+                    *ty == PgTypeInfo::with_name(CIDR) || *ty == PgTypeInfo::with_name(INET)
+                }
+            }
+
+            impl PgHasArrayType for $t {
+                fn array_type_info() -> PgTypeInfo {
+                    PgTypeInfo::array_of($type_name)
+                }
+
+                fn array_compatible(ty: &PgTypeInfo) -> bool {
+                    *ty == PgTypeInfo::array_of(CIDR) || *ty == PgTypeInfo::array_of(INET)
+                }
+            }
+
+            impl Decode<'_, Postgres> for $t {
+                fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+                    match value.format() {
+                        PgValueFormat::Binary => {
+                            let from_sql = $from_sql;
+                            Ok(from_sql(value.as_bytes()?, sql_type(&value))?)
+                        }
+                        PgValueFormat::Text => parse_text(value.as_str()?),
+                    }
+                }
+            }
+        };
+    }
+
+    pg_impl!(Ipv4Network, CIDR, |raw, ty| {
+        postgres_common::from_sql_ipv4_network(raw, ty, false)
+    });
+    pg_impl!(Ipv6Network, CIDR, |raw, ty| {
+        postgres_common::from_sql_ipv6_network(raw, ty, false)
+    });
+    pg_impl!(IpNetwork, CIDR, |raw, ty| {
+        postgres_common::from_sql_ip_network(raw, ty, false)
+    });
+    pg_impl!(Ipv4Interface, INET, |raw, _| {
+        postgres_common::from_sql_ipv4_interface(raw)
+    });
+    pg_impl!(Ipv6Interface, INET, |raw, _| {
+        postgres_common::from_sql_ipv6_interface(raw)
+    });
+    pg_impl!(IpInterface, INET, |raw, _| {
+        postgres_common::from_sql_ip_interface(raw)
+    });
+
+    impl Encode<'_, Postgres> for Ipv4Network {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+            buf.extend_from_slice(&postgres_common::to_sql_ipv4_network(self, SqlType::Cidr));
+            Ok(IsNull::No)
+        }
+
+        fn size_hint(&self) -> usize {
+            8
+        }
+    }
+
+    impl Encode<'_, Postgres> for Ipv6Network {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+            buf.extend_from_slice(&postgres_common::to_sql_ipv6_network(self, SqlType::Cidr));
+            Ok(IsNull::No)
+        }
+
+        fn size_hint(&self) -> usize {
+            20
+        }
+    }
+
+    impl Encode<'_, Postgres> for IpNetwork {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+            match *self {
+                IpNetwork::V4(ref network) => Encode::<Postgres>::encode_by_ref(network, buf),
+                IpNetwork::V6(ref network) => Encode::<Postgres>::encode_by_ref(network, buf),
+            }
+        }
+
+        fn size_hint(&self) -> usize {
+            match *self {
+                IpNetwork::V4(ref network) => Encode::<Postgres>::size_hint(network),
+                IpNetwork::V6(ref network) => Encode::<Postgres>::size_hint(network),
+            }
+        }
+    }
+
+    impl Encode<'_, Postgres> for Ipv4Interface {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+            buf.extend_from_slice(&postgres_common::to_sql_ipv4_interface(self));
+            Ok(IsNull::No)
+        }
+
+        fn size_hint(&self) -> usize {
+            8
+        }
+    }
+
+    impl Encode<'_, Postgres> for Ipv6Interface {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+            buf.extend_from_slice(&postgres_common::to_sql_ipv6_interface(self));
+            Ok(IsNull::No)
+        }
+
+        fn size_hint(&self) -> usize {
+            20
+        }
+    }
+
+    impl Encode<'_, Postgres> for IpInterface {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+            match *self {
+                IpInterface::V4(ref interface) => Encode::<Postgres>::encode_by_ref(interface, buf),
+                IpInterface::V6(ref interface) => Encode::<Postgres>::encode_by_ref(interface, buf),
+            }
+        }
+
+        fn size_hint(&self) -> usize {
+            match *self {
+                IpInterface::V4(ref interface) => Encode::<Postgres>::size_hint(interface),
+                IpInterface::V6(ref interface) => Encode::<Postgres>::size_hint(interface),
+            }
+        }
+    }
+}
+
+/// MySQL and SQLite have no network types, so networks and interfaces are stored as text
+/// in CIDR notation, for example `192.168.1.0/24` or `192.168.1.10/24`.
+#[cfg(any(feature = "sqlx-mysql", feature = "sqlx-sqlite"))]
+mod text {
+    use sqlx::decode::Decode;
+    use sqlx::encode::{Encode, IsNull};
+    use sqlx::error::BoxDynError;
+    use sqlx::{Database, Type};
+    use crate::{IpInterface, IpNetwork, Ipv4Interface, Ipv4Network, Ipv6Interface, Ipv6Network};
+
+    macro_rules! text_impl {
+        ($t:ty, $db:ty) => {
+            /// Stored as text in CIDR notation, for example `192.168.1.0/24` for networks and
+            /// `192.168.1.10/24` for interfaces. Decoding accepts the same format.
+            impl Type<$db> for $t {
+                fn type_info() -> <$db as Database>::TypeInfo {
+                    <str as Type<$db>>::type_info()
+                }
+
+                fn compatible(ty: &<$db as Database>::TypeInfo) -> bool {
+                    <str as Type<$db>>::compatible(ty)
+                }
+            }
+
+            impl<'q> Encode<'q, $db> for $t {
+                fn encode_by_ref(
+                    &self,
+                    buf: &mut <$db as Database>::ArgumentBuffer<'q>,
+                ) -> Result<IsNull, BoxDynError> {
+                    <String as Encode<'q, $db>>::encode(self.to_string(), buf)
+                }
+            }
+
+            impl<'r> Decode<'r, $db> for $t {
+                fn decode(value: <$db as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+                    Ok(<&str as Decode<'r, $db>>::decode(value)?.parse()?)
+                }
+            }
+        };
+    }
+
+    #[cfg(feature = "sqlx-mysql")]
+    text_impl!(Ipv4Network, sqlx::mysql::MySql);
+    #[cfg(feature = "sqlx-mysql")]
+    text_impl!(Ipv6Network, sqlx::mysql::MySql);
+    #[cfg(feature = "sqlx-mysql")]
+    text_impl!(IpNetwork, sqlx::mysql::MySql);
+    #[cfg(feature = "sqlx-mysql")]
+    text_impl!(Ipv4Interface, sqlx::mysql::MySql);
+    #[cfg(feature = "sqlx-mysql")]
+    text_impl!(Ipv6Interface, sqlx::mysql::MySql);
+    #[cfg(feature = "sqlx-mysql")]
+    text_impl!(IpInterface, sqlx::mysql::MySql);
+
+    #[cfg(feature = "sqlx-sqlite")]
+    text_impl!(Ipv4Network, sqlx::sqlite::Sqlite);
+    #[cfg(feature = "sqlx-sqlite")]
+    text_impl!(Ipv6Network, sqlx::sqlite::Sqlite);
+    #[cfg(feature = "sqlx-sqlite")]
+    text_impl!(IpNetwork, sqlx::sqlite::Sqlite);
+    #[cfg(feature = "sqlx-sqlite")]
+    text_impl!(Ipv4Interface, sqlx::sqlite::Sqlite);
+    #[cfg(feature = "sqlx-sqlite")]
+    text_impl!(Ipv6Interface, sqlx::sqlite::Sqlite);
+    #[cfg(feature = "sqlx-sqlite")]
+    text_impl!(IpInterface, sqlx::sqlite::Sqlite);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use sqlx::encode::{Encode, IsNull};
+    use crate::{IpInterface, IpNetwork, Ipv4Interface, Ipv4Network, Ipv6Network};
+
+    fn return_test_ipv4_network() -> Ipv4Network {
+        Ipv4Network::new(Ipv4Addr::new(192, 168, 0, 0), 16).unwrap()
+    }
+
+    fn return_test_ipv6_network() -> Ipv6Network {
+        Ipv6Network::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32).unwrap()
+    }
+
+    #[cfg(feature = "sqlx-postgres")]
+    #[test]
+    fn postgres_encode() {
+        use sqlx::postgres::{PgArgumentBuffer, Postgres};
+
+        let mut buf = PgArgumentBuffer::default();
+        let ip_network = IpNetwork::V4(return_test_ipv4_network());
+        assert_eq!(8, Encode::<Postgres>::size_hint(&ip_network));
+        assert!(matches!(
+            Encode::<Postgres>::encode_by_ref(&ip_network, &mut buf),
+            Ok(IsNull::No)
+        ));
+        assert_eq!(&[2, 16, 1, 4, 192, 168, 0, 0], &buf[..]);
+
+        let mut buf = PgArgumentBuffer::default();
+        let ip_network = return_test_ipv6_network();
+        assert!(Encode::<Postgres>::encode_by_ref(&ip_network, &mut buf).is_ok());
+        assert_eq!(20, buf.len());
+        assert_eq!(&[3, 32, 1, 16, 0x20, 0x01, 0x0d, 0xb8], &buf[..8]);
+
+        let mut buf = PgArgumentBuffer::default();
+        let interface =
+            IpInterface::V4(Ipv4Interface::new(Ipv4Addr::new(192, 168, 1, 10), 24).unwrap());
+        assert!(Encode::<Postgres>::encode_by_ref(&interface, &mut buf).is_ok());
+        assert_eq!(&[2, 24, 0, 4, 192, 168, 1, 10], &buf[..]);
+    }
+
+    #[cfg(feature = "sqlx-postgres")]
+    #[test]
+    fn postgres_type() {
+        use sqlx::postgres::{PgHasArrayType, PgTypeInfo, Postgres};
+        use sqlx::{Type, TypeInfo};
+
+        let cidr = <IpNetwork as Type<Postgres>>::type_info();
+        let inet = <IpInterface as Type<Postgres>>::type_info();
+        assert_eq!("cidr", cidr.name());
+        assert_eq!("inet", inet.name());
+        assert_eq!(
+            "cidr[]",
+            <IpNetwork as PgHasArrayType>::array_type_info().name()
+        );
+        assert_eq!(
+            "inet[]",
+            <IpInterface as PgHasArrayType>::array_type_info().name()
+        );
+        assert!(<Ipv4Network as Type<Postgres>>::compatible(&inet));
+        assert!(<Ipv4Interface as Type<Postgres>>::compatible(&cidr));
+        assert!(!<Ipv6Network as Type<Postgres>>::compatible(
+            &PgTypeInfo::with_name("text")
+        ));
+    }
+
+    #[cfg(feature = "sqlx-postgres")]
+    #[test]
+    fn postgres_parse_text() {
+        let network: IpNetwork = super::postgres::parse_text("192.168.0.0/16").unwrap();
+        assert_eq!(IpNetwork::V4(return_test_ipv4_network()), network);
+        let network: Ipv4Network = super::postgres::parse_text("192.168.1.1").unwrap();
+        assert_eq!(32, network.netmask());
+        let network: IpNetwork = super::postgres::parse_text("2001:db8::1").unwrap();
+        assert_eq!(128, network.netmask());
+        assert!(super::postgres::parse_text::<IpNetwork>("192.168.1.1/16").is_err());
+        let interface: IpInterface = super::postgres::parse_text("192.168.1.1/16").unwrap();
+        assert_eq!(16, interface.netmask());
+    }
+
+    #[cfg(feature = "sqlx-sqlite")]
+    #[test]
+    fn sqlite_encode() {
+        use sqlx::sqlite::{Sqlite, SqliteArgumentValue};
+
+        let mut buf = Vec::new();
+        let ip_network = return_test_ipv6_network();
+        assert!(Encode::<Sqlite>::encode_by_ref(&ip_network, &mut buf).is_ok());
+        match &buf[..] {
+            [SqliteArgumentValue::Text(text)] => assert_eq!("2001:db8::/32", text.as_ref()),
+            _ => panic!("expected single text argument"),
+        }
+    }
+
+    #[cfg(feature = "sqlx-mysql")]
+    #[test]
+    fn mysql_encode() {
+        use sqlx::mysql::MySql;
+
+        let mut buf = Vec::new();
+        let ip_network = return_test_ipv4_network();
+        assert!(Encode::<MySql>::encode_by_ref(&ip_network, &mut buf).is_ok());
+        // Length-encoded string
+        assert_eq!(b"\x0e192.168.0.0/16", &buf[..]);
+    }
+}
@@ -0,0 +1,802 @@
+//! Binary file format for network → value tables, that can be queried in place, for example from
+//! memory mapped file.
+//!
+//! Table is written by [`write_table`] and read by [`PrefixTable`], that validates the file once
+//! and then performs longest prefix match lookups directly on bytes without deserialization.
+//! Values are fixed-size records, for variable size values store offsets into separate blob.
+//!
+//! # Format
+//!
+//! All integers are little-endian. File starts with 48 bytes long header:
+//!
+//! | Offset | Size | Field                                                       |
+//! |--------|------|-------------------------------------------------------------|
+//! | 0      | 8    | magic `IPNTABL\0`                                           |
+//! | 8      | 2    | format version, currently 1                                 |
+//! | 10     | 2    | reserved, zero                                              |
+//! | 12     | 4    | size of value record in bytes                               |
+//! | 16     | 4    | number of IPv4 networks                                     |
+//! | 20     | 4    | number of IPv6 networks                                     |
+//! | 24     | 4    | number of IPv4 ranges                                       |
+//! | 28     | 4    | number of IPv6 ranges                                       |
+//! | 32     | 4    | CRC-32 (IEEE) of the whole file with this field set to zero |
+//! | 36     | 4    | reserved, zero                                              |
+//! | 40     | 8    | file length in bytes                                        |
+//!
+//! Header is followed by these sections, every section is a sequence of records:
+//!
+//! 1. IPv4 networks sorted by address – address (4 bytes, big-endian octets), netmask (1 byte),
+//!    3 bytes padding,
+//! 2. IPv6 networks sorted by address – address (16 bytes, big-endian octets), netmask (1 byte),
+//!    7 bytes padding,
+//! 3. IPv4 ranges – first address (4 bytes, big-endian octets), index of the most specific network
+//!    containing range (4 bytes, `0xFFFFFFFF` when no network contains range),
+//! 4. IPv6 ranges – first address (16 bytes, big-endian octets), network index (4 bytes),
+//!    4 bytes padding,
+//! 5. values – one record for every IPv4 and then every IPv6 network.
+//!
+//! Ranges split address space to consecutive parts with the same longest match, so lookup is
+//! a binary search of range containing address.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::net::Ipv4Addr;
+//! use ip_network::IpNetwork;
+//! use ip_network::table_file::{write_table, PrefixTable};
+//!
+//! let networks = vec![
+//!     ("10.0.0.0/8".parse::<IpNetwork>()?, 1u32.to_le_bytes()),
+//!     ("10.1.0.0/16".parse::<IpNetwork>()?, 2u32.to_le_bytes()),
+//! ];
+//! let mut file = vec![];
+//! write_table(&mut file, 4, networks)?;
+//!
+//! let table = PrefixTable::new(&file)?;
+//! let (network, value) = table.longest_match(Ipv4Addr::new(10, 1, 2, 3)).unwrap();
+//! assert_eq!("10.1.0.0/16", network.to_string());
+//! assert_eq!(&2u32.to_le_bytes(), value);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+const MAGIC: &[u8; 8] = b"IPNTABL\0";
+/// Current format version.
+pub const VERSION: u16 = 1;
+
+const HEADER_LEN: usize = 48;
+const CHECKSUM_OFFSET: usize = 32;
+const IPV4_NETWORK_LEN: usize = 8;
+const IPV6_NETWORK_LEN: usize = 24;
+const IPV4_RANGE_LEN: usize = 8;
+const IPV6_RANGE_LEN: usize = 24;
+/// Range index, when no network contains range.
+const NO_NETWORK: u32 = u32::MAX;
+
+/// Errors when reading table file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum TableFileError {
+    /// File doesn't start with valid header.
+    InvalidHeader,
+    /// File has unsupported format version.
+    UnsupportedVersion(u16),
+    /// File length doesn't match length computed from header.
+    InvalidLength { expected: u64, actual: u64 },
+    /// Checksum of file doesn't match.
+    ChecksumMismatch { expected: u32, actual: u32 },
+    /// File contains invalid network or range.
+    InvalidData,
+}
+
+impl Error for TableFileError {}
+
+impl fmt::Display for TableFileError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TableFileError::InvalidHeader => write!(fmt, "table file has invalid header"),
+            TableFileError::UnsupportedVersion(version) => {
+                write!(fmt, "table file has unsupported version {}", version)
+            }
+            TableFileError::InvalidLength { expected, actual } => write!(
+                fmt,
+                "table file has length {}, but {} was expected",
+                actual, expected
+            ),
+            TableFileError::ChecksumMismatch { expected, actual } => write!(
+                fmt,
+                "table file has checksum {:08x}, but {:08x} was expected",
+                actual, expected
+            ),
+            TableFileError::InvalidData => write!(fmt, "table file contains invalid data"),
+        }
+    }
+}
+
+impl From<TableFileError> for io::Error {
+    fn from(error: TableFileError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+/// Writes table of networks with values to `writer`. Every value must be `record_size` bytes
+/// long. When the same network is present multiple times, the last value is used.
+pub fn write_table<W, I, N, R>(mut writer: W, record_size: u32, networks: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = (N, R)>,
+    N: Into<IpNetwork>,
+    R: AsRef<[u8]>,
+{
+    let mut ipv4 = BTreeMap::new();
+    let mut ipv6 = BTreeMap::new();
+    for (network, value) in networks {
+        if value.as_ref().len() != record_size as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "value has different size than record size",
+            ));
+        }
+        match network.into() {
+            IpNetwork::V4(network) => ipv4.insert(network, value),
+            IpNetwork::V6(network) => ipv6.insert(network, value),
+        };
+    }
+    let too_many = || io::Error::new(io::ErrorKind::InvalidInput, "too many networks");
+    if ipv4.len() >= NO_NETWORK as usize || ipv6.len() >= NO_NETWORK as usize {
+        return Err(too_many());
+    }
+
+    let ipv4_ranges = ranges(
+        ipv4.keys().map(|n| {
+            (
+                u32::from(n.network_address()).into(),
+                u32::from(n.broadcast_address()).into(),
+            )
+        }),
+        u32::MAX.into(),
+    );
+    let ipv6_ranges = ranges(
+        ipv6.keys().map(|n| {
+            (
+                u128::from(n.network_address()),
+                u128::from(n.last_address()),
+            )
+        }),
+        u128::MAX,
+    );
+
+    let mut output = vec![0; HEADER_LEN];
+    for network in ipv4.keys() {
+        output.extend_from_slice(&network.network_address().octets());
+        output.extend_from_slice(&[network.netmask(), 0, 0, 0]);
+    }
+    for network in ipv6.keys() {
+        output.extend_from_slice(&network.network_address().octets());
+        output.extend_from_slice(&[network.netmask(), 0, 0, 0, 0, 0, 0, 0]);
+    }
+    for (start, index) in &ipv4_ranges {
+        output.extend_from_slice(&(*start as u32).to_be_bytes());
+        output.extend_from_slice(&index.to_le_bytes());
+    }
+    for (start, index) in &ipv6_ranges {
+        output.extend_from_slice(&start.to_be_bytes());
+        output.extend_from_slice(&index.to_le_bytes());
+        output.extend_from_slice(&[0; 4]);
+    }
+    for value in ipv4.values().chain(ipv6.values()) {
+        output.extend_from_slice(value.as_ref());
+    }
+
+    let header = Header {
+        record_size,
+        ipv4_networks: ipv4.len() as u32,
+        ipv6_networks: ipv6.len() as u32,
+        ipv4_ranges: ipv4_ranges.len().try_into().map_err(|_| too_many())?,
+        ipv6_ranges: ipv6_ranges.len().try_into().map_err(|_| too_many())?,
+    };
+    header.write(&mut output);
+    let checksum = crc32(&output);
+    output[CHECKSUM_OFFSET..CHECKSUM_OFFSET + 4].copy_from_slice(&checksum.to_le_bytes());
+
+    writer.write_all(&output)
+}
+
+/// Splits address space to ranges with the same most specific network. Networks must be sorted
+/// by first address and then by netmask. Returns first address of range and network index.
+fn ranges<I: Iterator<Item = (u128, u128)>>(networks: I, max: u128) -> Vec<(u128, u32)> {
+    fn push(ranges: &mut Vec<(u128, u32)>, start: u128, index: u32) {
+        // More specific network starting at the same address replaces less specific one.
+        if ranges.last().map(|(s, _)| *s) == Some(start) {
+            ranges.pop();
+        }
+        if ranges.last().map(|(_, i)| *i) != Some(index) {
+            ranges.push((start, index));
+        }
+    }
+
+    let mut output = vec![];
+    // Networks containing currently processed address, the most specific is the last one.
+    let mut open: Vec<(u128, u32)> = vec![];
+    for (index, (first, last)) in networks.enumerate() {
+        if output.is_empty() {
+            push(&mut output, 0, NO_NETWORK);
+        }
+        while let Some((end, _)) = open.last().copied() {
+            if end >= first {
+                break;
+            }
+            open.pop();
+            let parent = open.last().map_or(NO_NETWORK, |(_, i)| *i);
+            push(&mut output, end + 1, parent);
+        }
+        push(&mut output, first, index as u32);
+        open.push((last, index as u32));
+    }
+    while let Some((end, _)) = open.pop() {
+        if end == max {
+            break;
+        }
+        let parent = open.last().map_or(NO_NETWORK, |(_, i)| *i);
+        push(&mut output, end + 1, parent);
+    }
+    output
+}
+
+struct Header {
+    record_size: u32,
+    ipv4_networks: u32,
+    ipv6_networks: u32,
+    ipv4_ranges: u32,
+    ipv6_ranges: u32,
+}
+
+impl Header {
+    fn write(&self, output: &mut [u8]) {
+        let length = output.len() as u64;
+        let header = &mut output[..HEADER_LEN];
+        header[0..8].copy_from_slice(MAGIC);
+        header[8..10].copy_from_slice(&VERSION.to_le_bytes());
+        header[12..16].copy_from_slice(&self.record_size.to_le_bytes());
+        header[16..20].copy_from_slice(&self.ipv4_networks.to_le_bytes());
+        header[20..24].copy_from_slice(&self.ipv6_networks.to_le_bytes());
+        header[24..28].copy_from_slice(&self.ipv4_ranges.to_le_bytes());
+        header[28..32].copy_from_slice(&self.ipv6_ranges.to_le_bytes());
+        header[40..48].copy_from_slice(&length.to_le_bytes());
+    }
+
+    fn read(input: &[u8]) -> Result<Self, TableFileError> {
+        if input.len() < HEADER_LEN || &input[0..8] != MAGIC {
+            return Err(TableFileError::InvalidHeader);
+        }
+        let version = u16::from_le_bytes([input[8], input[9]]);
+        if version != VERSION {
+            return Err(TableFileError::UnsupportedVersion(version));
+        }
+        Ok(Self {
+            record_size: read_u32_le(input, 12),
+            ipv4_networks: read_u32_le(input, 16),
+            ipv6_networks: read_u32_le(input, 20),
+            ipv4_ranges: read_u32_le(input, 24),
+            ipv6_ranges: read_u32_le(input, 28),
+        })
+    }
+
+    /// Returns file length computed from counts or `None` if it overflows.
+    fn length(&self) -> Option<u64> {
+        let networks = u64::from(self.ipv4_networks) + u64::from(self.ipv6_networks);
+        // Counts are 32-bit, so only record size multiplication and sums can overflow.
+        (HEADER_LEN as u64)
+            .checked_add(u64::from(self.ipv4_networks) * IPV4_NETWORK_LEN as u64)?
+            .checked_add(u64::from(self.ipv6_networks) * IPV6_NETWORK_LEN as u64)?
+            .checked_add(u64::from(self.ipv4_ranges) * IPV4_RANGE_LEN as u64)?
+            .checked_add(u64::from(self.ipv6_ranges) * IPV6_RANGE_LEN as u64)?
+            .checked_add(networks.checked_mul(u64::from(self.record_size))?)
+    }
+}
+
+fn read_u32_le(input: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(input[offset..offset + 4].try_into().unwrap())
+}
+
+/// Table of networks with values, that is read directly from bytes of table file.
+#[derive(Clone, Copy, Debug)]
+pub struct PrefixTable<'a> {
+    record_size: usize,
+    ipv4_networks: &'a [u8],
+    ipv6_networks: &'a [u8],
+    ipv4_ranges: &'a [u8],
+    ipv6_ranges: &'a [u8],
+    values: &'a [u8],
+}
+
+impl<'a> PrefixTable<'a> {
+    /// Validates table file and creates table from its bytes. Corrupted input is rejected with
+    /// error, so lookups in returned table never panic.
+    pub fn new(input: &'a [u8]) -> Result<Self, TableFileError> {
+        let header = Header::read(input)?;
+        let expected = header.length().ok_or(TableFileError::InvalidHeader)?;
+        let stored = u64::from_le_bytes(input[40..48].try_into().unwrap());
+        if expected != input.len() as u64 || stored != expected {
+            return Err(TableFileError::InvalidLength {
+                expected,
+                actual: input.len() as u64,
+            });
+        }
+
+        let expected = read_u32_le(input, CHECKSUM_OFFSET);
+        let actual = checksum(input);
+        if expected != actual {
+            return Err(TableFileError::ChecksumMismatch { expected, actual });
+        }
+
+        let table = Self::split(input, &header);
+        table.validate()?;
+        Ok(table)
+    }
+
+    fn split(input: &'a [u8], header: &Header) -> Self {
+        let (_, rest) = input.split_at(HEADER_LEN);
+        let (ipv4_networks, rest) = rest.split_at(header.ipv4_networks as usize * IPV4_NETWORK_LEN);
+        let (ipv6_networks, rest) = rest.split_at(header.ipv6_networks as usize * IPV6_NETWORK_LEN);
+        let (ipv4_ranges, rest) = rest.split_at(header.ipv4_ranges as usize * IPV4_RANGE_LEN);
+        let (ipv6_ranges, values) = rest.split_at(header.ipv6_ranges as usize * IPV6_RANGE_LEN);
+        Self {
+            record_size: header.record_size as usize,
+            ipv4_networks,
+            ipv6_networks,
+            ipv4_ranges,
+            ipv6_ranges,
+            values,
+        }
+    }
+
+    fn validate(&self) -> Result<(), TableFileError> {
+        let ipv4_count = self.ipv4_networks.len() / IPV4_NETWORK_LEN;
+        let ipv6_count = self.ipv6_networks.len() / IPV6_NETWORK_LEN;
+        for i in 0..ipv4_count {
+            self.ipv4_network(i).ok_or(TableFileError::InvalidData)?;
+        }
+        for i in 0..ipv6_count {
+            self.ipv6_network(i).ok_or(TableFileError::InvalidData)?;
+        }
+
+        let ipv4_ranges = (0..self.ipv4_ranges.len() / IPV4_RANGE_LEN).map(|i| {
+            let (start, index) = self.ipv4_range(i);
+            (u128::from(u32::from(start)), index)
+        });
+        validate_ranges(ipv4_ranges, ipv4_count)?;
+        let ipv6_ranges = (0..self.ipv6_ranges.len() / IPV6_RANGE_LEN).map(|i| self.ipv6_range(i));
+        validate_ranges(ipv6_ranges, ipv6_count)
+    }
+
+    /// Returns size of value record in bytes.
+    pub fn record_size(&self) -> usize {
+        self.record_size
+    }
+
+    /// Returns number of networks in table.
+    pub fn len(&self) -> usize {
+        self.ipv4_networks.len() / IPV4_NETWORK_LEN + self.ipv6_networks.len() / IPV6_NETWORK_LEN
+    }
+
+    /// Returns `true` if table contains no networks.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the most specific network containing given address together with its value.
+    pub fn longest_match<A: Into<IpAddr>>(&self, address: A) -> Option<(IpNetwork, &'a [u8])> {
+        match address.into() {
+            IpAddr::V4(address) => self
+                .longest_match_ipv4(address)
+                .map(|(network, value)| (IpNetwork::V4(network), value)),
+            IpAddr::V6(address) => self
+                .longest_match_ipv6(address)
+                .map(|(network, value)| (IpNetwork::V6(network), value)),
+        }
+    }
+
+    /// Returns the most specific IPv4 network containing given address together with its value.
+    pub fn longest_match_ipv4(&self, address: Ipv4Addr) -> Option<(Ipv4Network, &'a [u8])> {
+        let count = self.ipv4_ranges.len() / IPV4_RANGE_LEN;
+        let position = partition_point(count, |i| self.ipv4_range(i).0 <= address);
+        let (_, index) = self.ipv4_range(position.checked_sub(1)?);
+        if index == NO_NETWORK {
+            return None;
+        }
+        let index = index as usize;
+        Some((self.ipv4_network(index)?, self.value(index)))
+    }
+
+    /// Returns the most specific IPv6 network containing given address together with its value.
+    pub fn longest_match_ipv6(&self, address: Ipv6Addr) -> Option<(Ipv6Network, &'a [u8])> {
+        let address = u128::from(address);
+        let count = self.ipv6_ranges.len() / IPV6_RANGE_LEN;
+        let position = partition_point(count, |i| self.ipv6_range(i).0 <= address);
+        let (_, index) = self.ipv6_range(position.checked_sub(1)?);
+        if index == NO_NETWORK {
+            return None;
+        }
+        let index = index as usize;
+        let ipv4_count = self.ipv4_networks.len() / IPV4_NETWORK_LEN;
+        Some((self.ipv6_network(index)?, self.value(ipv4_count + index)))
+    }
+
+    /// Returns iterator over all networks with values, IPv4 networks are first.
+    pub fn iter(&self) -> impl Iterator<Item = (IpNetwork, &'a [u8])> + '_ {
+        let ipv4_count = self.ipv4_networks.len() / IPV4_NETWORK_LEN;
+        (0..self.len()).filter_map(move |i| {
+            let network = if i < ipv4_count {
+                IpNetwork::V4(self.ipv4_network(i)?)
+            } else {
+                IpNetwork::V6(self.ipv6_network(i - ipv4_count)?)
+            };
+            Some((network, self.value(i)))
+        })
+    }
+
+    fn ipv4_network(&self, index: usize) -> Option<Ipv4Network> {
+        let record = &self.ipv4_networks[index * IPV4_NETWORK_LEN..][..IPV4_NETWORK_LEN];
+        let address: [u8; 4] = record[..4].try_into().unwrap();
+        Ipv4Network::new(Ipv4Addr::from(address), record[4]).ok()
+    }
+
+    fn ipv6_network(&self, index: usize) -> Option<Ipv6Network> {
+        let record = &self.ipv6_networks[index * IPV6_NETWORK_LEN..][..IPV6_NETWORK_LEN];
+        let address: [u8; 16] = record[..16].try_into().unwrap();
+        Ipv6Network::new(Ipv6Addr::from(address), record[16]).ok()
+    }
+
+    fn ipv4_range(&self, index: usize) -> (Ipv4Addr, u32) {
+        let record = &self.ipv4_ranges[index * IPV4_RANGE_LEN..][..IPV4_RANGE_LEN];
+        let start: [u8; 4] = record[..4].try_into().unwrap();
+        (Ipv4Addr::from(start), read_u32_le(record, 4))
+    }
+
+    fn ipv6_range(&self, index: usize) -> (u128, u32) {
+        let record = &self.ipv6_ranges[index * IPV6_RANGE_LEN..][..IPV6_RANGE_LEN];
+        let start: [u8; 16] = record[..16].try_into().unwrap();
+        (u128::from_be_bytes(start), read_u32_le(record, 16))
+    }
+
+    fn value(&self, index: usize) -> &'a [u8] {
+        &self.values[index * self.record_size..][..self.record_size]
+    }
+}
+
+/// Checks that ranges start at zero, are sorted and point to existing networks.
+fn validate_ranges<I>(ranges: I, networks: usize) -> Result<(), TableFileError>
+where
+    I: Iterator<Item = (u128, u32)>,
+{
+    let mut previous = None;
+    for (start, index) in ranges {
+        let valid_start = match previous {
+            None => start == 0,
+            Some(previous) => start > previous,
+        };
+        if !valid_start || (index != NO_NETWORK && index as usize >= networks) {
+            return Err(TableFileError::InvalidData);
+        }
+        previous = Some(start);
+    }
+    Ok(())
+}
+
+/// Returns number of leading items, for which predicate is true.
+fn partition_point<F: Fn(usize) -> bool>(count: usize, predicate: F) -> usize {
+    let (mut low, mut high) = (0, count);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if predicate(middle) {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    low
+}
+
+/// Returns checksum of table file, where checksum field is considered as zero.
+fn checksum(input: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(&input[..CHECKSUM_OFFSET]);
+    crc.update(&[0; 4]);
+    crc.update(&input[CHECKSUM_OFFSET + 4..]);
+    crc.finish()
+}
+
+fn crc32(input: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(input);
+    crc.finish()
+}
+
+/// CRC-32 with IEEE polynomial.
+struct Crc32(u32);
+
+impl Crc32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xedb8_8320
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    fn new() -> Self {
+        Crc32(u32::MAX)
+    }
+
+    fn update(&mut self, input: &[u8]) {
+        for byte in input {
+            self.0 = Self::TABLE[((self.0 ^ u32::from(*byte)) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+/// Table file mapped to memory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::net::Ipv4Addr;
+/// use ip_network::table_file::MmapPrefixTable;
+///
+/// let table = MmapPrefixTable::open("/var/lib/routes.table")?;
+/// if let Some((network, value)) = table.table().longest_match(Ipv4Addr::new(10, 0, 0, 1)) {
+///     println!("{} {:?}", network, value);
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(feature = "memmap2")]
+pub struct MmapPrefixTable {
+    mmap: memmap2::Mmap,
+}
+
+#[cfg(feature = "memmap2")]
+impl MmapPrefixTable {
+    /// Maps file to memory and validates it. File must not be modified while it is mapped.
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        // Safety: file is mapped read only and it must not be modified, that is documented.
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        PrefixTable::new(&mmap)?;
+        Ok(Self { mmap })
+    }
+
+    /// Returns table for lookups.
+    pub fn table(&self) -> PrefixTable<'_> {
+        let header = Header::read(&self.mmap).unwrap();
+        PrefixTable::split(&self.mmap, &header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::str::FromStr;
+    use super::{crc32, write_table, PrefixTable, TableFileError};
+    use crate::lpm::LpmTable;
+    use crate::xorshift::Random;
+    use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+    fn table_file(networks: &[(IpNetwork, u32)]) -> Vec<u8> {
+        let mut output = vec![];
+        write_table(
+            &mut output,
+            4,
+            networks.iter().map(|(n, v)| (*n, v.to_le_bytes())),
+        )
+        .unwrap();
+        output
+    }
+
+    #[test]
+    fn crc() {
+        assert_eq!(0xcbf4_3926, crc32(b"123456789"));
+    }
+
+    #[test]
+    fn lookup() {
+        let networks = [
+            (IpNetwork::from_str("0.0.0.0/0").unwrap(), 0),
+            (IpNetwork::from_str("10.0.0.0/8").unwrap(), 1),
+            (IpNetwork::from_str("10.1.0.0/16").unwrap(), 2),
+            (IpNetwork::from_str("10.255.255.255/32").unwrap(), 3),
+            (IpNetwork::from_str("255.255.255.0/24").unwrap(), 4),
+            (IpNetwork::from_str("2001:db8::/32").unwrap(), 5),
+            (IpNetwork::from_str("2001:db8::/48").unwrap(), 6),
+            (IpNetwork::from_str("ffff::/16").unwrap(), 7),
+        ];
+        let file = table_file(&networks);
+        let table = PrefixTable::new(&file).unwrap();
+        assert_eq!(8, table.len());
+        assert_eq!(4, table.record_size());
+
+        let lookup = |address: &str| {
+            let address: IpAddr = address.parse().unwrap();
+            table
+                .longest_match(address)
+                .map(|(n, v)| (n.to_string(), v.to_vec()))
+        };
+        let result =
+            |network: &str, value: u32| Some((network.to_owned(), value.to_le_bytes().to_vec()));
+        assert_eq!(result("0.0.0.0/0", 0), lookup("9.255.255.255"));
+        assert_eq!(result("10.0.0.0/8", 1), lookup("10.0.0.0"));
+        assert_eq!(result("10.1.0.0/16", 2), lookup("10.1.255.255"));
+        assert_eq!(result("10.0.0.0/8", 1), lookup("10.2.0.0"));
+        assert_eq!(result("10.255.255.255/32", 3), lookup("10.255.255.255"));
+        assert_eq!(result("0.0.0.0/0", 0), lookup("11.0.0.0"));
+        assert_eq!(result("255.255.255.0/24", 4), lookup("255.255.255.255"));
+        assert_eq!(None, lookup("::1"));
+        assert_eq!(result("2001:db8::/48", 6), lookup("2001:db8::1"));
+        assert_eq!(result("2001:db8::/32", 5), lookup("2001:db8:1::1"));
+        assert_eq!(None, lookup("2001:db9::"));
+        assert_eq!(
+            result("ffff::/16", 7),
+            lookup("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff")
+        );
+
+        let all: Vec<_> = table.iter().map(|(n, _)| n).collect();
+        assert_eq!(networks.iter().map(|(n, _)| *n).collect::<Vec<_>>(), all);
+    }
+
+    #[test]
+    fn empty() {
+        let file = table_file(&[]);
+        let table = PrefixTable::new(&file).unwrap();
+        assert!(table.is_empty());
+        assert_eq!(None, table.longest_match(Ipv4Addr::LOCALHOST));
+        assert_eq!(None, table.longest_match(Ipv6Addr::LOCALHOST));
+    }
+
+    #[test]
+    fn same_as_lpm_table() {
+        let mut random = Random(0x1234_5678);
+        let mut networks = vec![];
+        for i in 0..500 {
+            let address = Ipv4Addr::from(random.next() as u32 & 0x0f0f_ffff);
+            let netmask = (random.next() % 33) as u8;
+            networks.push((
+                Ipv4Network::new_truncate(address, netmask).unwrap().into(),
+                i,
+            ));
+            let address = Ipv6Addr::from(u128::from(random.next()) << 64 & !(0xfff0 << 112));
+            let netmask = (random.next() % 65) as u8;
+            networks.push((
+                Ipv6Network::new_truncate(address, netmask).unwrap().into(),
+                i,
+            ));
+        }
+        let file = table_file(&networks);
+        let table = PrefixTable::new(&file).unwrap();
+        let lpm: LpmTable<u32> = networks.iter().cloned().collect();
+
+        for _ in 0..5000 {
+            let addresses = [
+                IpAddr::V4(Ipv4Addr::from(random.next() as u32 & 0x0f0f_ffff)),
+                IpAddr::V6(Ipv6Addr::from(
+                    u128::from(random.next()) << 64 & !(0xfff0 << 112),
+                )),
+            ];
+            for address in &addresses {
+                let expected = lpm
+                    .longest_match(*address)
+                    .map(|(n, v)| (n, v.to_le_bytes().to_vec()));
+                let actual = table.longest_match(*address).map(|(n, v)| (n, v.to_vec()));
+                assert_eq!(expected, actual);
+            }
+        }
+    }
+
+    #[test]
+    fn corrupted() {
+        let file = table_file(&[
+            (IpNetwork::from_str("10.0.0.0/8").unwrap(), 1),
+            (IpNetwork::from_str("::/0").unwrap(), 2),
+        ]);
+
+        assert_eq!(
+            Err(TableFileError::InvalidHeader),
+            PrefixTable::new(&file[..10]).map(|_| ())
+        );
+        assert!(matches!(
+            PrefixTable::new(&file[..file.len() - 1]),
+            Err(TableFileError::InvalidLength { .. })
+        ));
+
+        let mut changed = file.clone();
+        changed[8] = 2;
+        assert_eq!(
+            Err(TableFileError::UnsupportedVersion(2)),
+            PrefixTable::new(&changed).map(|_| ())
+        );
+
+        // Every single changed byte after version is detected.
+        for i in 10..file.len() {
+            let mut changed = file.clone();
+            changed[i] ^= 0x10;
+            assert!(PrefixTable::new(&changed).is_err(), "byte {}", i);
+        }
+
+        let mut changed = file.clone();
+        changed[16] = 0xff;
+        assert!(matches!(
+            PrefixTable::new(&changed),
+            Err(TableFileError::InvalidLength { .. })
+        ));
+
+        // Header with maximal counts, that overflows computed length.
+        let mut header = file[..48].to_vec();
+        for byte in &mut header[12..32] {
+            *byte = 0xff;
+        }
+        assert_eq!(
+            Err(TableFileError::InvalidHeader),
+            PrefixTable::new(&header).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn invalid_value_size() {
+        let mut output = vec![];
+        let result = write_table(
+            &mut output,
+            4,
+            vec![(IpNetwork::from_str("10.0.0.0/8").unwrap(), [0; 3])],
+        );
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "memmap2")]
+    #[test]
+    fn mmap() {
+        use super::MmapPrefixTable;
+
+        let path = std::env::temp_dir().join(format!("ip_network_{}.table", std::process::id()));
+        std::fs::write(
+            &path,
+            table_file(&[(IpNetwork::from_str("10.0.0.0/8").unwrap(), 1)]),
+        )
+        .unwrap();
+        let table = MmapPrefixTable::open(&path).unwrap();
+        assert_eq!(
+            Some(&1u32.to_le_bytes()[..]),
+            table
+                .table()
+                .longest_match(Ipv4Addr::new(10, 1, 1, 1))
+                .map(|(_, v)| v)
+        );
+        drop(table);
+
+        std::fs::write(&path, b"invalid").unwrap();
+        let error = MmapPrefixTable::open(&path).err().unwrap();
+        assert_eq!(std::io::ErrorKind::InvalidData, error.kind());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,74 @@
+use std::borrow::Cow;
+use utoipa::openapi::schema::{ObjectBuilder, Schema, SchemaFormat, Type};
+use utoipa::openapi::RefOr;
+use utoipa::{PartialSchema, ToSchema};
+use crate::schema_common::*;
+use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+macro_rules! to_schema_impl {
+    ($t:ident, $format:expr, $pattern:expr, $description:expr, $examples:expr) => {
+        impl PartialSchema for $t {
+            fn schema() -> RefOr<Schema> {
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .format(Some(SchemaFormat::Custom($format.to_owned())))
+                    .pattern(Some($pattern))
+                    .description(Some($description))
+                    .examples($examples)
+                    .into()
+            }
+        }
+
+        impl ToSchema for $t {
+            fn name() -> Cow<'static, str> {
+                stringify!($t).into()
+            }
+        }
+    };
+}
+
+to_schema_impl!(
+    Ipv4Network,
+    IPV4_NETWORK_FORMAT,
+    IPV4_NETWORK_PATTERN,
+    IPV4_NETWORK_DESCRIPTION,
+    [IPV4_NETWORK_EXAMPLE]
+);
+to_schema_impl!(
+    Ipv6Network,
+    IPV6_NETWORK_FORMAT,
+    IPV6_NETWORK_PATTERN,
+    IPV6_NETWORK_DESCRIPTION,
+    [IPV6_NETWORK_EXAMPLE]
+);
+to_schema_impl!(
+    IpNetwork,
+    IP_NETWORK_FORMAT,
+    IP_NETWORK_PATTERN,
+    IP_NETWORK_DESCRIPTION,
+    [IPV4_NETWORK_EXAMPLE, IPV6_NETWORK_EXAMPLE]
+);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use utoipa::{PartialSchema, ToSchema};
+    use crate::{IpNetwork, Ipv4Network, Ipv6Network};
+
+    #[test]
+    fn schemas() {
+        let schema = serde_json::to_value(Ipv4Network::schema()).unwrap();
+        assert_eq!(json!("string"), schema["type"]);
+        assert_eq!(json!("ipv4-network"), schema["format"]);
+        assert_eq!(json!(["192.168.1.0/24"]), schema["examples"]);
+
+        let schema = serde_json::to_value(IpNetwork::schema()).unwrap();
+        assert_eq!(json!("ip-network"), schema["format"]);
+        assert_eq!(
+            json!(["192.168.1.0/24", "2001:db8::/32"]),
+            schema["examples"]
+        );
+
+        assert_eq!("Ipv6Network", Ipv6Network::name());
+    }
+}